@@ -0,0 +1,275 @@
+use core::fmt::{Debug, Display, Formatter};
+use core::ops::{Add, Mul, Neg, Sub};
+use ff::helpers::{sqrt_ratio_generic, sqrt_tonelli_shanks};
+use ff::{Field, PrimeField};
+use goldilocks::SmallField;
+use rand_core::RngCore;
+use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
+use subtle::{Choice, ConditionallySelectable, ConstantTimeEq, CtOption};
+
+/// BabyBear field with modulus 15 * 2^27 + 1.
+///
+/// The internal `u32` is the Montgomery form `x * 2^32 mod p` of the element
+/// `x`, always reduced to `[0, p)`, so that it is unique. Everything
+/// observable goes through the canonical value given by `to_canonical_u32`:
+/// `to_repr`, `is_odd`, `Debug`, `Display` and serialization.
+#[derive(Clone, Copy, Default, PartialEq, Eq)]
+#[repr(transparent)]
+pub struct BabyBear(pub(crate) u32);
+
+/// 15 * 2^27 + 1
+pub const MODULUS: u32 = 0x78000001;
+
+/// p^-1 mod 2^32
+const MONTY_MU: u32 = 0x88000001;
+
+/// 2^64 mod p, the Montgomery form of 2^32.
+const MONTY_R2: u32 = 0x45dddde3;
+
+/// p * 2^32, the bound below which `monty_reduce` is correct.
+const MONTY_BOUND: u64 = (MODULUS as u64) << 32;
+
+/// x * 2^-32 mod p, in `[0, p)`, for `x < p * 2^32`.
+#[inline]
+const fn monty_reduce(x: u64) -> u32 {
+    // x - t p is divisible by 2^32, and in (-p 2^32, p 2^32)
+    let t = (x as u32).wrapping_mul(MONTY_MU);
+    let (diff, under) = x.overflowing_sub(t as u64 * MODULUS as u64);
+    let res = (diff >> 32) as u32;
+    if under {
+        res.wrapping_add(MODULUS)
+    } else {
+        res
+    }
+}
+
+/// Reduce a sum of at most four products of Montgomery forms, which is below
+/// 4 p^2 < min(2^64, 2 p 2^32), to an element.
+#[inline]
+pub(crate) fn reduce_sum_of_products(x: u64) -> BabyBear {
+    let x = if x >= MONTY_BOUND { x - MONTY_BOUND } else { x };
+    BabyBear(monty_reduce(x))
+}
+
+impl BabyBear {
+    /// The element `value mod p`.
+    pub const fn new(value: u32) -> Self {
+        Self(monty_reduce((value % MODULUS) as u64 * MONTY_R2 as u64))
+    }
+
+    /// The canonical value of the element, in `[0, p)`.
+    #[inline]
+    pub const fn to_canonical_u32(&self) -> u32 {
+        monty_reduce(self.0 as u64)
+    }
+}
+
+/// The serialized form of an element: its canonical value, in a newtype
+/// struct like the one `#[derive(Serialize, Deserialize)]` on `BabyBear` would
+/// give.
+#[derive(Serialize, Deserialize)]
+#[serde(rename = "BabyBear")]
+struct Canonical(u32);
+
+/// Serializes the canonical form, so that an element serializes to the
+/// same bytes whatever the internal representation.
+impl Serialize for BabyBear {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        Canonical(self.to_canonical_u32()).serialize(serializer)
+    }
+}
+
+/// Rejects values that are not canonical, so that an element has a unique
+/// serialization.
+impl<'de> Deserialize<'de> for BabyBear {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let Canonical(value) = Canonical::deserialize(deserializer)?;
+        Option::from(Self::from_repr(value.to_le_bytes()))
+            .ok_or_else(|| de::Error::custom(format!("non-canonical BabyBear element {}", value)))
+    }
+}
+
+impl Debug for BabyBear {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        write!(f, "BabyBear({})", self.to_canonical_u32())
+    }
+}
+
+impl Display for BabyBear {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        write!(f, "{}", self.to_canonical_u32())
+    }
+}
+
+impl Field for BabyBear {
+    /// The zero element of the field, the additive identity.
+    const ZERO: Self = Self::new(0);
+
+    /// The one element of the field, the multiplicative identity.
+    const ONE: Self = Self::new(1);
+
+    /// Returns an element chosen uniformly at random using a user-provided RNG.
+    /// Note: this sampler is not constant time!
+    fn random(mut rng: impl RngCore) -> Self {
+        loop {
+            let value = rng.next_u32() >> 1;
+            if value < MODULUS {
+                return Self::new(value);
+            }
+        }
+    }
+
+    /// Squares this element.
+    #[must_use]
+    fn square(&self) -> Self {
+        *self * *self
+    }
+
+    /// Doubles this element.
+    #[must_use]
+    fn double(&self) -> Self {
+        *self + *self
+    }
+
+    /// Computes the multiplicative inverse of this element,
+    /// failing if the element is zero.
+    fn invert(&self) -> CtOption<Self> {
+        // a^(p - 2), with an exponent that does not depend on a
+        CtOption::new(self.pow_vartime([(MODULUS - 2) as u64]), !self.is_zero())
+    }
+
+    /// Returns the square root of the field element, if it is
+    /// quadratic residue.
+    fn sqrt(&self) -> CtOption<Self> {
+        // (t - 1) / 2 where p - 1 = 2^27 * 15
+        sqrt_tonelli_shanks(self, [7u64])
+    }
+
+    /// Computes the square root of `num / div`, see `Field::sqrt_ratio`.
+    fn sqrt_ratio(num: &Self, div: &Self) -> (Choice, Self) {
+        sqrt_ratio_generic(num, div)
+    }
+}
+
+/// This represents an element of a prime field.
+impl PrimeField for BabyBear {
+    /// The prime field can be converted back and forth into this binary
+    /// representation, the little-endian bytes of the canonical value.
+    type Repr = [u8; 4];
+
+    const MODULUS: &'static str = "0x78000001";
+
+    const NUM_BITS: u32 = 31;
+
+    const CAPACITY: u32 = 30;
+
+    /// p - 1 = 2^27 * 15
+    const S: u32 = 27;
+
+    const TWO_INV: Self = Self::new(0x3c000001);
+
+    /// The smallest generator of the multiplicative group.
+    const MULTIPLICATIVE_GENERATOR: Self = Self::new(31);
+
+    /// 31^15
+    const ROOT_OF_UNITY: Self = Self::new(440564289);
+
+    const ROOT_OF_UNITY_INV: Self = Self::new(1713844692);
+
+    /// 31^(2^27)
+    const DELTA: Self = Self::new(1995471372);
+
+    /// Fails if the input is not canonical (is not smaller than the field's
+    /// modulus).
+    fn from_repr(repr: Self::Repr) -> CtOption<Self> {
+        let value = u32::from_le_bytes(repr);
+        CtOption::new(Self::new(value), Choice::from((value < MODULUS) as u8))
+    }
+
+    fn to_repr(&self) -> Self::Repr {
+        self.to_canonical_u32().to_le_bytes()
+    }
+
+    fn is_odd(&self) -> Choice {
+        Choice::from((self.to_canonical_u32() & 1) as u8)
+    }
+}
+
+impl SmallField for BabyBear {
+    fn to_canonical_u64_vec(&self) -> Vec<u64> {
+        vec![self.to_canonical_u32() as u64]
+    }
+}
+
+/// Reduces the input modulo p.
+impl From<u64> for BabyBear {
+    fn from(input: u64) -> Self {
+        Self::new((input % MODULUS as u64) as u32)
+    }
+}
+
+/// The canonical value of the element.
+impl From<BabyBear> for u32 {
+    fn from(input: BabyBear) -> Self {
+        input.to_canonical_u32()
+    }
+}
+
+impl ConditionallySelectable for BabyBear {
+    fn conditional_select(a: &Self, b: &Self, choice: Choice) -> Self {
+        Self(u32::conditional_select(&a.0, &b.0, choice))
+    }
+}
+
+impl ConstantTimeEq for BabyBear {
+    fn ct_eq(&self, other: &Self) -> Choice {
+        self.0.ct_eq(&other.0)
+    }
+}
+
+impl Neg for BabyBear {
+    type Output = Self;
+
+    #[inline]
+    fn neg(self) -> Self {
+        Self::ZERO - self
+    }
+}
+
+impl Add for BabyBear {
+    type Output = Self;
+
+    /// The Montgomery form is additive.
+    #[inline]
+    fn add(self, rhs: Self) -> Self {
+        // 2p < 2^32
+        let sum = self.0 + rhs.0;
+        Self(if sum >= MODULUS { sum - MODULUS } else { sum })
+    }
+}
+
+impl Sub for BabyBear {
+    type Output = Self;
+
+    #[inline]
+    fn sub(self, rhs: Self) -> Self {
+        let (diff, under) = self.0.overflowing_sub(rhs.0);
+        Self(if under {
+            diff.wrapping_add(MODULUS)
+        } else {
+            diff
+        })
+    }
+}
+
+impl Mul for BabyBear {
+    type Output = Self;
+
+    /// (x 2^32) (y 2^32) 2^-32 = x y 2^32
+    #[inline]
+    fn mul(self, rhs: Self) -> Self {
+        Self(monty_reduce(self.0 as u64 * rhs.0 as u64))
+    }
+}
+
+goldilocks::impl_derived_ops!(BabyBear);
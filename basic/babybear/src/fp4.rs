@@ -0,0 +1,179 @@
+use crate::fp::{reduce_sum_of_products, BabyBear};
+use core::ops::{Add, Mul, Neg, Sub};
+use ff::Field;
+use goldilocks::extension::{sqrt_ratio, sqrt_tonelli_shanks};
+use goldilocks::ExtensionField;
+use rand_core::RngCore;
+use serde::{Deserialize, Serialize};
+use subtle::{Choice, CtOption};
+
+/// Quartic extension of the BabyBear field, GF(p)[x] / (x^4 - 11).
+/// An element `a_0 + a_1 x + a_2 x^2 + a_3 x^3` is stored as
+/// `[a_0, a_1, a_2, a_3]`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BabyBearExt4(pub(crate) [BabyBear; 4]);
+
+/// x^4 = W
+const W: BabyBear = BabyBear::new(11);
+
+/// x^(ip) = FROBENIUS[i] x^i, where FROBENIUS[i] = W^(i (p - 1) / 4)
+const FROBENIUS: [BabyBear; 4] = [
+    BabyBear::new(1),
+    BabyBear::new(1728404513),
+    BabyBear::new(2013265920),
+    BabyBear::new(284861408),
+];
+
+/// (t - 1) / 2 where p^4 - 1 = 2^29 * t
+const TM1D2: [u64; 2] = [0xa5e0000546000007, 0x31704001];
+
+/// A 2^29 root of unity, x^t.
+const ROOT_OF_UNITY: BabyBearExt4 = BabyBearExt4([
+    BabyBear::new(0),
+    BabyBear::new(0),
+    BabyBear::new(0),
+    BabyBear::new(1483681942),
+]);
+
+impl BabyBearExt4 {
+    pub const fn new(a0: BabyBear, a1: BabyBear, a2: BabyBear, a3: BabyBear) -> Self {
+        Self([a0, a1, a2, a3])
+    }
+}
+
+impl Field for BabyBearExt4 {
+    /// The zero element of the field, the additive identity.
+    const ZERO: Self = Self([BabyBear::ZERO; 4]);
+
+    /// The one element of the field, the multiplicative identity.
+    const ONE: Self = Self([
+        BabyBear::ONE,
+        BabyBear::ZERO,
+        BabyBear::ZERO,
+        BabyBear::ZERO,
+    ]);
+
+    /// Returns an element chosen uniformly at random using a user-provided RNG.
+    /// Note: this sampler is not constant time!
+    fn random(mut rng: impl RngCore) -> Self {
+        Self(core::array::from_fn(|_| BabyBear::random(&mut rng)))
+    }
+
+    /// Squares this element.
+    #[must_use]
+    fn square(&self) -> Self {
+        *self * *self
+    }
+
+    /// Doubles this element.
+    #[must_use]
+    fn double(&self) -> Self {
+        *self + *self
+    }
+
+    /// Computes the multiplicative inverse of this element,
+    /// failing if the element is zero.
+    fn invert(&self) -> CtOption<Self> {
+        // 1 / a = a^p a^(p^2) a^(p^3) / N(a), where N(a) is in the base field
+        let frobenius = self.frobenius();
+        let frobenius2 = frobenius.frobenius();
+        let conjugates = frobenius * frobenius2 * frobenius2.frobenius();
+        let norm = (*self * conjugates).0[0];
+        norm.invert().map(|norm_inv| conjugates * norm_inv)
+    }
+
+    /// Returns the square root of the field element, if it is
+    /// quadratic residue.
+    /// Note: this is not constant time!
+    fn sqrt(&self) -> CtOption<Self> {
+        sqrt_tonelli_shanks(self, &TM1D2, 29, ROOT_OF_UNITY)
+    }
+
+    /// Computes the square root of `num / div`, see `Field::sqrt_ratio`.
+    /// The non-square used when `num / div` is not a square is `x`, whose
+    /// norm -W is not a square.
+    fn sqrt_ratio(num: &Self, div: &Self) -> (Choice, Self) {
+        sqrt_ratio(
+            num,
+            div,
+            Self([
+                BabyBear::ZERO,
+                BabyBear::ONE,
+                BabyBear::ZERO,
+                BabyBear::ZERO,
+            ]),
+        )
+    }
+}
+
+impl ExtensionField for BabyBearExt4 {
+    const DEGREE: usize = 4;
+
+    type BaseField = BabyBear;
+
+    fn from_bases(bases: &[BabyBear]) -> Self {
+        assert!(bases.len() <= 4, "too many coefficients for BabyBearExt4");
+        let mut res = Self::ZERO;
+        res.0[..bases.len()].copy_from_slice(bases);
+        res
+    }
+
+    fn as_bases(&self) -> &[BabyBear] {
+        &self.0
+    }
+
+    /// `\sum_i a_i x^(ip)`, as the Frobenius map fixes the base field.
+    fn frobenius(&self) -> Self {
+        Self(core::array::from_fn(|i| self.0[i] * FROBENIUS[i]))
+    }
+}
+
+impl Neg for BabyBearExt4 {
+    type Output = Self;
+
+    #[inline]
+    fn neg(self) -> Self {
+        Self(self.0.map(|a| -a))
+    }
+}
+
+impl Add for BabyBearExt4 {
+    type Output = Self;
+
+    #[inline]
+    fn add(self, rhs: Self) -> Self::Output {
+        Self(core::array::from_fn(|i| self.0[i] + rhs.0[i]))
+    }
+}
+
+impl Sub for BabyBearExt4 {
+    type Output = Self;
+
+    #[inline]
+    fn sub(self, rhs: Self) -> Self {
+        Self(core::array::from_fn(|i| self.0[i] - rhs.0[i]))
+    }
+}
+
+impl Mul for BabyBearExt4 {
+    type Output = Self;
+
+    /// Schoolbook multiplication with lazy reduction: with the reduction
+    /// x^4 = W folded into `bw`, every coefficient is a sum of four products
+    /// of Montgomery forms, reduced once.
+    #[inline]
+    fn mul(self, rhs: Self) -> Self {
+        let [a0, a1, a2, a3] = self.0.map(|a| a.0 as u64);
+        let [b0, b1, b2, b3] = rhs.0.map(|b| b.0 as u64);
+        let [bw1, bw2, bw3] = [rhs.0[1], rhs.0[2], rhs.0[3]].map(|b| (b * W).0 as u64);
+
+        Self([
+            reduce_sum_of_products(a0 * b0 + a1 * bw3 + a2 * bw2 + a3 * bw1),
+            reduce_sum_of_products(a0 * b1 + a1 * b0 + a2 * bw3 + a3 * bw2),
+            reduce_sum_of_products(a0 * b2 + a1 * b1 + a2 * b0 + a3 * bw3),
+            reduce_sum_of_products(a0 * b3 + a1 * b2 + a2 * b1 + a3 * b0),
+        ])
+    }
+}
+
+goldilocks::impl_extension_ops!(BabyBearExt4, BabyBear);
@@ -0,0 +1,12 @@
+//! This crate implements the BabyBear field with modulus 15 * 2^27 + 1, in
+//! Montgomery form, and its quartic extension. The traits it implements,
+//! `SmallField` and `ExtensionField`, are those of the `goldilocks` crate.
+
+pub use fp::BabyBear;
+pub use fp4::BabyBearExt4;
+
+mod fp;
+mod fp4;
+
+#[cfg(test)]
+mod tests;
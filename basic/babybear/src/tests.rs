@@ -0,0 +1,106 @@
+use ff::{Field, PrimeField};
+use goldilocks::conformance::{extension_field_conformance_tests, small_field_conformance_tests};
+use goldilocks::ntt::{intt, low_degree_extension, ntt, root_of_unity};
+use goldilocks::ExtensionField;
+use rand_core::SeedableRng;
+use rand_xorshift::XorShiftRng;
+
+use crate::fp::MODULUS;
+use crate::{BabyBear, BabyBearExt4};
+
+fn test_rng() -> XorShiftRng {
+    XorShiftRng::from_seed([
+        0x59, 0x62, 0xbe, 0x5d, 0x76, 0x3d, 0x31, 0x8d, 0x17, 0xdb, 0x37, 0x32, 0x54, 0x06, 0xbc,
+        0xe5,
+    ])
+}
+
+#[test]
+fn test_conformance() {
+    small_field_conformance_tests::<BabyBear>(test_rng());
+    extension_field_conformance_tests::<BabyBearExt4>(test_rng());
+}
+
+#[test]
+fn test_montgomery_form() {
+    // the Montgomery form of x is x 2^32 mod p
+    for value in [0, 1, 2, 31, MODULUS - 1] {
+        let element = BabyBear::new(value);
+        assert_eq!(element.0 as u64, ((value as u64) << 32) % MODULUS as u64);
+        assert_eq!(element.to_canonical_u32(), value);
+        assert_eq!(u32::from(element), value);
+        assert_eq!(element, BabyBear::from(value as u64));
+    }
+    assert_eq!(BabyBear::new(MODULUS), BabyBear::ZERO);
+    assert_eq!(format!("{}", -BabyBear::ONE), "2013265920");
+    assert_eq!(format!("{:?}", BabyBear::new(7)), "BabyBear(7)");
+
+    // the largest sums of products reduced by the extension multiplication
+    let max = BabyBearExt4([-BabyBear::ONE; 4]);
+    // (-1 - x - x^2 - x^3)^2 = 1 + 2x + 3x^2 + 4x^3 + 3x^4 + 2x^5 + x^6
+    let expected = BabyBearExt4([1 + 3 * 11, 2 + 2 * 11, 3 + 11, 4].map(BabyBear::new));
+    assert_eq!(max * max, expected);
+}
+
+#[test]
+fn test_extension_reduction() {
+    // x^4 = 11
+    let x = BabyBearExt4::from_bases(&[BabyBear::ZERO, BabyBear::ONE]);
+    assert_eq!(x.square().square(), BabyBearExt4::from(BabyBear::new(11)));
+    assert_eq!(x.norm(), -BabyBear::new(11));
+}
+
+#[test]
+fn test_serdes() {
+    let mut rng = test_rng();
+    for _ in 0..1000 {
+        let a = BabyBearExt4::random(&mut rng);
+        let bytes = bincode::serialize(&a).unwrap();
+        assert_eq!(bytes.len(), 4 * BabyBearExt4::DEGREE);
+        assert_eq!(bincode::deserialize::<BabyBearExt4>(&bytes).unwrap(), a);
+    }
+    assert_eq!(
+        bincode::serialize(&BabyBear::new(5)).unwrap(),
+        5u32.to_le_bytes()
+    );
+    assert!(bincode::deserialize::<BabyBear>(&MODULUS.to_le_bytes()).is_err());
+}
+
+#[test]
+fn test_ntt() {
+    let mut rng = test_rng();
+    assert_eq!(
+        root_of_unity::<BabyBear>(1 << BabyBear::S),
+        BabyBear::ROOT_OF_UNITY
+    );
+    for log_n in [0, 1, 4, 10] {
+        let coefficients: Vec<_> = (0..1 << log_n)
+            .map(|_| BabyBear::random(&mut rng))
+            .collect();
+        let mut values = coefficients.clone();
+        ntt(&mut values);
+        let root = root_of_unity::<BabyBear>(1 << log_n);
+        for (i, value) in values.iter().enumerate().take(4) {
+            let x = root.pow_vartime([i as u64]);
+            let expected = coefficients
+                .iter()
+                .rev()
+                .fold(BabyBear::ZERO, |acc, c| acc * x + c);
+            assert_eq!(*value, expected);
+        }
+        intt(&mut values);
+        assert_eq!(values, coefficients);
+
+        // the extension agrees with the evaluations on the subgroup
+        let mut evaluations = coefficients.clone();
+        ntt(&mut evaluations);
+        let extended = low_degree_extension(&evaluations, 4);
+        let shift = BabyBear::MULTIPLICATIVE_GENERATOR;
+        let x = shift * root_of_unity::<BabyBear>(4 << log_n);
+        let expected = coefficients
+            .iter()
+            .rev()
+            .fold(BabyBear::ZERO, |acc, c| acc * x + c);
+        assert_eq!(extended[1], expected);
+    }
+}
@@ -0,0 +1,574 @@
+//! The arkworks field traits for the Goldilocks field, behind the `ark`
+//! feature.
+//!
+//! They are implemented on `ArkGoldilocks`, a `#[repr(transparent)]` wrapper
+//! of `Goldilocks`, rather than on `Goldilocks` itself: `ark_ff::Field`
+//! requires conversions from every integer type, which would make a literal
+//! in `Goldilocks::from(2)` ambiguous in every crate of a build that enables
+//! the feature. Converting between the two types is free, including for
+//! slices with `as_ark_slice` and `as_goldilocks_slice`, so that the output
+//! of e.g. `Poseidon2<Goldilocks>` can be handed to code generic over
+//! `ark_ff::PrimeField` without copying.
+//!
+//! `to_ark` and `from_ark` convert to and from any other arkworks field of
+//! the same modulus, e.g. one defined with `#[derive(MontConfig)]`.
+
+use core::borrow::Borrow;
+use core::cmp::Ordering;
+use core::fmt::{Debug, Display, Formatter};
+use core::hash::{Hash, Hasher};
+use core::iter::{Once, Product, Sum};
+use core::ops::{Add, AddAssign, Div, DivAssign, Mul, MulAssign, Neg, Sub, SubAssign};
+use core::str::FromStr;
+use std::io::{Read, Write};
+
+use ark_ff::{BigInt, FftField, LegendreSymbol, One, SqrtPrecomputation, Zero};
+use ark_serialize::{
+    buffer_byte_size, CanonicalDeserialize, CanonicalDeserializeWithFlags, CanonicalSerialize,
+    CanonicalSerializeWithFlags, Compress, EmptyFlags, Flags, SerializationError, Valid, Validate,
+};
+use ark_std::rand::{
+    distributions::{Distribution, Standard},
+    Rng,
+};
+use ff::{Field, PrimeField};
+use num_bigint::BigUint;
+use zeroize::Zeroize;
+
+use crate::fp::{Goldilocks, LegendreSymbol as GoldilocksLegendreSymbol, MODULUS};
+
+/// A Goldilocks element that implements the arkworks field traits.
+#[derive(Clone, Copy, Default, PartialEq, Eq)]
+#[repr(transparent)]
+pub struct ArkGoldilocks(pub Goldilocks);
+
+impl From<Goldilocks> for ArkGoldilocks {
+    fn from(input: Goldilocks) -> Self {
+        Self(input)
+    }
+}
+
+impl From<ArkGoldilocks> for Goldilocks {
+    fn from(input: ArkGoldilocks) -> Self {
+        input.0
+    }
+}
+
+/// View a slice of `Goldilocks` as a slice of `ArkGoldilocks`.
+pub fn as_ark_slice(slice: &[Goldilocks]) -> &[ArkGoldilocks] {
+    // SAFETY: `ArkGoldilocks` is a `#[repr(transparent)]` wrapper of
+    // `Goldilocks`.
+    unsafe { &*(slice as *const [Goldilocks] as *const [ArkGoldilocks]) }
+}
+
+/// View a mutable slice of `Goldilocks` as a slice of `ArkGoldilocks`.
+pub fn as_ark_slice_mut(slice: &mut [Goldilocks]) -> &mut [ArkGoldilocks] {
+    // SAFETY: as in `as_ark_slice`.
+    unsafe { &mut *(slice as *mut [Goldilocks] as *mut [ArkGoldilocks]) }
+}
+
+/// View a slice of `ArkGoldilocks` as a slice of `Goldilocks`.
+pub fn as_goldilocks_slice(slice: &[ArkGoldilocks]) -> &[Goldilocks] {
+    // SAFETY: as in `as_ark_slice`.
+    unsafe { &*(slice as *const [ArkGoldilocks] as *const [Goldilocks]) }
+}
+
+/// View a mutable slice of `ArkGoldilocks` as a slice of `Goldilocks`.
+pub fn as_goldilocks_slice_mut(slice: &mut [ArkGoldilocks]) -> &mut [Goldilocks] {
+    // SAFETY: as in `as_ark_slice`.
+    unsafe { &mut *(slice as *mut [ArkGoldilocks] as *mut [Goldilocks]) }
+}
+
+/// Convert an element to the arkworks field `F`, which must have the
+/// Goldilocks modulus.
+pub fn to_ark<F: ark_ff::PrimeField>(x: Goldilocks) -> F {
+    assert_goldilocks_modulus::<F>();
+    F::from(x.to_canonical_u64())
+}
+
+/// Convert an element of the arkworks field `F`, which must have the
+/// Goldilocks modulus.
+pub fn from_ark<F: ark_ff::PrimeField>(x: F) -> Goldilocks {
+    assert_goldilocks_modulus::<F>();
+    Goldilocks(x.into_bigint().as_ref()[0])
+}
+
+fn assert_goldilocks_modulus<F: ark_ff::PrimeField>() {
+    let modulus = F::MODULUS;
+    let limbs = modulus.as_ref();
+    assert!(
+        limbs[0] == MODULUS && limbs[1..].iter().all(|limb| *limb == 0),
+        "the modulus {} of the arkworks field is not the Goldilocks modulus",
+        modulus
+    );
+}
+
+impl ark_ff::Field for ArkGoldilocks {
+    type BasePrimeField = Self;
+
+    type BasePrimeFieldIter = Once<Self>;
+
+    /// (t - 1) / 2 where p - 1 = 2^32 * t, as in `ff::Field::sqrt`.
+    const SQRT_PRECOMP: Option<SqrtPrecomputation<Self>> =
+        Some(SqrtPrecomputation::TonelliShanks {
+            two_adicity: 32,
+            quadratic_nonresidue_to_trace: Self(Goldilocks::ROOT_OF_UNITY),
+            trace_of_modulus_minus_one_div_two: &[0x7fffffff],
+        });
+
+    const ZERO: Self = Self(Goldilocks::ZERO);
+
+    const ONE: Self = Self(Goldilocks::ONE);
+
+    /// The default defers to `BasePrimeField`, which is `Self` here.
+    fn characteristic() -> &'static [u64] {
+        &[MODULUS]
+    }
+
+    fn extension_degree() -> u64 {
+        1
+    }
+
+    fn to_base_prime_field_elements(&self) -> Self::BasePrimeFieldIter {
+        core::iter::once(*self)
+    }
+
+    fn from_base_prime_field_elems(elems: &[Self]) -> Option<Self> {
+        (elems.len() == 1).then(|| elems[0])
+    }
+
+    fn from_base_prime_field(elem: Self) -> Self {
+        elem
+    }
+
+    fn double(&self) -> Self {
+        Self(self.0.double())
+    }
+
+    fn double_in_place(&mut self) -> &mut Self {
+        self.0 = self.0.double();
+        self
+    }
+
+    fn neg_in_place(&mut self) -> &mut Self {
+        self.0 = -self.0;
+        self
+    }
+
+    /// Reads the canonical little-endian value, followed by the flags in the
+    /// top bits of an extra byte as there are no spare bits in a `u64`.
+    fn from_random_bytes_with_flags<F: Flags>(bytes: &[u8]) -> Option<(Self, F)> {
+        if F::BIT_SIZE > 8 {
+            return None;
+        }
+        let output_size = buffer_byte_size(64 + F::BIT_SIZE);
+        let mut buffer = [0u8; 9];
+        let len = bytes.len().min(output_size);
+        buffer[..len].copy_from_slice(&bytes[..len]);
+        let flags = F::from_u8_remove_flags(&mut buffer[output_size - 1])?;
+        Option::from(Goldilocks::from_repr(buffer[..8].try_into().unwrap()))
+            .map(|x| (Self(x), flags))
+    }
+
+    fn legendre(&self) -> LegendreSymbol {
+        match self.0.legendre() {
+            GoldilocksLegendreSymbol::Zero => LegendreSymbol::Zero,
+            GoldilocksLegendreSymbol::QuadraticResidue => LegendreSymbol::QuadraticResidue,
+            GoldilocksLegendreSymbol::QuadraticNonResidue => LegendreSymbol::QuadraticNonResidue,
+        }
+    }
+
+    fn sqrt(&self) -> Option<Self> {
+        Option::from(self.0.sqrt()).map(Self)
+    }
+
+    fn square(&self) -> Self {
+        Self(self.0.square())
+    }
+
+    fn square_in_place(&mut self) -> &mut Self {
+        self.0 = self.0.square();
+        self
+    }
+
+    fn inverse(&self) -> Option<Self> {
+        Option::from(self.0.invert()).map(Self)
+    }
+
+    fn inverse_in_place(&mut self) -> Option<&mut Self> {
+        self.0 = Option::from(self.0.invert())?;
+        Some(self)
+    }
+
+    /// The Frobenius map is the identity on a prime field.
+    fn frobenius_map_in_place(&mut self, _power: usize) {}
+}
+
+impl FftField for ArkGoldilocks {
+    const GENERATOR: Self = Self(Goldilocks::MULTIPLICATIVE_GENERATOR);
+
+    const TWO_ADICITY: u32 = Goldilocks::S;
+
+    const TWO_ADIC_ROOT_OF_UNITY: Self = Self(Goldilocks::ROOT_OF_UNITY);
+}
+
+impl ark_ff::PrimeField for ArkGoldilocks {
+    type BigInt = BigInt<1>;
+
+    const MODULUS: BigInt<1> = BigInt([MODULUS]);
+
+    const MODULUS_MINUS_ONE_DIV_TWO: BigInt<1> = BigInt([MODULUS >> 1]);
+
+    const MODULUS_BIT_SIZE: u32 = 64;
+
+    /// p - 1 = 2^32 * (2^32 - 1)
+    const TRACE: BigInt<1> = BigInt([0xffffffff]);
+
+    const TRACE_MINUS_ONE_DIV_TWO: BigInt<1> = BigInt([0x7fffffff]);
+
+    /// Fails if the input is not canonical.
+    fn from_bigint(repr: BigInt<1>) -> Option<Self> {
+        Option::from(Goldilocks::from_repr(repr.0[0].to_le_bytes())).map(Self)
+    }
+
+    fn into_bigint(self) -> BigInt<1> {
+        BigInt([self.0.to_canonical_u64()])
+    }
+}
+
+/// Reduces the input modulo p.
+impl From<BigInt<1>> for ArkGoldilocks {
+    fn from(input: BigInt<1>) -> Self {
+        Self::from(input.0[0])
+    }
+}
+
+/// The canonical value of the element.
+impl From<ArkGoldilocks> for BigInt<1> {
+    fn from(input: ArkGoldilocks) -> Self {
+        BigInt([input.0.to_canonical_u64()])
+    }
+}
+
+/// Reduces the input modulo p.
+impl From<BigUint> for ArkGoldilocks {
+    fn from(input: BigUint) -> Self {
+        let reduced = input % BigUint::from(MODULUS);
+        Self::from(reduced.to_u64_digits().first().copied().unwrap_or(0))
+    }
+}
+
+/// The canonical value of the element.
+impl From<ArkGoldilocks> for BigUint {
+    fn from(input: ArkGoldilocks) -> Self {
+        BigUint::from(input.0.to_canonical_u64())
+    }
+}
+
+/// Reduces the input modulo p.
+impl From<u128> for ArkGoldilocks {
+    fn from(input: u128) -> Self {
+        Self(Goldilocks((input % MODULUS as u128) as u64))
+    }
+}
+
+/// Reduces the input modulo p.
+impl From<u64> for ArkGoldilocks {
+    fn from(input: u64) -> Self {
+        Self(Goldilocks::from(input))
+    }
+}
+
+impl From<u32> for ArkGoldilocks {
+    fn from(input: u32) -> Self {
+        Self(Goldilocks(input as u64))
+    }
+}
+
+impl From<u16> for ArkGoldilocks {
+    fn from(input: u16) -> Self {
+        Self(Goldilocks(input as u64))
+    }
+}
+
+impl From<u8> for ArkGoldilocks {
+    fn from(input: u8) -> Self {
+        Self(Goldilocks(input as u64))
+    }
+}
+
+impl From<bool> for ArkGoldilocks {
+    fn from(input: bool) -> Self {
+        Self(Goldilocks(input as u64))
+    }
+}
+
+/// Parses a decimal integer, reduced modulo p.
+impl FromStr for ArkGoldilocks {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, ()> {
+        if s.is_empty() {
+            return Err(());
+        }
+        let ten = Goldilocks(10);
+        s.chars()
+            .try_fold(Goldilocks::ZERO, |acc, c| {
+                let digit = c.to_digit(10).ok_or(())?;
+                Ok(acc * ten + Goldilocks(digit as u64))
+            })
+            .map(Self)
+    }
+}
+
+impl Debug for ArkGoldilocks {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        Debug::fmt(&self.0, f)
+    }
+}
+
+impl Display for ArkGoldilocks {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        Display::fmt(&self.0, f)
+    }
+}
+
+/// Orders the canonical values, as arkworks does.
+impl Ord for ArkGoldilocks {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.0.to_canonical_u64().cmp(&other.0.to_canonical_u64())
+    }
+}
+
+impl PartialOrd for ArkGoldilocks {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+/// Hashes the canonical value, consistently with `PartialEq`.
+impl Hash for ArkGoldilocks {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.0.to_canonical_u64().hash(state);
+    }
+}
+
+impl Zeroize for ArkGoldilocks {
+    fn zeroize(&mut self) {
+        self.0 .0.zeroize();
+    }
+}
+
+impl Zero for ArkGoldilocks {
+    fn zero() -> Self {
+        Self(Goldilocks::ZERO)
+    }
+
+    fn is_zero(&self) -> bool {
+        self.0.is_zero_vartime()
+    }
+}
+
+impl One for ArkGoldilocks {
+    fn one() -> Self {
+        Self(Goldilocks::ONE)
+    }
+}
+
+/// Makes `ArkGoldilocks` implement `ark_std::UniformRand`.
+impl Distribution<ArkGoldilocks> for Standard {
+    fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> ArkGoldilocks {
+        ArkGoldilocks(Goldilocks::random(rng))
+    }
+}
+
+impl Valid for ArkGoldilocks {
+    /// Every element is valid: deserialization rejects non-canonical values.
+    fn check(&self) -> Result<(), SerializationError> {
+        Ok(())
+    }
+}
+
+/// The little-endian bytes of the canonical value, as `to_repr`, with the
+/// flags in an extra byte.
+impl CanonicalSerializeWithFlags for ArkGoldilocks {
+    fn serialize_with_flags<W: Write, F: Flags>(
+        &self,
+        mut writer: W,
+        flags: F,
+    ) -> Result<(), SerializationError> {
+        if F::BIT_SIZE > 8 {
+            return Err(SerializationError::NotEnoughSpace);
+        }
+        let output_size = buffer_byte_size(64 + F::BIT_SIZE);
+        let mut buffer = [0u8; 9];
+        buffer[..8].copy_from_slice(&self.0.to_repr());
+        buffer[output_size - 1] |= flags.u8_bitmask();
+        writer.write_all(&buffer[..output_size])?;
+        Ok(())
+    }
+
+    fn serialized_size_with_flags<F: Flags>(&self) -> usize {
+        buffer_byte_size(64 + F::BIT_SIZE)
+    }
+}
+
+impl CanonicalSerialize for ArkGoldilocks {
+    fn serialize_with_mode<W: Write>(
+        &self,
+        writer: W,
+        _compress: Compress,
+    ) -> Result<(), SerializationError> {
+        self.serialize_with_flags(writer, EmptyFlags)
+    }
+
+    fn serialized_size(&self, _compress: Compress) -> usize {
+        self.serialized_size_with_flags::<EmptyFlags>()
+    }
+}
+
+/// Rejects values that are not canonical, so that an element has a unique
+/// serialization.
+impl CanonicalDeserializeWithFlags for ArkGoldilocks {
+    fn deserialize_with_flags<R: Read, F: Flags>(
+        mut reader: R,
+    ) -> Result<(Self, F), SerializationError> {
+        if F::BIT_SIZE > 8 {
+            return Err(SerializationError::NotEnoughSpace);
+        }
+        let output_size = buffer_byte_size(64 + F::BIT_SIZE);
+        let mut buffer = [0u8; 9];
+        reader.read_exact(&mut buffer[..output_size])?;
+        let flags = F::from_u8_remove_flags(&mut buffer[output_size - 1])
+            .ok_or(SerializationError::UnexpectedFlags)?;
+        if buffer[8] != 0 {
+            return Err(SerializationError::InvalidData);
+        }
+        let element = Option::from(Goldilocks::from_repr(buffer[..8].try_into().unwrap()))
+            .ok_or(SerializationError::InvalidData)?;
+        Ok((Self(element), flags))
+    }
+}
+
+impl CanonicalDeserialize for ArkGoldilocks {
+    fn deserialize_with_mode<R: Read>(
+        reader: R,
+        _compress: Compress,
+        _validate: Validate,
+    ) -> Result<Self, SerializationError> {
+        Self::deserialize_with_flags::<R, EmptyFlags>(reader).map(|(element, _)| element)
+    }
+}
+
+impl Neg for ArkGoldilocks {
+    type Output = Self;
+
+    #[inline]
+    fn neg(self) -> Self {
+        Self(-self.0)
+    }
+}
+
+impl Div for ArkGoldilocks {
+    type Output = Self;
+
+    /// Panics if `rhs` is zero.
+    #[inline]
+    #[allow(clippy::suspicious_arithmetic_impl)]
+    fn div(self, rhs: Self) -> Self {
+        Self(self.0 * rhs.0.invert().expect("division by zero"))
+    }
+}
+
+impl Add for ArkGoldilocks {
+    type Output = Self;
+
+    #[inline]
+    fn add(self, rhs: Self) -> Self {
+        Self(self.0 + rhs.0)
+    }
+}
+
+impl Sub for ArkGoldilocks {
+    type Output = Self;
+
+    #[inline]
+    fn sub(self, rhs: Self) -> Self {
+        Self(self.0 - rhs.0)
+    }
+}
+
+impl Mul for ArkGoldilocks {
+    type Output = Self;
+
+    #[inline]
+    fn mul(self, rhs: Self) -> Self {
+        Self(self.0 * rhs.0)
+    }
+}
+
+/// Implements the operators by reference and the assigning operators that
+/// `ark_ff::Field` requires, from those by value.
+macro_rules! impl_ref_ops {
+    ($(($op:ident, $method:ident, $assign_op:ident, $assign_method:ident)),*) => {
+        $(
+            impl<'a> $op<&'a ArkGoldilocks> for ArkGoldilocks {
+                type Output = Self;
+
+                #[inline]
+                fn $method(self, rhs: &'a ArkGoldilocks) -> Self {
+                    $op::$method(self, *rhs)
+                }
+            }
+
+            impl<'a> $op<&'a mut ArkGoldilocks> for ArkGoldilocks {
+                type Output = Self;
+
+                #[inline]
+                fn $method(self, rhs: &'a mut ArkGoldilocks) -> Self {
+                    $op::$method(self, *rhs)
+                }
+            }
+
+            impl $assign_op for ArkGoldilocks {
+                #[inline]
+                fn $assign_method(&mut self, rhs: Self) {
+                    *self = $op::$method(*self, rhs);
+                }
+            }
+
+            impl<'a> $assign_op<&'a ArkGoldilocks> for ArkGoldilocks {
+                #[inline]
+                fn $assign_method(&mut self, rhs: &'a ArkGoldilocks) {
+                    *self = $op::$method(*self, *rhs);
+                }
+            }
+
+            impl<'a> $assign_op<&'a mut ArkGoldilocks> for ArkGoldilocks {
+                #[inline]
+                fn $assign_method(&mut self, rhs: &'a mut ArkGoldilocks) {
+                    *self = $op::$method(*self, *rhs);
+                }
+            }
+        )*
+    };
+}
+
+impl_ref_ops!(
+    (Add, add, AddAssign, add_assign),
+    (Sub, sub, SubAssign, sub_assign),
+    (Mul, mul, MulAssign, mul_assign),
+    (Div, div, DivAssign, div_assign)
+);
+
+impl<T: Borrow<ArkGoldilocks>> Sum<T> for ArkGoldilocks {
+    fn sum<I: Iterator<Item = T>>(iter: I) -> Self {
+        Self(iter.map(|x| x.borrow().0).sum())
+    }
+}
+
+impl<T: Borrow<ArkGoldilocks>> Product<T> for ArkGoldilocks {
+    fn product<I: Iterator<Item = T>>(iter: I) -> Self {
+        Self(iter.map(|x| x.borrow().0).product())
+    }
+}
@@ -0,0 +1,301 @@
+//! Checks of the `ff` contract that every `SmallField` and `ExtensionField`
+//! must satisfy, for the tests of the fields implementing them. The generic
+//! code of the other crates relies on these properties, e.g. on a unique
+//! `to_repr` for every element.
+//!
+//! The modulus is recovered as `-1 + 1`, so these checks apply to fields of
+//! at most 64 bits.
+
+use ff::Field;
+use rand_core::RngCore;
+
+use crate::{ExtensionField, SmallField};
+
+/// Run every check of this module on `F`.
+pub fn small_field_conformance_tests<F: SmallField>(mut rng: impl RngCore) {
+    constants_tests::<F>();
+    arithmetic_tests::<F>(&mut rng);
+    from_u64_tests::<F>(&mut rng);
+    repr_tests::<F>(&mut rng);
+    sqrt_ratio_tests::<F>(&mut rng);
+}
+
+/// The modulus of `F`.
+pub fn modulus<F: SmallField>() -> u128 {
+    (-F::ONE).to_canonical_u64_vec()[0] as u128 + 1
+}
+
+/// The constants of `PrimeField` agree with each other and with the modulus.
+pub fn constants_tests<F: SmallField>() {
+    let modulus = modulus::<F>();
+
+    assert!(modulus < 1 << F::NUM_BITS);
+    assert!(modulus >= 1 << (F::NUM_BITS - 1));
+    assert_eq!(F::CAPACITY, F::NUM_BITS - 1);
+
+    // modulus - 1 = 2^S * t with t odd
+    let t = (modulus - 1) >> F::S;
+    assert_eq!(t << F::S, modulus - 1);
+    assert_eq!(t & 1, 1);
+
+    assert_eq!(F::TWO_INV.double(), F::ONE);
+
+    assert!(bool::from(F::MULTIPLICATIVE_GENERATOR.sqrt().is_none()));
+    assert_eq!(
+        F::ROOT_OF_UNITY,
+        F::MULTIPLICATIVE_GENERATOR.pow_vartime([t as u64])
+    );
+    assert_eq!(F::ROOT_OF_UNITY * F::ROOT_OF_UNITY_INV, F::ONE);
+    let mut root = F::ROOT_OF_UNITY;
+    for _ in 0..F::S - 1 {
+        root = root.square();
+    }
+    assert_eq!(root, -F::ONE);
+
+    let mut delta = F::MULTIPLICATIVE_GENERATOR;
+    for _ in 0..F::S {
+        delta = delta.square();
+    }
+    assert_eq!(F::DELTA, delta);
+    assert_eq!(F::DELTA.pow_vartime([t as u64]), F::ONE);
+}
+
+/// The arithmetic is that of the integers modulo the modulus, including at
+/// the edges of the field.
+pub fn arithmetic_tests<F: SmallField>(mut rng: impl RngCore) {
+    let modulus = modulus::<F>();
+    let canonical = |a: F| a.to_canonical_u64_vec()[0] as u128;
+
+    let mut values = vec![F::ZERO, F::ONE, -F::ONE, F::TWO_INV, -F::TWO_INV];
+    values.extend((0..100).map(|_| F::random(&mut rng)));
+
+    for a in values.iter() {
+        let x = canonical(*a);
+        assert!(x < modulus);
+        for b in values.iter() {
+            let y = canonical(*b);
+            assert_eq!(canonical(*a + b), (x + y) % modulus);
+            assert_eq!(canonical(*a - b), (x + modulus - y) % modulus);
+            assert_eq!(canonical(*a * b), x * y % modulus);
+        }
+        assert_eq!(canonical(-*a), (modulus - x) % modulus);
+        assert_eq!(canonical(a.double()), 2 * x % modulus);
+        assert_eq!(canonical(a.square()), x * x % modulus);
+        match Option::<F>::from(a.invert()) {
+            Some(inverse) => assert_eq!(canonical(inverse) * x % modulus, 1),
+            None => assert_eq!(x, 0),
+        }
+    }
+}
+
+/// `From<u64>` reduces its input.
+pub fn from_u64_tests<F: SmallField>(mut rng: impl RngCore) {
+    let modulus = modulus::<F>();
+
+    let mut values = vec![0, 1, u64::MAX];
+    if modulus <= u64::MAX as u128 {
+        values.extend([modulus as u64 - 1, modulus as u64]);
+    }
+    values.extend((0..1000).map(|_| rng.next_u64()));
+
+    for value in values {
+        let element = F::from(value);
+        let reduced = (value as u128 % modulus) as u64;
+        assert_eq!(element.to_canonical_u64_vec(), [reduced]);
+        assert_eq!(element, F::from_u128(value as u128));
+        assert_eq!(element.is_odd().unwrap_u8() as u64, reduced & 1);
+    }
+}
+
+/// `to_repr` is the little-endian encoding of the canonical value, and
+/// `from_repr` accepts exactly the canonical encodings.
+pub fn repr_tests<F: SmallField>(mut rng: impl RngCore) {
+    let modulus = modulus::<F>();
+
+    let encode = |value: u128| {
+        let mut repr = F::Repr::default();
+        let len = repr.as_ref().len();
+        repr.as_mut().copy_from_slice(&value.to_le_bytes()[..len]);
+        repr
+    };
+
+    for _ in 0..1000 {
+        // the product is likely to be stored in a non-canonical form
+        let a = F::random(&mut rng) * F::random(&mut rng);
+        let canonical = a.to_canonical_u64_vec()[0];
+
+        let repr = a.to_repr();
+        assert_eq!(repr.as_ref(), encode(canonical as u128).as_ref());
+        assert_eq!(F::from_repr(repr).unwrap(), a);
+        assert_eq!(F::from_repr_vartime(repr), Some(a));
+    }
+
+    assert!(bool::from(F::from_repr(encode(modulus - 1)).is_some()));
+
+    // the encodings of non-canonical values are rejected
+    let repr_bits = 8 * F::Repr::default().as_ref().len() as u32;
+    let max = u128::MAX >> (128 - repr_bits);
+    for value in [modulus, modulus + 1, modulus + 2, max] {
+        if repr_bits < 128 && value >> repr_bits != 0 {
+            continue;
+        }
+        let repr = encode(value);
+        assert!(bool::from(F::from_repr(repr).is_none()));
+        assert_eq!(F::from_repr_vartime(repr), None);
+    }
+}
+
+/// `sqrt_ratio` follows the contract of `Field::sqrt_ratio`.
+pub fn sqrt_ratio_tests<F: SmallField>(mut rng: impl RngCore) {
+    let mut non_square = None;
+    for _ in 0..1000 {
+        let a = F::random(&mut rng);
+        let b = F::random(&mut rng);
+
+        // num / div is a square
+        let (is_square, root) = F::sqrt_ratio(&(a.square() * b), &b);
+        assert!(bool::from(is_square));
+        assert_eq!(root.square(), a.square());
+
+        let (is_square, root) = F::sqrt_ratio(&a, &b);
+        let ratio = a * b.invert().unwrap();
+        if bool::from(is_square) {
+            assert_eq!(root.square(), ratio);
+        } else {
+            // the root is that of num / div times a fixed non-square
+            assert!(bool::from(ratio.sqrt().is_none()));
+            let g = root.square() * ratio.invert().unwrap();
+            assert!(bool::from(g.sqrt().is_none()));
+            assert_eq!(*non_square.get_or_insert(g), g);
+        }
+
+        let (is_square, root) = F::sqrt_ratio(&F::ZERO, &b);
+        assert!(bool::from(is_square));
+        assert_eq!(root, F::ZERO);
+
+        let (is_square, root) = F::sqrt_ratio(&a, &F::ZERO);
+        assert!(!bool::from(is_square));
+        assert_eq!(root, F::ZERO);
+    }
+    assert!(non_square.is_some());
+
+    let (is_square, root) = F::sqrt_alt(&F::ONE);
+    assert!(bool::from(is_square));
+    assert_eq!(root.square(), F::ONE);
+}
+
+/// Run every check of an extension field on `E`. The checks of its base
+/// field are those of `small_field_conformance_tests`.
+pub fn extension_field_conformance_tests<E: ExtensionField>(mut rng: impl RngCore) {
+    field_axioms_tests::<E>(&mut rng);
+    frobenius_tests::<E>(&mut rng);
+    mixed_arithmetic_tests::<E>(&mut rng);
+    extension_sqrt_tests::<E>(&mut rng);
+}
+
+/// The operations of `E` satisfy the field axioms.
+pub fn field_axioms_tests<E: ExtensionField>(mut rng: impl RngCore) {
+    assert!(bool::from(E::ZERO.invert().is_none()));
+    for _ in 0..10000 {
+        let a = E::random(&mut rng);
+        let b = E::random(&mut rng);
+        let c = E::random(&mut rng);
+
+        assert_eq!((a * b) * c, a * (b * c));
+        assert_eq!(a * b, b * a);
+        assert_eq!(a * (b + c), a * b + a * c);
+        assert_eq!((a + b) + c, a + (b + c));
+        assert_eq!(a - b, -(b - a));
+        assert_eq!(a.square(), a * a);
+        assert_eq!(a.double(), a + a);
+        assert_eq!(a * E::ONE, a);
+        assert_eq!(a + E::ZERO, a);
+        if !bool::from(a.is_zero()) {
+            assert_eq!(a * a.invert().unwrap(), E::ONE);
+        }
+    }
+}
+
+/// `frobenius` is the `p`-th power map, and `norm` the product of the
+/// conjugates.
+pub fn frobenius_tests<E: ExtensionField>(mut rng: impl RngCore) {
+    // the modulus of the base field, as the exponent of the Frobenius map
+    let modulus = modulus::<E::BaseField>() as u64;
+    for _ in 0..10000 {
+        let a = E::random(&mut rng);
+        let b = E::random(&mut rng);
+
+        assert_eq!(a.frobenius(), a.pow_vartime([modulus]));
+        assert_eq!(a.frobenius_power(E::DEGREE), a);
+        assert_eq!((a * b).frobenius(), a.frobenius() * b.frobenius());
+
+        let norm = (0..E::DEGREE).fold(E::ONE, |acc, k| acc * a.frobenius_power(k));
+        assert_eq!(norm, E::from(a.norm()));
+
+        let base = E::BaseField::random(&mut rng);
+        assert_eq!(E::from(base).frobenius(), E::from(base));
+    }
+}
+
+/// The arithmetic with the base field is that of its embedding.
+pub fn mixed_arithmetic_tests<E: ExtensionField>(mut rng: impl RngCore) {
+    for _ in 0..100000 {
+        let a = E::random(&mut rng);
+        let b = E::BaseField::random(&mut rng);
+
+        assert_eq!(a + b, a + E::from(b));
+        assert_eq!(a - b, a - E::from(b));
+        assert_eq!(a * b, a * E::from(b));
+
+        let mut c = a;
+        c += b;
+        c -= b;
+        c *= b;
+        assert_eq!(c, a * b);
+
+        assert_eq!(E::from_bases(a.as_bases()), a);
+        assert_eq!(E::from_bases(&[b]), E::from(b));
+        assert_eq!(a.as_bases().len(), E::DEGREE);
+        assert_eq!(
+            a.to_canonical_u64_vec().len(),
+            E::DEGREE * b.to_canonical_u64_vec().len()
+        );
+    }
+}
+
+/// `sqrt` and `sqrt_ratio` follow the contract of `Field`.
+pub fn extension_sqrt_tests<E: ExtensionField>(mut rng: impl RngCore) {
+    assert_eq!(E::ZERO.sqrt().unwrap(), E::ZERO);
+
+    let mut non_square = None;
+    for _ in 0..1000 {
+        let a = E::random(&mut rng);
+        let b = a.square().sqrt().unwrap();
+        assert!(b == a || b == -a);
+
+        let (is_square, root) = E::sqrt_ratio(&a, &E::ONE);
+        if bool::from(is_square) {
+            assert_eq!(root.square(), a);
+        } else {
+            // the root is that of a times a fixed non-square
+            assert!(bool::from(a.sqrt().is_none()));
+            let g = root.square() * a.invert().unwrap();
+            assert!(bool::from(g.sqrt().is_none()));
+            assert_eq!(*non_square.get_or_insert(g), g);
+        }
+
+        let c = E::random(&mut rng);
+        let (is_square, root) = E::sqrt_ratio(&(a.square() * c), &c);
+        assert!(bool::from(is_square));
+        assert_eq!(root.square(), a.square());
+
+        let (is_square, root) = E::sqrt_ratio(&E::ZERO, &a);
+        assert!(bool::from(is_square));
+        assert_eq!(root, E::ZERO);
+
+        let (is_square, root) = E::sqrt_ratio(&a, &E::ZERO);
+        assert!(!bool::from(is_square));
+        assert_eq!(root, E::ZERO);
+    }
+    assert!(non_square.is_some());
+}
@@ -0,0 +1,337 @@
+//! This module defines the trait shared by the extension fields of a small
+//! field, and the helpers and operator macros their implementations have in
+//! common, including those of the fields of other crates.
+
+use core::ops::{Add, AddAssign, Mul, MulAssign, Sub, SubAssign};
+
+use ff::Field;
+use serde::{de::DeserializeOwned, Serialize};
+use subtle::{Choice, CtOption};
+
+use crate::SmallField;
+
+/// An extension field of degree `DEGREE` over `BaseField`, whose elements
+/// are written as `\sum_i a_i x^i` in the polynomial basis.
+pub trait ExtensionField:
+    Field
+    + Serialize
+    + DeserializeOwned
+    + From<Self::BaseField>
+    + Add<Self::BaseField, Output = Self>
+    + Sub<Self::BaseField, Output = Self>
+    + Mul<Self::BaseField, Output = Self>
+    + AddAssign<Self::BaseField>
+    + SubAssign<Self::BaseField>
+    + MulAssign<Self::BaseField>
+{
+    /// Degree of the extension.
+    const DEGREE: usize;
+
+    type BaseField: SmallField;
+
+    /// Construct an element from its coefficients in the polynomial basis.
+    /// Missing coefficients are zero.
+    fn from_bases(bases: &[Self::BaseField]) -> Self;
+
+    /// The coefficients of the element in the polynomial basis.
+    fn as_bases(&self) -> &[Self::BaseField];
+
+    /// The Frobenius endomorphism `a -> a^p`.
+    fn frobenius(&self) -> Self;
+
+    /// The `k`-th power of the Frobenius endomorphism, `a -> a^(p^k)`.
+    fn frobenius_power(&self, k: usize) -> Self {
+        (0..k % Self::DEGREE).fold(*self, |acc, _| acc.frobenius())
+    }
+
+    /// The norm of the element over the base field, i.e. the product of its
+    /// conjugates.
+    fn norm(&self) -> Self::BaseField {
+        let norm = (1..Self::DEGREE).fold(*self, |acc, k| acc * self.frobenius_power(k));
+        norm.as_bases()[0]
+    }
+
+    /// The element as a list of canonical 64 bits limbs, which is how it is
+    /// absorbed into a transcript.
+    fn to_canonical_u64_vec(&self) -> Vec<u64> {
+        self.as_bases()
+            .iter()
+            .flat_map(|base| base.to_canonical_u64_vec())
+            .collect()
+    }
+}
+
+/// Square root by Tonelli-Shanks in a field of order `2^s * t + 1`, where
+/// `tm1d2 = (t - 1) / 2` and `root_of_unity` has order `2^s`.
+/// Note: this is not constant time!
+pub fn sqrt_tonelli_shanks<F: Field>(
+    f: &F,
+    tm1d2: &[u64],
+    s: u32,
+    root_of_unity: F,
+) -> CtOption<F> {
+    if bool::from(f.is_zero()) {
+        return CtOption::new(F::ZERO, Choice::from(1));
+    }
+
+    // x = f^((t + 1) / 2) and b = f^t, so that x^2 = b * f
+    let w = f.pow_vartime(tm1d2);
+    let mut x = w * f;
+    let mut b = x * w;
+    let mut z = root_of_unity;
+    let mut v = s;
+
+    while b != F::ONE {
+        // the order of b is 2^k
+        let mut k = 0;
+        let mut b2k = b;
+        while b2k != F::ONE {
+            b2k = b2k.square();
+            k += 1;
+        }
+        if k == v {
+            // b has the order of the root of unity, f is not a square
+            return CtOption::new(F::ZERO, Choice::from(0));
+        }
+
+        let mut w = z;
+        for _ in 0..v - k - 1 {
+            w = w.square();
+        }
+        z = w.square();
+        b *= z;
+        x *= w;
+        v = k;
+    }
+    CtOption::new(x, Choice::from(1))
+}
+
+/// Implements `Field::sqrt_ratio` from `sqrt`, given a non-square of the
+/// field.
+pub fn sqrt_ratio<F: Field>(num: &F, div: &F, non_square: F) -> (Choice, F) {
+    let a = div.invert().unwrap_or(F::ZERO) * num;
+    let b = a * non_square;
+    let sqrt_a = a.sqrt();
+    let sqrt_b = b.sqrt();
+
+    let num_is_zero = num.is_zero();
+    let div_is_zero = div.is_zero();
+    let is_square = sqrt_a.is_some();
+    let is_nonsquare = sqrt_b.is_some();
+    assert!(bool::from(
+        num_is_zero | div_is_zero | (is_square ^ is_nonsquare)
+    ));
+
+    (
+        is_square & (num_is_zero | !div_is_zero),
+        F::conditional_select(
+            &sqrt_b.unwrap_or(F::ZERO),
+            &sqrt_a.unwrap_or(F::ZERO),
+            is_square,
+        ),
+    )
+}
+
+/// Implements the by-reference and assigning variants of `Add`, `Sub` and
+/// `Mul` of a field `$field` from its owned `Add`, `Sub` and `Mul`, and `Sum`
+/// and `Product`.
+#[macro_export]
+macro_rules! impl_derived_ops {
+    ($field:ident) => {
+        impl<'a> ::core::ops::Add<&'a $field> for $field {
+            type Output = Self;
+
+            #[inline]
+            fn add(self, rhs: &'a $field) -> Self::Output {
+                self + *rhs
+            }
+        }
+
+        impl ::core::ops::AddAssign for $field {
+            #[inline]
+            fn add_assign(&mut self, rhs: Self) {
+                *self = *self + rhs;
+            }
+        }
+
+        impl<'a> ::core::ops::AddAssign<&'a $field> for $field {
+            #[inline]
+            fn add_assign(&mut self, rhs: &'a $field) {
+                *self = *self + *rhs;
+            }
+        }
+
+        impl<'a> ::core::ops::Sub<&'a $field> for $field {
+            type Output = Self;
+
+            #[inline]
+            fn sub(self, rhs: &'a $field) -> Self::Output {
+                self - *rhs
+            }
+        }
+
+        impl ::core::ops::SubAssign for $field {
+            #[inline]
+            fn sub_assign(&mut self, rhs: Self) {
+                *self = *self - rhs;
+            }
+        }
+
+        impl<'a> ::core::ops::SubAssign<&'a $field> for $field {
+            #[inline]
+            fn sub_assign(&mut self, rhs: &'a $field) {
+                *self = *self - *rhs;
+            }
+        }
+
+        impl<'a> ::core::ops::Mul<&'a $field> for $field {
+            type Output = Self;
+
+            #[inline]
+            fn mul(self, rhs: &'a $field) -> Self::Output {
+                self * *rhs
+            }
+        }
+
+        impl ::core::ops::MulAssign for $field {
+            #[inline]
+            fn mul_assign(&mut self, rhs: Self) {
+                *self = *self * rhs;
+            }
+        }
+
+        impl<'a> ::core::ops::MulAssign<&'a $field> for $field {
+            #[inline]
+            fn mul_assign(&mut self, rhs: &'a $field) {
+                *self = *self * *rhs;
+            }
+        }
+
+        impl<T: ::core::borrow::Borrow<$field>> ::core::iter::Sum<T> for $field {
+            fn sum<I: Iterator<Item = T>>(iter: I) -> Self {
+                iter.fold(<Self as $crate::ff::Field>::ZERO, |acc, item| {
+                    acc + item.borrow()
+                })
+            }
+        }
+
+        impl<T: ::core::borrow::Borrow<$field>> ::core::iter::Product<T> for $field {
+            fn product<I: Iterator<Item = T>>(iter: I) -> Self {
+                iter.fold(<Self as $crate::ff::Field>::ONE, |acc, item| {
+                    acc * item.borrow()
+                })
+            }
+        }
+    };
+}
+
+/// Implements the operators of an extension field `$ext` of `$base` stored
+/// as an array of coefficients, from its `Add`, `Sub`, `Mul` and `Neg`: the
+/// operators of `impl_derived_ops`, constant time selection and equality,
+/// and the mixed arithmetic with the base field.
+#[macro_export]
+macro_rules! impl_extension_ops {
+    ($ext:ident, $base:ident) => {
+        $crate::impl_derived_ops!($ext);
+
+        impl $crate::subtle::ConditionallySelectable for $ext {
+            fn conditional_select(a: &Self, b: &Self, choice: $crate::subtle::Choice) -> Self {
+                let mut res = *a;
+                res.0.iter_mut().zip(b.0.iter()).for_each(|(res, b)| {
+                    *res = <$base as $crate::subtle::ConditionallySelectable>::conditional_select(
+                        res, b, choice,
+                    )
+                });
+                res
+            }
+        }
+
+        impl $crate::subtle::ConstantTimeEq for $ext {
+            fn ct_eq(&self, other: &Self) -> $crate::subtle::Choice {
+                self.0
+                    .iter()
+                    .zip(other.0.iter())
+                    .fold($crate::subtle::Choice::from(1), |acc, (a, b)| {
+                        acc & $crate::subtle::ConstantTimeEq::ct_eq(a, b)
+                    })
+            }
+        }
+
+        impl From<$base> for $ext {
+            fn from(base: $base) -> Self {
+                let mut res = <Self as $crate::ff::Field>::ZERO;
+                res.0[0] = base;
+                res
+            }
+        }
+
+        impl ::core::ops::Add<$base> for $ext {
+            type Output = Self;
+
+            #[inline]
+            fn add(mut self, rhs: $base) -> Self::Output {
+                self.0[0] += rhs;
+                self
+            }
+        }
+
+        impl ::core::ops::AddAssign<$base> for $ext {
+            #[inline]
+            fn add_assign(&mut self, rhs: $base) {
+                self.0[0] += rhs;
+            }
+        }
+
+        impl ::core::ops::Sub<$base> for $ext {
+            type Output = Self;
+
+            #[inline]
+            fn sub(mut self, rhs: $base) -> Self::Output {
+                self.0[0] -= rhs;
+                self
+            }
+        }
+
+        impl ::core::ops::SubAssign<$base> for $ext {
+            #[inline]
+            fn sub_assign(&mut self, rhs: $base) {
+                self.0[0] -= rhs;
+            }
+        }
+
+        impl ::core::ops::Mul<$base> for $ext {
+            type Output = Self;
+
+            #[inline]
+            fn mul(mut self, rhs: $base) -> Self::Output {
+                self *= rhs;
+                self
+            }
+        }
+
+        impl ::core::ops::MulAssign<$base> for $ext {
+            #[inline]
+            fn mul_assign(&mut self, rhs: $base) {
+                self.0.iter_mut().for_each(|a| *a *= rhs);
+            }
+        }
+
+        impl ::core::ops::Mul<$ext> for $base {
+            type Output = $ext;
+
+            #[inline]
+            fn mul(self, rhs: $ext) -> Self::Output {
+                rhs * self
+            }
+        }
+
+        impl ::core::ops::Add<$ext> for $base {
+            type Output = $ext;
+
+            #[inline]
+            fn add(self, rhs: $ext) -> Self::Output {
+                rhs + self
+            }
+        }
+    };
+}
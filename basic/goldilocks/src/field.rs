@@ -1,10 +1,22 @@
 //! This module defines our customized field trait.
 
 use ff::PrimeField;
-use serde::{Deserialize, Serialize};
+use serde::Serialize;
 
 use crate::Goldilocks;
 
-pub trait SmallField: PrimeField + Serialize {}
+/// A prime field of at most 64 bits. On top of the contract of `PrimeField`,
+/// `to_repr` must be the little-endian encoding of the canonical value; see
+/// `conformance`, behind the `test-utils` feature, for the checks that every
+/// implementation must pass.
+pub trait SmallField: PrimeField + Serialize {
+    /// The element as a list of canonical 64 bits limbs, which is how it is
+    /// absorbed into a transcript.
+    fn to_canonical_u64_vec(&self) -> Vec<u64>;
+}
 
-impl SmallField for Goldilocks {}
\ No newline at end of file
+impl SmallField for Goldilocks {
+    fn to_canonical_u64_vec(&self) -> Vec<u64> {
+        vec![self.to_canonical_u64()]
+    }
+}
@@ -2,17 +2,24 @@ use crate::util::{add_no_canonicalize_trashing_input, branch_hint, split, sqrt_t
 use crate::util::{assume, try_inverse_u64};
 use core::iter::{Product, Sum};
 use core::ops::{Add, AddAssign, Mul, MulAssign, Neg, Sub, SubAssign};
+use ff::helpers::sqrt_ratio_generic;
 use ff::{Field, PrimeField};
 use rand_core::RngCore;
-use std::fmt::{Display, Formatter};
+use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
+use std::fmt::{Debug, Display, Formatter};
 use subtle::{Choice, ConditionallySelectable, ConstantTimeEq, CtOption};
-use serde::{Deserialize, Serialize};
 
 /// Goldilocks field with modulus 2^64 - 2^32 + 1.
-/// A Goldilocks field may store a non-canonical form of the element
-/// where the value can be between 0 and 2^64.
-/// For unique representation of its form, use `to_canonical_u64`
-#[derive(Clone, Copy, Debug, Default, Eq, Deserialize, Serialize)]
+///
+/// The internal `u64` is NOT canonical: arithmetic only reduces its results
+/// to 64 bits, so an element `x` may be stored as either `x` or `x + p` when
+/// `x < 2^64 - p`. Everything observable goes through the canonical value in
+/// `[0, p)`, given by `to_canonical_u64`: equality, `to_repr`, `is_odd`,
+/// `Debug`, `Display` and serialization. Conversely, every way of constructing an
+/// element from outside the crate reduces (`From<u64>`) or rejects
+/// (`from_repr`, deserialization) values that are not canonical.
+#[derive(Clone, Copy, Default, Eq)]
+#[repr(transparent)]
 pub struct Goldilocks(pub(crate) u64);
 
 impl PartialEq for Goldilocks {
@@ -21,9 +28,40 @@ impl PartialEq for Goldilocks {
     }
 }
 
+/// The serialized form of an element: its canonical value, in a newtype
+/// struct like the one `#[derive(Serialize, Deserialize)]` on `Goldilocks` would
+/// give.
+#[derive(Serialize, Deserialize)]
+#[serde(rename = "Goldilocks")]
+struct Canonical(u64);
+
+/// Serializes the canonical form, so that equal elements serialize to the
+/// same bytes, e.g. when they are appended to a transcript.
+impl Serialize for Goldilocks {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        Canonical(self.to_canonical_u64()).serialize(serializer)
+    }
+}
+
+/// Rejects values that are not canonical, so that an element has a unique
+/// serialization.
+impl<'de> Deserialize<'de> for Goldilocks {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let Canonical(value) = Canonical::deserialize(deserializer)?;
+        Option::from(Self::from_repr(value.to_le_bytes()))
+            .ok_or_else(|| de::Error::custom(format!("non-canonical Goldilocks element {}", value)))
+    }
+}
+
+impl Debug for Goldilocks {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Goldilocks({})", self.to_canonical_u64())
+    }
+}
+
 impl Display for Goldilocks {
     fn fmt(&self, w: &mut Formatter<'_>) -> Result<(), std::fmt::Error> {
-        write!(w, "{}", self.0)
+        write!(w, "{}", self.to_canonical_u64())
     }
 }
 
@@ -101,40 +139,23 @@ impl Field for Goldilocks {
     /// - The choice of root from `sqrt` is unspecified.
     /// - The value of $G_S$ is unspecified, and cannot be assumed to have any specific
     ///   value in a generic context.
-    fn sqrt_ratio(_: &Self, _: &Self) -> (Choice, Self) {
-        unimplemented!()
+    fn sqrt_ratio(num: &Self, div: &Self) -> (Choice, Self) {
+        sqrt_ratio_generic(num, div)
     }
 }
 
+/// The internal, possibly non-canonical, value.
 impl AsRef<u64> for Goldilocks {
     fn as_ref(&self) -> &u64 {
         &self.0
     }
 }
 
-impl AsMut<[u8]> for Goldilocks {
-    fn as_mut(&mut self) -> &mut [u8] {
-        let ptr = self as *mut Self as *mut u8;
-        // SAFETY Self is repr(transparent) and u64 is 8 bytes wide,
-        // with alignment greater than that of u8
-        unsafe { std::slice::from_raw_parts_mut(ptr, 8) }
-    }
-}
-
-impl AsRef<[u8]> for Goldilocks {
-    fn as_ref(&self) -> &[u8] {
-        let ptr = self as *const Self as *const u8;
-        // SAFETY Self is repr(transparent) and u64 is 8 bytes wide,
-        // with alignment greater than that of u8
-        unsafe { std::slice::from_raw_parts(ptr, 8) }
-    }
-}
-
 /// This represents an element of a prime field.
 impl PrimeField for Goldilocks {
     /// The prime field can be converted back and forth into this binary
-    /// representation.
-    type Repr = Self;
+    /// representation, the little-endian bytes of the canonical value.
+    type Repr = [u8; 8];
 
     /// Modulus of the field written as a string for debugging purposes.
     ///
@@ -192,7 +213,8 @@ impl PrimeField for Goldilocks {
     /// The byte representation is interpreted with the same endianness as elements
     /// returned by [`PrimeField::to_repr`].
     fn from_repr(repr: Self::Repr) -> CtOption<Self> {
-        CtOption::new(repr, Choice::from(1))
+        let value = u64::from_le_bytes(repr);
+        CtOption::new(Self(value), Choice::from((value < MODULUS) as u8))
     }
 
     /// Attempts to convert a byte representation of a field element into an element of
@@ -216,24 +238,26 @@ impl PrimeField for Goldilocks {
     /// The endianness of the byte representation is implementation-specific. Generic
     /// encodings of field elements should be treated as opaque.
     fn to_repr(&self) -> Self::Repr {
-        *self
+        self.to_canonical_u64().to_le_bytes()
     }
 
     /// Returns true iff this element is odd.
     fn is_odd(&self) -> Choice {
-        Choice::from((self.0 & 1) as u8)
+        Choice::from((self.to_canonical_u64() & 1) as u8)
     }
 }
 
+/// Reduces the input modulo p.
 impl From<u64> for Goldilocks {
     fn from(input: u64) -> Self {
-        Self(input)
+        Self(Self(input).to_canonical_u64())
     }
 }
 
+/// The canonical value of the element.
 impl From<Goldilocks> for u64 {
     fn from(input: Goldilocks) -> Self {
-        input.0
+        input.to_canonical_u64()
     }
 }
 
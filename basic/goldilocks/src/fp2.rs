@@ -0,0 +1,149 @@
+use crate::extension::{sqrt_ratio, sqrt_tonelli_shanks, ExtensionField};
+use crate::fp::Goldilocks;
+use core::ops::{Add, Mul, Neg, Sub};
+use ff::Field;
+use rand_core::RngCore;
+use serde::{Deserialize, Serialize};
+use subtle::{Choice, CtOption};
+
+/// Quadratic extension of the Goldilocks field, GF(p)[x] / (x^2 - 7).
+/// An element `a_0 + a_1 x` is stored as `[a_0, a_1]`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GoldilocksExt2(pub(crate) [Goldilocks; 2]);
+
+/// x^2 = W
+const W: Goldilocks = Goldilocks(7);
+
+/// (t - 1) / 2 where p^2 - 1 = 2^33 * t
+const TM1D2: [u64; 2] = [0x80000000bfffffff, 0x3fffffff];
+
+/// A 2^33 root of unity, x^t.
+const ROOT_OF_UNITY: GoldilocksExt2 =
+    GoldilocksExt2([Goldilocks(0), Goldilocks(0x076de30b51a3f645)]);
+
+impl GoldilocksExt2 {
+    pub const fn new(a0: Goldilocks, a1: Goldilocks) -> Self {
+        Self([a0, a1])
+    }
+}
+
+impl Field for GoldilocksExt2 {
+    /// The zero element of the field, the additive identity.
+    const ZERO: Self = Self([Goldilocks::ZERO; 2]);
+
+    /// The one element of the field, the multiplicative identity.
+    const ONE: Self = Self([Goldilocks::ONE, Goldilocks::ZERO]);
+
+    /// Returns an element chosen uniformly at random using a user-provided RNG.
+    /// Note: this sampler is not constant time!
+    fn random(mut rng: impl RngCore) -> Self {
+        Self([Goldilocks::random(&mut rng), Goldilocks::random(&mut rng)])
+    }
+
+    /// Squares this element.
+    #[must_use]
+    fn square(&self) -> Self {
+        // (a_0 + a_1 x)^2 = a_0^2 + W a_1^2 + 2 a_0 a_1 x
+        let [a0, a1] = self.0;
+        Self([a0.square() + W * a1.square(), (a0 * a1).double()])
+    }
+
+    /// Doubles this element.
+    #[must_use]
+    fn double(&self) -> Self {
+        *self + *self
+    }
+
+    /// Computes the multiplicative inverse of this element,
+    /// failing if the element is zero.
+    fn invert(&self) -> CtOption<Self> {
+        // 1 / a = conj(a) / (a * conj(a)), where a * conj(a) is the norm
+        self.norm()
+            .invert()
+            .map(|norm_inv| self.frobenius() * norm_inv)
+    }
+
+    /// Returns the square root of the field element, if it is
+    /// quadratic residue.
+    /// Note: this is not constant time!
+    fn sqrt(&self) -> CtOption<Self> {
+        sqrt_tonelli_shanks(self, &TM1D2, 33, ROOT_OF_UNITY)
+    }
+
+    /// Computes the square root of `num / div`, see `Field::sqrt_ratio`.
+    /// The non-square used when `num / div` is not a square is `x`.
+    fn sqrt_ratio(num: &Self, div: &Self) -> (Choice, Self) {
+        sqrt_ratio(num, div, Self([Goldilocks::ZERO, Goldilocks::ONE]))
+    }
+}
+
+impl ExtensionField for GoldilocksExt2 {
+    const DEGREE: usize = 2;
+
+    type BaseField = Goldilocks;
+
+    fn from_bases(bases: &[Goldilocks]) -> Self {
+        assert!(bases.len() <= 2, "too many coefficients for GoldilocksExt2");
+        let mut res = Self::ZERO;
+        res.0[..bases.len()].copy_from_slice(bases);
+        res
+    }
+
+    fn as_bases(&self) -> &[Goldilocks] {
+        &self.0
+    }
+
+    /// The conjugate `a_0 - a_1 x`, as x^p = W^((p - 1) / 2) x = -x.
+    fn frobenius(&self) -> Self {
+        Self([self.0[0], -self.0[1]])
+    }
+
+    fn norm(&self) -> Goldilocks {
+        let [a0, a1] = self.0;
+        a0.square() - W * a1.square()
+    }
+}
+
+impl Neg for GoldilocksExt2 {
+    type Output = Self;
+
+    #[inline]
+    fn neg(self) -> Self {
+        Self([-self.0[0], -self.0[1]])
+    }
+}
+
+impl Add for GoldilocksExt2 {
+    type Output = Self;
+
+    #[inline]
+    fn add(self, rhs: Self) -> Self::Output {
+        Self([self.0[0] + rhs.0[0], self.0[1] + rhs.0[1]])
+    }
+}
+
+impl Sub for GoldilocksExt2 {
+    type Output = Self;
+
+    #[inline]
+    fn sub(self, rhs: Self) -> Self {
+        Self([self.0[0] - rhs.0[0], self.0[1] - rhs.0[1]])
+    }
+}
+
+impl Mul for GoldilocksExt2 {
+    type Output = Self;
+
+    /// Karatsuba multiplication, with three base field multiplications and a
+    /// multiplication by W.
+    #[inline]
+    fn mul(self, rhs: Self) -> Self {
+        let [a0, a1] = self.0;
+        let [b0, b1] = rhs.0;
+        let v0 = a0 * b0;
+        let v1 = a1 * b1;
+        Self([v0 + W * v1, (a0 + a1) * (b0 + b1) - v0 - v1])
+    }
+}
+
+impl_extension_ops!(GoldilocksExt2, Goldilocks);
@@ -0,0 +1,183 @@
+use crate::extension::{sqrt_ratio, sqrt_tonelli_shanks, ExtensionField};
+use crate::fp::Goldilocks;
+use core::ops::{Add, Mul, Neg, Sub};
+use ff::{Field, PrimeField};
+use rand_core::RngCore;
+use serde::{Deserialize, Serialize};
+use subtle::{Choice, CtOption};
+
+/// Cubic extension of the Goldilocks field, GF(p)[x] / (x^3 - x - 1).
+/// An element `a_0 + a_1 x + a_2 x^2` is stored as `[a_0, a_1, a_2]`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GoldilocksExt3(pub(crate) [Goldilocks; 3]);
+
+/// x^p
+const FROBENIUS_X: GoldilocksExt3 = GoldilocksExt3([
+    Goldilocks(0x93528dfff4c6373d),
+    Goldilocks(0x8b79bf80a6bdfc48),
+    Goldilocks(0xa3042afe90d6ad26),
+]);
+
+/// x^(2p)
+const FROBENIUS_X2: GoldilocksExt3 = GoldilocksExt3([
+    Goldilocks(0x5cfbd5006f2952dc),
+    Goldilocks(0xc9a946ff7a631b9f),
+    Goldilocks(0x7486407e594203b8),
+]);
+
+/// (t - 1) / 2 where p^3 - 1 = 2^32 * t
+const TM1D2: [u64; 3] = [0x80000002fffffffe, 0x80000002fffffffc, 0x7ffffffe];
+
+impl GoldilocksExt3 {
+    pub const fn new(a0: Goldilocks, a1: Goldilocks, a2: Goldilocks) -> Self {
+        Self([a0, a1, a2])
+    }
+}
+
+impl Field for GoldilocksExt3 {
+    /// The zero element of the field, the additive identity.
+    const ZERO: Self = Self([Goldilocks::ZERO; 3]);
+
+    /// The one element of the field, the multiplicative identity.
+    const ONE: Self = Self([Goldilocks::ONE, Goldilocks::ZERO, Goldilocks::ZERO]);
+
+    /// Returns an element chosen uniformly at random using a user-provided RNG.
+    /// Note: this sampler is not constant time!
+    fn random(mut rng: impl RngCore) -> Self {
+        Self([
+            Goldilocks::random(&mut rng),
+            Goldilocks::random(&mut rng),
+            Goldilocks::random(&mut rng),
+        ])
+    }
+
+    /// Squares this element.
+    #[must_use]
+    fn square(&self) -> Self {
+        let [a0, a1, a2] = self.0;
+        let a0a1 = (a0 * a1).double();
+        let a0a2 = (a0 * a2).double();
+        let a1a2 = (a1 * a2).double();
+        let a2a2 = a2.square();
+        // reduce a_0^2 + 2 a_0 a_1 x + (a_1^2 + 2 a_0 a_2) x^2 + 2 a_1 a_2 x^3 + a_2^2 x^4
+        Self([
+            a0.square() + a1a2,
+            a0a1 + a1a2 + a2a2,
+            a1.square() + a0a2 + a2a2,
+        ])
+    }
+
+    /// Doubles this element.
+    #[must_use]
+    fn double(&self) -> Self {
+        *self + *self
+    }
+
+    /// Computes the multiplicative inverse of this element,
+    /// failing if the element is zero.
+    fn invert(&self) -> CtOption<Self> {
+        // 1 / a = a^p a^(p^2) / N(a), where N(a) = a a^p a^(p^2) is in the base field
+        let frobenius = self.frobenius();
+        let conjugates = frobenius * frobenius.frobenius();
+        let norm = (*self * conjugates).0[0];
+        norm.invert().map(|norm_inv| conjugates * norm_inv)
+    }
+
+    /// Returns the square root of the field element, if it is
+    /// quadratic residue.
+    /// Note: this is not constant time!
+    fn sqrt(&self) -> CtOption<Self> {
+        // p^3 - 1 has the same two-adicity as p - 1, so the 2^32 root of unity
+        // of the base field is one of the extension too
+        sqrt_tonelli_shanks(self, &TM1D2, 32, Goldilocks::ROOT_OF_UNITY.into())
+    }
+
+    /// Computes the square root of `num / div`, see `Field::sqrt_ratio`.
+    /// The non-square used when `num / div` is not a square is the generator
+    /// of the base field, which stays a non-square in an extension of odd
+    /// degree.
+    fn sqrt_ratio(num: &Self, div: &Self) -> (Choice, Self) {
+        sqrt_ratio(num, div, Goldilocks::MULTIPLICATIVE_GENERATOR.into())
+    }
+}
+
+impl ExtensionField for GoldilocksExt3 {
+    const DEGREE: usize = 3;
+
+    type BaseField = Goldilocks;
+
+    fn from_bases(bases: &[Goldilocks]) -> Self {
+        assert!(bases.len() <= 3, "too many coefficients for GoldilocksExt3");
+        let mut res = Self::ZERO;
+        res.0[..bases.len()].copy_from_slice(bases);
+        res
+    }
+
+    fn as_bases(&self) -> &[Goldilocks] {
+        &self.0
+    }
+
+    /// `a_0 + a_1 x^p + a_2 x^(2p)`, as the Frobenius map fixes the base field.
+    fn frobenius(&self) -> Self {
+        let [a0, a1, a2] = self.0;
+        FROBENIUS_X * a1 + FROBENIUS_X2 * a2 + a0
+    }
+}
+
+impl Neg for GoldilocksExt3 {
+    type Output = Self;
+
+    #[inline]
+    fn neg(self) -> Self {
+        Self([-self.0[0], -self.0[1], -self.0[2]])
+    }
+}
+
+impl Add for GoldilocksExt3 {
+    type Output = Self;
+
+    #[inline]
+    fn add(self, rhs: Self) -> Self::Output {
+        Self([
+            self.0[0] + rhs.0[0],
+            self.0[1] + rhs.0[1],
+            self.0[2] + rhs.0[2],
+        ])
+    }
+}
+
+impl Sub for GoldilocksExt3 {
+    type Output = Self;
+
+    #[inline]
+    fn sub(self, rhs: Self) -> Self {
+        Self([
+            self.0[0] - rhs.0[0],
+            self.0[1] - rhs.0[1],
+            self.0[2] - rhs.0[2],
+        ])
+    }
+}
+
+impl Mul for GoldilocksExt3 {
+    type Output = Self;
+
+    /// Karatsuba multiplication, with six base field multiplications, followed
+    /// by the reduction x^3 = x + 1 and x^4 = x^2 + x.
+    #[inline]
+    fn mul(self, rhs: Self) -> Self {
+        let [a0, a1, a2] = self.0;
+        let [b0, b1, b2] = rhs.0;
+        let v0 = a0 * b0;
+        let v1 = a1 * b1;
+        let v2 = a2 * b2;
+
+        let c1 = (a0 + a1) * (b0 + b1) - v0 - v1;
+        let c2 = (a0 + a2) * (b0 + b2) - v0 - v2 + v1;
+        let c3 = (a1 + a2) * (b1 + b2) - v1 - v2;
+
+        Self([v0 + c3, c1 + c3 + v2, c2 + v2])
+    }
+}
+
+impl_extension_ops!(GoldilocksExt3, Goldilocks);
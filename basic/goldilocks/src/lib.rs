@@ -1,12 +1,38 @@
-//! This crate implements Goldilocks field with modulus 2^64 - 2^32 + 1
+//! This crate implements Goldilocks field with modulus 2^64 - 2^32 + 1,
+//! and its quadratic and cubic extensions.
 //! Credit: the majority of the code is borrowed or inspired from Plonky2 with modifications.
 
+// The AVX-512 intrinsics are unstable on the nightly toolchain the workspace
+// is pinned to.
+#![cfg_attr(
+    target_arch = "x86_64",
+    feature(stdarch_x86_avx512, avx512_target_feature)
+)]
+
+pub use extension::ExtensionField;
 pub use fp::Goldilocks;
+pub use fp2::GoldilocksExt2;
+pub use fp3::GoldilocksExt3;
 pub use field::SmallField;
 
+// for the exported macros of `extension`, so that their users do not need
+// these crates as direct dependencies
+#[doc(hidden)]
+pub use {ff, subtle};
+
+#[cfg(feature = "ark")]
+pub mod ark;
+#[cfg(any(test, feature = "test-utils"))]
+pub mod conformance;
+#[macro_use]
+pub mod extension;
 mod fp;
+mod fp2;
+mod fp3;
+pub mod ntt;
+pub mod packed;
 mod util;
 
 #[cfg(test)]
 mod tests;
-mod field;
\ No newline at end of file
+mod field;
@@ -0,0 +1,169 @@
+//! Radix-2 number theoretic transforms over the two-adic subgroups of a
+//! `SmallField`, i.e. the subgroups of order `2^k` for `k <= F::S`, generated
+//! by `F::ROOT_OF_UNITY^(2^(S - k))`.
+//!
+//! The transforms take and return values in natural order: the forward NTT
+//! maps the coefficients `c_j` of a polynomial of degree `< n` to its
+//! evaluations `\sum_j c_j w^(ij)` at the powers `w^i` of the generator `w`
+//! of the subgroup of order `n`.
+
+use rayon::prelude::{
+    IndexedParallelIterator, IntoParallelRefMutIterator, ParallelIterator, ParallelSliceMut,
+};
+
+use crate::SmallField;
+
+/// Minimum number of butterflies or multiplications processed by a rayon
+/// task.
+const MIN_LEN: usize = 1 << 10;
+
+/// The generator of the subgroup of order `n`.
+pub fn root_of_unity<F: SmallField>(n: usize) -> F {
+    let log_n = log2_size::<F>(n);
+    let mut root = F::ROOT_OF_UNITY;
+    for _ in log_n..F::S {
+        root = root.square();
+    }
+    root
+}
+
+/// Turn the coefficients of a polynomial into its evaluations over the
+/// subgroup of order `values.len()`, in place.
+pub fn ntt<F: SmallField>(values: &mut [F]) {
+    let root = root_of_unity(values.len());
+    transform(values, root);
+}
+
+/// Turn the evaluations of a polynomial over the subgroup of order
+/// `values.len()` into its coefficients, in place. This inverts `ntt`.
+pub fn intt<F: SmallField>(values: &mut [F]) {
+    let root_inv = root_of_unity::<F>(values.len()).invert().unwrap();
+    transform(values, root_inv);
+    let n_inv = F::from(values.len() as u64).invert().unwrap();
+    values
+        .par_iter_mut()
+        .with_min_len(MIN_LEN)
+        .for_each(|value| *value *= n_inv);
+}
+
+/// Turn the coefficients of a polynomial into its evaluations over the coset
+/// `shift * H` of the subgroup `H` of order `values.len()`, in place.
+pub fn coset_ntt<F: SmallField>(values: &mut [F], shift: F) {
+    // f(shift * x) has the coefficients c_j shift^j
+    scale_by_powers(values, shift);
+    ntt(values);
+}
+
+/// Turn the evaluations of a polynomial over the coset `shift * H` into its
+/// coefficients, in place. This inverts `coset_ntt`.
+pub fn coset_intt<F: SmallField>(values: &mut [F], shift: F) {
+    intt(values);
+    scale_by_powers(values, shift.invert().unwrap());
+}
+
+/// Extend the evaluations of a polynomial over the subgroup `H` of order
+/// `evaluations.len()` to its evaluations over the coset `g * H'`, where `H'`
+/// has order `blowup` times that of `H` and `g` is
+/// `F::MULTIPLICATIVE_GENERATOR`, so that the coset is disjoint from `H'`.
+/// `blowup` must be a power of two.
+pub fn low_degree_extension<F: SmallField>(evaluations: &[F], blowup: usize) -> Vec<F> {
+    assert!(
+        blowup.is_power_of_two(),
+        "blowup factor {} should be a power of two",
+        blowup
+    );
+    let mut values = evaluations.to_vec();
+    intt(&mut values);
+    values.resize(evaluations.len() * blowup, F::ZERO);
+    coset_ntt(&mut values, F::MULTIPLICATIVE_GENERATOR);
+    values
+}
+
+/// log2 of the size of a transform, checking that the field has a subgroup
+/// of that order.
+fn log2_size<F: SmallField>(n: usize) -> u32 {
+    assert!(
+        n.is_power_of_two(),
+        "the size {} of a transform should be a power of two",
+        n
+    );
+    let log_n = n.trailing_zeros();
+    assert!(
+        log_n <= F::S,
+        "the size 2^{} of a transform exceeds the two-adicity 2^{} of the field",
+        log_n,
+        F::S
+    );
+    log_n
+}
+
+/// Multiply `values[i]` by `x^i`.
+fn scale_by_powers<F: SmallField>(values: &mut [F], x: F) {
+    values
+        .par_chunks_mut(MIN_LEN)
+        .enumerate()
+        .for_each(|(chunk, values)| {
+            let mut power = x.pow_vartime([(chunk * MIN_LEN) as u64]);
+            values.iter_mut().for_each(|value| {
+                *value *= power;
+                power *= x;
+            });
+        });
+}
+
+/// Evaluate at the powers of `root`, of order `values.len()`: a decimation in
+/// time on the bit reversed values.
+fn transform<F: SmallField>(values: &mut [F], root: F) {
+    let n = values.len();
+    let log_n = log2_size::<F>(n);
+    if n == 1 {
+        return;
+    }
+
+    bit_reverse(values, log_n);
+
+    // twiddles[j] = root^j; a block of size m uses the m-th root root^(n / m)
+    let mut twiddles = vec![F::ONE; n / 2];
+    scale_by_powers(&mut twiddles, root);
+
+    let mut half = 1;
+    while half < n {
+        let stride = n / (2 * half);
+        values
+            .par_chunks_mut(2 * half)
+            .with_min_len(MIN_LEN / half + 1)
+            .for_each(|block| {
+                let (low, high) = block.split_at_mut(half);
+                let butterfly = |(j, (low, high)): (usize, (&mut F, &mut F))| {
+                    let t = *high * twiddles[j * stride];
+                    *high = *low - t;
+                    *low += t;
+                };
+                if half >= MIN_LEN {
+                    low.par_iter_mut()
+                        .zip(high.par_iter_mut())
+                        .enumerate()
+                        .with_min_len(MIN_LEN)
+                        .for_each(butterfly);
+                } else {
+                    low.iter_mut()
+                        .zip(high.iter_mut())
+                        .enumerate()
+                        .for_each(butterfly);
+                }
+            });
+        half <<= 1;
+    }
+}
+
+/// Permute `values`, of size `2^log_n`, into bit reversed order.
+fn bit_reverse<F: SmallField>(values: &mut [F], log_n: u32) {
+    let copy = values.to_vec();
+    values
+        .par_iter_mut()
+        .with_min_len(MIN_LEN)
+        .enumerate()
+        .for_each(|(i, value)| {
+            *value = copy[i.reverse_bits() >> (usize::BITS - log_n)];
+        });
+}
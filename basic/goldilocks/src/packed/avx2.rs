@@ -0,0 +1,157 @@
+//! AVX2 implementation, two registers of 4 lanes.
+
+use core::arch::x86_64::*;
+
+use super::{PackedArith, PackedGoldilocks, PackedKernel};
+use crate::fp::{EPSILON, MODULUS};
+
+const SIGN_BIT: i64 = i64::MIN;
+
+#[inline]
+#[target_feature(enable = "avx2")]
+unsafe fn load(a: &PackedGoldilocks) -> [__m256i; 2] {
+    let ptr = a.0.as_ptr() as *const __m256i;
+    [_mm256_load_si256(ptr), _mm256_load_si256(ptr.add(1))]
+}
+
+#[inline]
+#[target_feature(enable = "avx2")]
+unsafe fn store(x: [__m256i; 2]) -> PackedGoldilocks {
+    let mut res = PackedGoldilocks::ZERO;
+    let ptr = res.0.as_mut_ptr() as *mut __m256i;
+    _mm256_store_si256(ptr, x[0]);
+    _mm256_store_si256(ptr.add(1), x[1]);
+    res
+}
+
+/// All ones in the lanes where a < b as unsigned integers. AVX2 only
+/// compares signed integers, so both sides are shifted by 2^63.
+#[inline]
+#[target_feature(enable = "avx2")]
+unsafe fn cmplt_epu64(a: __m256i, b: __m256i) -> __m256i {
+    let sign_bit = _mm256_set1_epi64x(SIGN_BIT);
+    _mm256_cmpgt_epi64(_mm256_xor_si256(b, sign_bit), _mm256_xor_si256(a, sign_bit))
+}
+
+/// Subtract p from the lanes that are at least p.
+#[inline]
+#[target_feature(enable = "avx2")]
+unsafe fn canonicalize(x: __m256i) -> __m256i {
+    let p = _mm256_set1_epi64x(MODULUS as i64);
+    let epsilon = _mm256_set1_epi64x(EPSILON as i64);
+    let geq_p = _mm256_xor_si256(cmplt_epu64(x, p), _mm256_set1_epi64x(-1));
+    // x - p = x + EPSILON (mod 2^64)
+    _mm256_add_epi64(x, _mm256_and_si256(geq_p, epsilon))
+}
+
+/// a + b, where b is canonical so that the sum wraps around at most once.
+#[inline]
+#[target_feature(enable = "avx2")]
+unsafe fn add_canonical(a: __m256i, b: __m256i) -> __m256i {
+    let epsilon = _mm256_set1_epi64x(EPSILON as i64);
+    let sum = _mm256_add_epi64(a, b);
+    let carry = cmplt_epu64(sum, a);
+    // 2^64 = EPSILON (mod p)
+    _mm256_add_epi64(sum, _mm256_and_si256(carry, epsilon))
+}
+
+#[inline]
+#[target_feature(enable = "avx2")]
+unsafe fn sub_one(a: __m256i, b: __m256i) -> __m256i {
+    let epsilon = _mm256_set1_epi64x(EPSILON as i64);
+    let b = canonicalize(b);
+    let diff = _mm256_sub_epi64(a, b);
+    let borrow = cmplt_epu64(a, b);
+    // cannot underflow as b < p
+    _mm256_sub_epi64(diff, _mm256_and_si256(borrow, epsilon))
+}
+
+#[inline]
+#[target_feature(enable = "avx2")]
+unsafe fn mul_one(a: __m256i, b: __m256i) -> __m256i {
+    let epsilon = _mm256_set1_epi64x(EPSILON as i64);
+
+    // the 128 bits product from the 32 bits halves of a and b
+    let a_hi = _mm256_srli_epi64(a, 32);
+    let b_hi = _mm256_srli_epi64(b, 32);
+    let lo_lo = _mm256_mul_epu32(a, b);
+    let lo_hi = _mm256_mul_epu32(a, b_hi);
+    let hi_lo = _mm256_mul_epu32(a_hi, b);
+    let hi_hi = _mm256_mul_epu32(a_hi, b_hi);
+    let t = _mm256_add_epi64(hi_lo, _mm256_srli_epi64(lo_lo, 32));
+    let u = _mm256_add_epi64(lo_hi, _mm256_and_si256(t, epsilon));
+    let lo = _mm256_or_si256(_mm256_slli_epi64(u, 32), _mm256_and_si256(lo_lo, epsilon));
+    let hi = _mm256_add_epi64(
+        hi_hi,
+        _mm256_add_epi64(_mm256_srli_epi64(t, 32), _mm256_srli_epi64(u, 32)),
+    );
+
+    // reduce as reduce128: lo - hi_hi + hi_lo * EPSILON, with 2^96 = -1 and
+    // 2^64 = EPSILON
+    let hi_hi = _mm256_srli_epi64(hi, 32);
+    let hi_lo = _mm256_and_si256(hi, epsilon);
+    let t0 = _mm256_sub_epi64(lo, hi_hi);
+    let borrow = cmplt_epu64(lo, hi_hi);
+    let t0 = _mm256_sub_epi64(t0, _mm256_and_si256(borrow, epsilon));
+    let t1 = _mm256_sub_epi64(_mm256_slli_epi64(hi_lo, 32), hi_lo);
+    let sum = _mm256_add_epi64(t0, t1);
+    let carry = cmplt_epu64(sum, t0);
+    _mm256_add_epi64(sum, _mm256_and_si256(carry, epsilon))
+}
+
+#[inline]
+#[target_feature(enable = "avx2")]
+pub(super) unsafe fn add(a: &PackedGoldilocks, b: &PackedGoldilocks) -> PackedGoldilocks {
+    let [a0, a1] = load(a);
+    let [b0, b1] = load(b);
+    store([
+        add_canonical(a0, canonicalize(b0)),
+        add_canonical(a1, canonicalize(b1)),
+    ])
+}
+
+#[inline]
+#[target_feature(enable = "avx2")]
+pub(super) unsafe fn sub(a: &PackedGoldilocks, b: &PackedGoldilocks) -> PackedGoldilocks {
+    let [a0, a1] = load(a);
+    let [b0, b1] = load(b);
+    store([sub_one(a0, b0), sub_one(a1, b1)])
+}
+
+#[inline]
+#[target_feature(enable = "avx2")]
+pub(super) unsafe fn mul(a: &PackedGoldilocks, b: &PackedGoldilocks) -> PackedGoldilocks {
+    let [a0, a1] = load(a);
+    let [b0, b1] = load(b);
+    store([mul_one(a0, b0), mul_one(a1, b1)])
+}
+
+/// The arithmetic of this backend; only created by `run`, once the CPU is
+/// known to support it.
+#[derive(Clone, Copy)]
+struct Arith(());
+
+impl PackedArith for Arith {
+    #[inline(always)]
+    fn add(self, a: PackedGoldilocks, b: PackedGoldilocks) -> PackedGoldilocks {
+        // SAFETY: `Arith` exists, so the CPU supports the backend
+        unsafe { add(&a, &b) }
+    }
+
+    #[inline(always)]
+    fn sub(self, a: PackedGoldilocks, b: PackedGoldilocks) -> PackedGoldilocks {
+        // SAFETY: `Arith` exists, so the CPU supports the backend
+        unsafe { sub(&a, &b) }
+    }
+
+    #[inline(always)]
+    fn mul(self, a: PackedGoldilocks, b: PackedGoldilocks) -> PackedGoldilocks {
+        // SAFETY: `Arith` exists, so the CPU supports the backend
+        unsafe { mul(&a, &b) }
+    }
+}
+
+#[target_feature(enable = "avx2")]
+pub(super) unsafe fn run<K: PackedKernel>(kernel: K) -> K::Output {
+    kernel.run(Arith(()))
+}
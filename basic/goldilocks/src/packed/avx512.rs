@@ -0,0 +1,123 @@
+//! AVX-512 implementation, one register of 8 lanes.
+
+use core::arch::x86_64::*;
+
+use super::{PackedArith, PackedGoldilocks, PackedKernel};
+use crate::fp::{EPSILON, MODULUS};
+
+#[inline]
+#[target_feature(enable = "avx512f")]
+unsafe fn load(a: &PackedGoldilocks) -> __m512i {
+    _mm512_load_si512(a.0.as_ptr() as *const _)
+}
+
+#[inline]
+#[target_feature(enable = "avx512f")]
+unsafe fn store(x: __m512i) -> PackedGoldilocks {
+    let mut res = PackedGoldilocks::ZERO;
+    _mm512_store_si512(res.0.as_mut_ptr() as *mut _, x);
+    res
+}
+
+/// Subtract p from the lanes that are at least p.
+#[inline]
+#[target_feature(enable = "avx512f")]
+unsafe fn canonicalize(x: __m512i) -> __m512i {
+    let p = _mm512_set1_epi64(MODULUS as i64);
+    let geq_p = _mm512_cmpge_epu64_mask(x, p);
+    _mm512_mask_sub_epi64(x, geq_p, x, p)
+}
+
+/// a + b, where b is canonical so that the sum wraps around at most once.
+#[inline]
+#[target_feature(enable = "avx512f")]
+unsafe fn add_canonical(a: __m512i, b: __m512i) -> __m512i {
+    let epsilon = _mm512_set1_epi64(EPSILON as i64);
+    let sum = _mm512_add_epi64(a, b);
+    let carry = _mm512_cmplt_epu64_mask(sum, a);
+    // 2^64 = EPSILON (mod p)
+    _mm512_mask_add_epi64(sum, carry, sum, epsilon)
+}
+
+#[inline]
+#[target_feature(enable = "avx512f")]
+pub(super) unsafe fn add(a: &PackedGoldilocks, b: &PackedGoldilocks) -> PackedGoldilocks {
+    store(add_canonical(load(a), canonicalize(load(b))))
+}
+
+#[inline]
+#[target_feature(enable = "avx512f")]
+pub(super) unsafe fn sub(a: &PackedGoldilocks, b: &PackedGoldilocks) -> PackedGoldilocks {
+    let epsilon = _mm512_set1_epi64(EPSILON as i64);
+    let a = load(a);
+    let b = canonicalize(load(b));
+    let diff = _mm512_sub_epi64(a, b);
+    let borrow = _mm512_cmplt_epu64_mask(a, b);
+    // cannot underflow as b < p
+    store(_mm512_mask_sub_epi64(diff, borrow, diff, epsilon))
+}
+
+#[inline]
+#[target_feature(enable = "avx512f")]
+pub(super) unsafe fn mul(a: &PackedGoldilocks, b: &PackedGoldilocks) -> PackedGoldilocks {
+    let epsilon = _mm512_set1_epi64(EPSILON as i64);
+    let a = load(a);
+    let b = load(b);
+
+    // the 128 bits product from the 32 bits halves of a and b
+    let a_hi = _mm512_srli_epi64(a, 32);
+    let b_hi = _mm512_srli_epi64(b, 32);
+    let lo_lo = _mm512_mul_epu32(a, b);
+    let lo_hi = _mm512_mul_epu32(a, b_hi);
+    let hi_lo = _mm512_mul_epu32(a_hi, b);
+    let hi_hi = _mm512_mul_epu32(a_hi, b_hi);
+    let t = _mm512_add_epi64(hi_lo, _mm512_srli_epi64(lo_lo, 32));
+    let u = _mm512_add_epi64(lo_hi, _mm512_and_si512(t, epsilon));
+    let lo = _mm512_or_si512(_mm512_slli_epi64(u, 32), _mm512_and_si512(lo_lo, epsilon));
+    let hi = _mm512_add_epi64(
+        hi_hi,
+        _mm512_add_epi64(_mm512_srli_epi64(t, 32), _mm512_srli_epi64(u, 32)),
+    );
+
+    // reduce as reduce128: lo - hi_hi + hi_lo * EPSILON, with 2^96 = -1 and
+    // 2^64 = EPSILON
+    let hi_hi = _mm512_srli_epi64(hi, 32);
+    let hi_lo = _mm512_and_si512(hi, epsilon);
+    let t0 = _mm512_sub_epi64(lo, hi_hi);
+    let borrow = _mm512_cmplt_epu64_mask(lo, hi_hi);
+    let t0 = _mm512_mask_sub_epi64(t0, borrow, t0, epsilon);
+    let t1 = _mm512_sub_epi64(_mm512_slli_epi64(hi_lo, 32), hi_lo);
+    let sum = _mm512_add_epi64(t0, t1);
+    let carry = _mm512_cmplt_epu64_mask(sum, t0);
+    store(_mm512_mask_add_epi64(sum, carry, sum, epsilon))
+}
+
+/// The arithmetic of this backend; only created by `run`, once the CPU is
+/// known to support it.
+#[derive(Clone, Copy)]
+struct Arith(());
+
+impl PackedArith for Arith {
+    #[inline(always)]
+    fn add(self, a: PackedGoldilocks, b: PackedGoldilocks) -> PackedGoldilocks {
+        // SAFETY: `Arith` exists, so the CPU supports the backend
+        unsafe { add(&a, &b) }
+    }
+
+    #[inline(always)]
+    fn sub(self, a: PackedGoldilocks, b: PackedGoldilocks) -> PackedGoldilocks {
+        // SAFETY: `Arith` exists, so the CPU supports the backend
+        unsafe { sub(&a, &b) }
+    }
+
+    #[inline(always)]
+    fn mul(self, a: PackedGoldilocks, b: PackedGoldilocks) -> PackedGoldilocks {
+        // SAFETY: `Arith` exists, so the CPU supports the backend
+        unsafe { mul(&a, &b) }
+    }
+}
+
+#[target_feature(enable = "avx512f")]
+pub(super) unsafe fn run<K: PackedKernel>(kernel: K) -> K::Output {
+    kernel.run(Arith(()))
+}
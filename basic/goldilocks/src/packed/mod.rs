@@ -0,0 +1,293 @@
+//! Goldilocks arithmetic on `WIDTH` lanes at once, with AVX-512 and AVX2
+//! implementations selected at runtime by CPU feature detection, and a
+//! portable fallback.
+//!
+//! Like `Goldilocks`, a lane may hold a non-canonical value; every backend
+//! accepts and returns any `u64` in a lane.
+//!
+//! The operators on `PackedGoldilocks` pick the backend on every call, which
+//! is fine for a few operations. A hot loop is written as a `PackedKernel`
+//! instead and run with `Backend::run`, which compiles it once per backend
+//! with the backend's CPU features enabled.
+
+use core::any::TypeId;
+use core::ops::{Add, AddAssign, Mul, MulAssign, Neg, Sub, SubAssign};
+use std::sync::OnceLock;
+
+use ff::Field;
+
+use crate::fp::Goldilocks;
+
+#[cfg(target_arch = "x86_64")]
+mod avx2;
+#[cfg(target_arch = "x86_64")]
+mod avx512;
+mod portable;
+
+/// Number of lanes of a `PackedGoldilocks`, i.e. one AVX-512 register.
+pub const WIDTH: usize = 8;
+
+/// The implementation of the packed arithmetic.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Backend {
+    Avx512,
+    Avx2,
+    Portable,
+}
+
+impl Backend {
+    /// The fastest backend supported by the CPU, detected once.
+    pub fn detect() -> Self {
+        static BACKEND: OnceLock<Backend> = OnceLock::new();
+        *BACKEND.get_or_init(|| {
+            [Backend::Avx512, Backend::Avx2]
+                .into_iter()
+                .find(|backend| backend.is_supported())
+                .unwrap_or(Backend::Portable)
+        })
+    }
+
+    /// Whether the CPU supports this backend.
+    pub fn is_supported(self) -> bool {
+        match self {
+            #[cfg(target_arch = "x86_64")]
+            Backend::Avx512 => is_x86_feature_detected!("avx512f"),
+            #[cfg(target_arch = "x86_64")]
+            Backend::Avx2 => is_x86_feature_detected!("avx2"),
+            Backend::Portable => true,
+            #[allow(unreachable_patterns)]
+            _ => false,
+        }
+    }
+
+    /// Run `kernel` with the arithmetic of this backend.
+    /// Panics if the CPU does not support the backend.
+    pub fn run<K: PackedKernel>(self, kernel: K) -> K::Output {
+        assert!(self.is_supported(), "{:?} is not supported", self);
+        match self {
+            // SAFETY: the CPU supports the backend
+            #[cfg(target_arch = "x86_64")]
+            Backend::Avx512 => unsafe { avx512::run(kernel) },
+            #[cfg(target_arch = "x86_64")]
+            Backend::Avx2 => unsafe { avx2::run(kernel) },
+            _ => portable::run(kernel),
+        }
+    }
+}
+
+/// The packed arithmetic of a backend, as passed to `PackedKernel::run`.
+pub trait PackedArith: Copy {
+    fn add(self, a: PackedGoldilocks, b: PackedGoldilocks) -> PackedGoldilocks;
+
+    fn sub(self, a: PackedGoldilocks, b: PackedGoldilocks) -> PackedGoldilocks;
+
+    fn mul(self, a: PackedGoldilocks, b: PackedGoldilocks) -> PackedGoldilocks;
+}
+
+/// A loop over packed elements, run with `Backend::run`.
+///
+/// `run` is called from a function with the CPU features of the backend
+/// enabled, and should be `#[inline(always)]` so that it is compiled with
+/// them and the arithmetic of `arith` is inlined into it.
+pub trait PackedKernel {
+    type Output;
+
+    fn run<A: PackedArith>(self, arith: A) -> Self::Output;
+}
+
+/// `WIDTH` Goldilocks elements, operated on lane by lane.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[repr(C, align(64))]
+pub struct PackedGoldilocks(pub [Goldilocks; WIDTH]);
+
+impl PackedGoldilocks {
+    pub const ZERO: Self = Self([Goldilocks::ZERO; WIDTH]);
+
+    pub const ONE: Self = Self([Goldilocks::ONE; WIDTH]);
+
+    /// Every lane set to `x`.
+    #[inline]
+    pub const fn broadcast(x: Goldilocks) -> Self {
+        Self([x; WIDTH])
+    }
+
+    /// The lanes from a slice of exactly `WIDTH` elements.
+    #[inline]
+    pub fn from_slice(slice: &[Goldilocks]) -> Self {
+        Self(slice.try_into().expect("slice length should be WIDTH"))
+    }
+
+    /// The even and the odd entries of a slice of `2 * WIDTH` elements, i.e.
+    /// the evaluations at 0 and at 1 of the lowest variable of a table.
+    #[inline]
+    pub fn deinterleave(slice: &[Goldilocks]) -> (Self, Self) {
+        assert_eq!(slice.len(), 2 * WIDTH, "slice length should be 2 * WIDTH");
+        (
+            Self(core::array::from_fn(|i| slice[i << 1])),
+            Self(core::array::from_fn(|i| slice[(i << 1) + 1])),
+        )
+    }
+
+    #[inline]
+    pub fn as_slice(&self) -> &[Goldilocks] {
+        &self.0
+    }
+
+    #[inline]
+    pub fn as_mut_slice(&mut self) -> &mut [Goldilocks] {
+        &mut self.0
+    }
+
+    /// The sum of the lanes.
+    #[inline]
+    pub fn sum(&self) -> Goldilocks {
+        self.0.iter().sum()
+    }
+
+    pub(crate) fn add_with(self, rhs: Self, backend: Backend) -> Self {
+        match backend {
+            #[cfg(target_arch = "x86_64")]
+            Backend::Avx512 => unsafe { avx512::add(&self, &rhs) },
+            #[cfg(target_arch = "x86_64")]
+            Backend::Avx2 => unsafe { avx2::add(&self, &rhs) },
+            _ => portable::add(&self, &rhs),
+        }
+    }
+
+    pub(crate) fn sub_with(self, rhs: Self, backend: Backend) -> Self {
+        match backend {
+            #[cfg(target_arch = "x86_64")]
+            Backend::Avx512 => unsafe { avx512::sub(&self, &rhs) },
+            #[cfg(target_arch = "x86_64")]
+            Backend::Avx2 => unsafe { avx2::sub(&self, &rhs) },
+            _ => portable::sub(&self, &rhs),
+        }
+    }
+
+    pub(crate) fn mul_with(self, rhs: Self, backend: Backend) -> Self {
+        match backend {
+            #[cfg(target_arch = "x86_64")]
+            Backend::Avx512 => unsafe { avx512::mul(&self, &rhs) },
+            #[cfg(target_arch = "x86_64")]
+            Backend::Avx2 => unsafe { avx2::mul(&self, &rhs) },
+            _ => portable::mul(&self, &rhs),
+        }
+    }
+}
+
+impl From<Goldilocks> for PackedGoldilocks {
+    fn from(x: Goldilocks) -> Self {
+        Self::broadcast(x)
+    }
+}
+
+impl Add for PackedGoldilocks {
+    type Output = Self;
+
+    #[inline]
+    fn add(self, rhs: Self) -> Self {
+        self.add_with(rhs, Backend::detect())
+    }
+}
+
+impl Sub for PackedGoldilocks {
+    type Output = Self;
+
+    #[inline]
+    fn sub(self, rhs: Self) -> Self {
+        self.sub_with(rhs, Backend::detect())
+    }
+}
+
+impl Mul for PackedGoldilocks {
+    type Output = Self;
+
+    #[inline]
+    fn mul(self, rhs: Self) -> Self {
+        self.mul_with(rhs, Backend::detect())
+    }
+}
+
+impl Neg for PackedGoldilocks {
+    type Output = Self;
+
+    #[inline]
+    fn neg(self) -> Self {
+        Self::ZERO - self
+    }
+}
+
+impl Add<Goldilocks> for PackedGoldilocks {
+    type Output = Self;
+
+    #[inline]
+    fn add(self, rhs: Goldilocks) -> Self {
+        self + Self::broadcast(rhs)
+    }
+}
+
+impl Sub<Goldilocks> for PackedGoldilocks {
+    type Output = Self;
+
+    #[inline]
+    fn sub(self, rhs: Goldilocks) -> Self {
+        self - Self::broadcast(rhs)
+    }
+}
+
+impl Mul<Goldilocks> for PackedGoldilocks {
+    type Output = Self;
+
+    #[inline]
+    fn mul(self, rhs: Goldilocks) -> Self {
+        self * Self::broadcast(rhs)
+    }
+}
+
+impl AddAssign for PackedGoldilocks {
+    #[inline]
+    fn add_assign(&mut self, rhs: Self) {
+        *self = *self + rhs;
+    }
+}
+
+impl SubAssign for PackedGoldilocks {
+    #[inline]
+    fn sub_assign(&mut self, rhs: Self) {
+        *self = *self - rhs;
+    }
+}
+
+impl MulAssign for PackedGoldilocks {
+    #[inline]
+    fn mul_assign(&mut self, rhs: Self) {
+        *self = *self * rhs;
+    }
+}
+
+impl MulAssign<Goldilocks> for PackedGoldilocks {
+    #[inline]
+    fn mul_assign(&mut self, rhs: Goldilocks) {
+        *self = *self * rhs;
+    }
+}
+
+/// `x` as a `Goldilocks` if `F` is `Goldilocks`, so that generic code can
+/// take a packed fast path.
+pub fn as_goldilocks<F: 'static>(x: &F) -> Option<&Goldilocks> {
+    (x as &dyn core::any::Any).downcast_ref()
+}
+
+/// `slice` as a slice of `Goldilocks` if `F` is `Goldilocks`.
+pub fn as_goldilocks_slice<F: 'static>(slice: &[F]) -> Option<&[Goldilocks]> {
+    // SAFETY: F is Goldilocks
+    (TypeId::of::<F>() == TypeId::of::<Goldilocks>())
+        .then(|| unsafe { &*(slice as *const [F] as *const [Goldilocks]) })
+}
+
+/// `slice` as a mutable slice of `Goldilocks` if `F` is `Goldilocks`.
+pub fn as_goldilocks_slice_mut<F: 'static>(slice: &mut [F]) -> Option<&mut [Goldilocks]> {
+    // SAFETY: F is Goldilocks
+    (TypeId::of::<F>() == TypeId::of::<Goldilocks>())
+        .then(|| unsafe { &mut *(slice as *mut [F] as *mut [Goldilocks]) })
+}
@@ -0,0 +1,42 @@
+//! The portable fallback, lane by lane with the scalar arithmetic.
+
+use super::{PackedArith, PackedGoldilocks, PackedKernel};
+
+#[derive(Clone, Copy)]
+struct Arith;
+
+impl PackedArith for Arith {
+    #[inline(always)]
+    fn add(self, a: PackedGoldilocks, b: PackedGoldilocks) -> PackedGoldilocks {
+        add(&a, &b)
+    }
+
+    #[inline(always)]
+    fn sub(self, a: PackedGoldilocks, b: PackedGoldilocks) -> PackedGoldilocks {
+        sub(&a, &b)
+    }
+
+    #[inline(always)]
+    fn mul(self, a: PackedGoldilocks, b: PackedGoldilocks) -> PackedGoldilocks {
+        mul(&a, &b)
+    }
+}
+
+pub(super) fn run<K: PackedKernel>(kernel: K) -> K::Output {
+    kernel.run(Arith)
+}
+
+#[inline]
+pub(super) fn add(a: &PackedGoldilocks, b: &PackedGoldilocks) -> PackedGoldilocks {
+    PackedGoldilocks(core::array::from_fn(|i| a.0[i] + b.0[i]))
+}
+
+#[inline]
+pub(super) fn sub(a: &PackedGoldilocks, b: &PackedGoldilocks) -> PackedGoldilocks {
+    PackedGoldilocks(core::array::from_fn(|i| a.0[i] - b.0[i]))
+}
+
+#[inline]
+pub(super) fn mul(a: &PackedGoldilocks, b: &PackedGoldilocks) -> PackedGoldilocks {
+    PackedGoldilocks(core::array::from_fn(|i| a.0[i] * b.0[i]))
+}
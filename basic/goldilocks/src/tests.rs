@@ -1,7 +1,17 @@
 use std::ops::Neg;
 
+use crate::conformance::{extension_field_conformance_tests, small_field_conformance_tests};
+use crate::extension::ExtensionField;
 use crate::fp::Goldilocks;
 use crate::fp::LegendreSymbol;
+use crate::fp::MODULUS;
+use crate::fp2::GoldilocksExt2;
+use crate::fp3::GoldilocksExt3;
+use crate::ntt::{coset_intt, coset_ntt, intt, low_degree_extension, ntt, root_of_unity};
+use crate::packed::{
+    as_goldilocks, as_goldilocks_slice, as_goldilocks_slice_mut, Backend, PackedArith,
+    PackedGoldilocks, PackedKernel, WIDTH,
+};
 
 use ark_std::{end_timer, start_timer};
 use ff::Field;
@@ -57,6 +67,224 @@ fn test_field() {
     random_prime_field_tests::<Goldilocks>("Goldilocks".to_string());
 }
 
+#[test]
+fn test_conformance() {
+    let rng = XorShiftRng::from_seed([
+        0x59, 0x62, 0xbe, 0x5d, 0x76, 0x3d, 0x31, 0x8d, 0x17, 0xdb, 0x37, 0x32, 0x54, 0x06, 0xbc,
+        0xe5,
+    ]);
+    small_field_conformance_tests::<Goldilocks>(rng);
+}
+
+#[test]
+fn test_canonical_representation() {
+    // 2^64 - 1 = p + 2^32 - 2
+    let a = Goldilocks::from(u64::MAX);
+    assert_eq!(a.0, 0xfffffffe);
+    assert_eq!(u64::from(a), 0xfffffffe);
+    assert_eq!(Goldilocks::from(MODULUS).0, 0);
+
+    // a non-canonical internal value is observed as its canonical value
+    let b = Goldilocks(MODULUS + 3);
+    assert_eq!(b, Goldilocks::from(3));
+    assert_eq!(b.to_repr(), [3, 0, 0, 0, 0, 0, 0, 0]);
+    assert!(bool::from(b.is_odd()));
+    assert_eq!(b.to_string(), "3");
+    assert_eq!(format!("{:?}", b), "Goldilocks(3)");
+    assert_eq!(u64::from(b), 3);
+
+    // only canonical values are deserialized
+    let bytes = bincode::serialize(&b).unwrap();
+    assert_eq!(bytes, 3u64.to_le_bytes());
+    assert_eq!(bincode::deserialize::<Goldilocks>(&bytes).unwrap(), b);
+    let bytes = bincode::serialize(&(MODULUS + 3)).unwrap();
+    assert!(bincode::deserialize::<Goldilocks>(&bytes).is_err());
+}
+
+#[test]
+fn test_packed() {
+    let mut rng = XorShiftRng::from_seed([
+        0x59, 0x62, 0xbe, 0x5d, 0x76, 0x3d, 0x31, 0x8d, 0x17, 0xdb, 0x37, 0x32, 0x54, 0x06, 0xbc,
+        0xe5,
+    ]);
+    assert!(Backend::detect().is_supported());
+
+    // random lanes, and edge cases of the non-canonical representation
+    let edge_cases = [
+        0,
+        1,
+        MODULUS - 1,
+        MODULUS,
+        MODULUS + 1,
+        u64::MAX - 1,
+        u64::MAX,
+        1 << 32,
+    ];
+    let mut inputs = vec![PackedGoldilocks(edge_cases.map(Goldilocks))];
+    inputs.extend((0..1000).map(|_| {
+        PackedGoldilocks(core::array::from_fn(|_| match rng.next_u32() % 4 {
+            0 => Goldilocks(rng.next_u64()),
+            1 => Goldilocks(MODULUS + rng.next_u64() % (u64::MAX - MODULUS + 1)),
+            _ => Goldilocks::random(&mut rng),
+        }))
+    }));
+
+    for backend in [Backend::Avx512, Backend::Avx2, Backend::Portable] {
+        if !backend.is_supported() {
+            continue;
+        }
+        for a in inputs.iter() {
+            for b in [inputs[0], inputs[(a.0[0].0 % 1000) as usize + 1], *a] {
+                let add = a.add_with(b, backend);
+                let sub = a.sub_with(b, backend);
+                let mul = a.mul_with(b, backend);
+                for i in 0..WIDTH {
+                    assert_eq!(add.0[i], a.0[i] + b.0[i], "{:?}", backend);
+                    assert_eq!(sub.0[i], a.0[i] - b.0[i], "{:?}", backend);
+                    assert_eq!(mul.0[i], a.0[i] * b.0[i], "{:?}", backend);
+                }
+            }
+        }
+    }
+
+    // a * b - (a + b) over all inputs, in one kernel
+    struct Kernel<'a>(&'a [PackedGoldilocks]);
+    impl PackedKernel for Kernel<'_> {
+        type Output = Vec<PackedGoldilocks>;
+
+        #[inline(always)]
+        fn run<A: PackedArith>(self, arith: A) -> Vec<PackedGoldilocks> {
+            self.0
+                .windows(2)
+                .map(|ab| arith.sub(arith.mul(ab[0], ab[1]), arith.add(ab[0], ab[1])))
+                .collect()
+        }
+    }
+    let expected = inputs
+        .windows(2)
+        .map(|ab| ab[0] * ab[1] - (ab[0] + ab[1]))
+        .collect::<Vec<_>>();
+    for backend in [Backend::Avx512, Backend::Avx2, Backend::Portable] {
+        if backend.is_supported() {
+            assert_eq!(backend.run(Kernel(&inputs)), expected, "{:?}", backend);
+        }
+    }
+
+    let a = inputs[1];
+    let x = Goldilocks::random(&mut rng);
+    assert_eq!(a * x, PackedGoldilocks(a.0.map(|a| a * x)));
+    assert_eq!(a + x, PackedGoldilocks(a.0.map(|a| a + x)));
+    assert_eq!(a - x, PackedGoldilocks(a.0.map(|a| a - x)));
+    assert_eq!(-a, PackedGoldilocks(a.0.map(|a| -a)));
+    assert_eq!(a.sum(), a.0.iter().sum());
+
+    let values = (0..2 * WIDTH as u64)
+        .map(Goldilocks::from)
+        .collect::<Vec<_>>();
+    let (even, odd) = PackedGoldilocks::deinterleave(&values);
+    assert_eq!(
+        even,
+        PackedGoldilocks(core::array::from_fn(|i| values[2 * i]))
+    );
+    assert_eq!(
+        odd,
+        PackedGoldilocks(core::array::from_fn(|i| values[2 * i + 1]))
+    );
+    assert_eq!(
+        PackedGoldilocks::from_slice(&values[..WIDTH]).as_slice(),
+        &values[..WIDTH]
+    );
+
+    assert_eq!(as_goldilocks(&x), Some(&x));
+    assert_eq!(as_goldilocks(&GoldilocksExt2::ONE), None);
+    assert_eq!(as_goldilocks_slice(&values), Some(&values[..]));
+    assert_eq!(as_goldilocks_slice(&[GoldilocksExt2::ONE]), None);
+    let mut values = values;
+    as_goldilocks_slice_mut(&mut values).unwrap()[0] = x;
+    assert_eq!(values[0], x);
+}
+
+/// Evaluate the polynomial with the given coefficients at `x`.
+fn evaluate_naive<F: PrimeField>(coefficients: &[F], x: F) -> F {
+    coefficients
+        .iter()
+        .rev()
+        .fold(F::ZERO, |acc, coefficient| acc * x + coefficient)
+}
+
+#[test]
+fn test_ntt() {
+    let mut rng = XorShiftRng::from_seed([
+        0x59, 0x62, 0xbe, 0x5d, 0x76, 0x3d, 0x31, 0x8d, 0x17, 0xdb, 0x37, 0x32, 0x54, 0x06, 0xbc,
+        0xe5,
+    ]);
+
+    // sizes on both sides of the threshold of the parallel butterflies
+    for log_n in [0, 1, 2, 3, 5, 8, 11, 12] {
+        let n = 1 << log_n;
+        let root = root_of_unity::<Goldilocks>(n);
+        assert_eq!(root.pow_vartime([n as u64]), Goldilocks::ONE);
+        if n > 1 {
+            assert_eq!(root.pow_vartime([n as u64 / 2]), -Goldilocks::ONE);
+        }
+
+        let coefficients = (0..n)
+            .map(|_| Goldilocks::random(&mut rng))
+            .collect::<Vec<_>>();
+        // the naive DFT is quadratic, so only check a few points of large sizes
+        let points = (0..n).step_by((n / 64).max(1)).collect::<Vec<_>>();
+
+        let mut values = coefficients.clone();
+        ntt(&mut values);
+        for &i in points.iter() {
+            assert_eq!(
+                values[i],
+                evaluate_naive(&coefficients, root.pow_vartime([i as u64]))
+            );
+        }
+        intt(&mut values);
+        assert_eq!(values, coefficients);
+
+        let shift = Goldilocks::random(&mut rng);
+        coset_ntt(&mut values, shift);
+        for &i in points.iter() {
+            assert_eq!(
+                values[i],
+                evaluate_naive(&coefficients, shift * root.pow_vartime([i as u64]))
+            );
+        }
+        coset_intt(&mut values, shift);
+        assert_eq!(values, coefficients);
+
+        for blowup in [1, 2, 8] {
+            let mut evaluations = coefficients.clone();
+            ntt(&mut evaluations);
+            let extension = low_degree_extension(&evaluations, blowup);
+            assert_eq!(extension.len(), n * blowup);
+            let extension_root = root_of_unity::<Goldilocks>(n * blowup);
+            for i in (0..n * blowup).step_by((n * blowup / 64).max(1)) {
+                let x =
+                    Goldilocks::MULTIPLICATIVE_GENERATOR * extension_root.pow_vartime([i as u64]);
+                assert_eq!(extension[i], evaluate_naive(&coefficients, x));
+            }
+        }
+    }
+}
+
+#[test]
+#[should_panic(expected = "exceeds the two-adicity")]
+fn test_ntt_too_large() {
+    root_of_unity::<Goldilocks>(1 << 33);
+}
+
+#[test]
+fn test_extension_fields() {
+    random_field_tests::<GoldilocksExt2>("GoldilocksExt2".to_string());
+    random_extension_field_tests::<GoldilocksExt2>("GoldilocksExt2".to_string());
+    random_field_tests::<GoldilocksExt3>("GoldilocksExt3".to_string());
+    random_extension_field_tests::<GoldilocksExt3>("GoldilocksExt3".to_string());
+}
+
 pub fn random_field_tests<F: Field>(type_name: String) {
     let mut rng = XorShiftRng::from_seed([
         0x59, 0x62, 0xbe, 0x5d, 0x76, 0x3d, 0x31, 0x8d, 0x17, 0xdb, 0x37, 0x32, 0x54, 0x06, 0xbc,
@@ -285,4 +513,145 @@ fn random_serdes_tests<F: PrimeField, R: RngCore>(mut rng: R, type_name: String)
         assert_eq!(b.as_ref(), d.as_ref());
     }
     end_timer!(start);
-}
\ No newline at end of file
+}
+pub fn random_extension_field_tests<E: ExtensionField>(type_name: String) {
+    let mut rng = XorShiftRng::from_seed([
+        0x59, 0x62, 0xbe, 0x5d, 0x76, 0x3d, 0x31, 0x8d, 0x17, 0xdb, 0x37, 0x32, 0x54, 0x06, 0xbc,
+        0xe5,
+    ]);
+
+    let message = format!("conformance {}", type_name);
+    let start = start_timer!(|| message);
+    extension_field_conformance_tests::<E>(&mut rng);
+    end_timer!(start);
+    random_extension_serdes_tests::<E, _>(&mut rng, type_name);
+}
+
+fn random_extension_serdes_tests<E: ExtensionField, R: RngCore>(mut rng: R, type_name: String) {
+    let message = format!("serdes {}", type_name);
+    let start = start_timer!(|| message);
+    for _ in 0..10000 {
+        let a = E::random(&mut rng);
+        let bytes = bincode::serialize(&a).unwrap();
+        assert_eq!(bytes.len(), 8 * E::DEGREE);
+        let b: E = bincode::deserialize(&bytes).unwrap();
+        assert_eq!(a, b);
+        assert_eq!(a.to_canonical_u64_vec().len(), E::DEGREE);
+    }
+    end_timer!(start);
+}
+
+#[cfg(feature = "ark")]
+// for the impls generated by `#[derive(MontConfig)]` in ark-ff 0.4
+#[allow(non_local_definitions)]
+mod ark {
+    use super::*;
+    use crate::ark::{
+        as_ark_slice, as_ark_slice_mut, as_goldilocks_slice, from_ark, to_ark, ArkGoldilocks,
+    };
+
+    /// Goldilocks as arkworks defines prime fields, to check the arkworks traits
+    /// and the conversions against.
+    #[derive(ark_ff::MontConfig)]
+    #[modulus = "18446744069414584321"]
+    #[generator = "7"]
+    pub struct MontGoldilocksConfig;
+
+    type MontGoldilocks = ark_ff::Fp64<ark_ff::MontBackend<MontGoldilocksConfig, 1>>;
+
+    /// Checks of the arkworks traits that the two implementations must agree on.
+    fn ark_field_tests<F: ark_ff::PrimeField>(values: &[u64]) -> Vec<Vec<u8>> {
+        use ark_ff::BigInteger;
+        use ark_serialize::CanonicalSerialize;
+
+        assert_eq!(F::characteristic(), [MODULUS]);
+        assert_eq!(F::from_str("18446744069414584322").ok(), Some(F::one()));
+        assert_eq!(F::from_le_bytes_mod_order(&[0xff; 16]), F::from(u128::MAX));
+        let root = F::get_root_of_unity(1 << 32).unwrap();
+        assert_eq!(root.pow([1u64 << 32]), F::one());
+        assert_ne!(root.pow([1u64 << 31]), F::one());
+
+        let mut modulus_bytes = vec![];
+        F::MODULUS.serialize_compressed(&mut modulus_bytes).unwrap();
+        assert!(F::deserialize_compressed(modulus_bytes.as_slice()).is_err());
+
+        values
+            .iter()
+            .map(|value| {
+                let a = F::from(*value);
+                assert_eq!(F::from_bigint(a.into_bigint()), Some(a));
+                assert_eq!(a * a.inverse().unwrap(), F::one());
+                assert_eq!(
+                    a.square().sqrt().map(|root| root.square()),
+                    Some(a.square())
+                );
+                assert_eq!(a.legendre().is_qr(), a.sqrt().is_some());
+
+                let mut bytes = vec![];
+                a.serialize_compressed(&mut bytes).unwrap();
+                assert_eq!(bytes.len(), 8);
+                assert_eq!(bytes, a.into_bigint().to_bytes_le());
+                assert_eq!(F::deserialize_compressed(bytes.as_slice()).unwrap(), a);
+                assert_eq!(F::from_random_bytes(&bytes), Some(a));
+                bytes
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_ark() {
+        let mut rng = XorShiftRng::from_seed([
+            0x59, 0x62, 0xbe, 0x5d, 0x76, 0x3d, 0x31, 0x8d, 0x17, 0xdb, 0x37, 0x32, 0x54, 0x06,
+            0xbc, 0xe5,
+        ]);
+
+        // the two implementations agree on the same elements, including their
+        // serialization
+        let values: Vec<u64> = (0..100).map(|_| rng.next_u64()).collect();
+        assert_eq!(
+            ark_field_tests::<ArkGoldilocks>(&values),
+            ark_field_tests::<MontGoldilocks>(&values)
+        );
+
+        for _ in 0..1000 {
+            let a = Goldilocks::random(&mut rng);
+            let b = Goldilocks::random(&mut rng);
+            let (mont_a, mont_b) = (to_ark::<MontGoldilocks>(a), to_ark::<MontGoldilocks>(b));
+            assert_eq!(from_ark(mont_a), a);
+            assert_eq!(to_ark::<MontGoldilocks>(a * b), mont_a * mont_b);
+            assert_eq!(to_ark::<MontGoldilocks>(a + b), mont_a + mont_b);
+            assert_eq!(to_ark::<MontGoldilocks>(a - b), mont_a - mont_b);
+            assert_eq!(
+                to_ark::<MontGoldilocks>(a * b.invert().unwrap()),
+                mont_a / mont_b
+            );
+            assert_eq!(ArkGoldilocks(a).cmp(&ArkGoldilocks(b)), mont_a.cmp(&mont_b));
+            assert_eq!(to_ark::<ArkGoldilocks>(a), ArkGoldilocks(a));
+            assert_eq!(from_ark(ArkGoldilocks(a)), a);
+        }
+        // a non-canonical internal value converts to its canonical value
+        assert_eq!(
+            to_ark::<MontGoldilocks>(Goldilocks(MODULUS + 5)),
+            MontGoldilocks::from(5u64)
+        );
+
+        // the slice views share the elements
+        let mut elements: Vec<Goldilocks> = (0..10).map(|_| Goldilocks::random(&mut rng)).collect();
+        let expected: Vec<ArkGoldilocks> = elements.iter().map(|x| ArkGoldilocks(*x)).collect();
+        assert_eq!(as_ark_slice(&elements), expected.as_slice());
+        assert_eq!(as_goldilocks_slice(&expected), elements.as_slice());
+        as_ark_slice_mut(&mut elements)[3] = ArkGoldilocks(Goldilocks::ONE);
+        assert_eq!(elements[3], Goldilocks::ONE);
+    }
+
+    #[test]
+    #[should_panic(expected = "is not the Goldilocks modulus")]
+    fn test_ark_wrong_modulus() {
+        #[derive(ark_ff::MontConfig)]
+        #[modulus = "2013265921"]
+        #[generator = "31"]
+        pub struct BabyBearConfig;
+
+        to_ark::<ark_ff::Fp64<ark_ff::MontBackend<BabyBearConfig, 1>>>(Goldilocks::ONE);
+    }
+}
@@ -0,0 +1,244 @@
+use core::fmt::{Display, Formatter};
+use core::ops::{Add, Mul, Neg, Sub};
+use ff::helpers::sqrt_ratio_generic;
+use ff::{Field, PrimeField};
+use goldilocks::SmallField;
+use rand_core::RngCore;
+use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
+use subtle::{Choice, ConditionallySelectable, ConstantTimeEq, CtOption};
+
+/// Mersenne31 field with modulus 2^31 - 1.
+///
+/// The internal `u32` is the canonical value, in `[0, p)`. Since
+/// 2^31 = 1 mod p, a product is reduced by folding its high bits onto its
+/// low bits, which lets sums of a few products be reduced only once.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[repr(transparent)]
+pub struct Mersenne31(pub(crate) u32);
+
+/// 2^31 - 1
+pub const MODULUS: u32 = 0x7fffffff;
+
+/// x mod p, for any `x`.
+#[inline]
+pub(crate) const fn reduce_u64(x: u64) -> Mersenne31 {
+    // below 2^31 + 2^33, then below 2^31 + 8
+    let x = (x & MODULUS as u64) + (x >> 31);
+    let x = ((x & MODULUS as u64) + (x >> 31)) as u32;
+    Mersenne31(if x >= MODULUS { x - MODULUS } else { x })
+}
+
+impl Mersenne31 {
+    /// The element `value mod p`.
+    pub const fn new(value: u32) -> Self {
+        reduce_u64(value as u64)
+    }
+
+    /// The canonical value of the element, in `[0, p)`.
+    #[inline]
+    pub const fn to_canonical_u32(&self) -> u32 {
+        self.0
+    }
+}
+
+/// The serialized form of an element: its canonical value, in a newtype
+/// struct like the one `#[derive(Serialize, Deserialize)]` on `Mersenne31` would
+/// give.
+#[derive(Serialize, Deserialize)]
+#[serde(rename = "Mersenne31")]
+struct Canonical(u32);
+
+/// Serializes the canonical value.
+impl Serialize for Mersenne31 {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        Canonical(self.0).serialize(serializer)
+    }
+}
+
+/// Rejects values that are not canonical, so that an element has a unique
+/// serialization.
+impl<'de> Deserialize<'de> for Mersenne31 {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let Canonical(value) = Canonical::deserialize(deserializer)?;
+        Option::from(Self::from_repr(value.to_le_bytes()))
+            .ok_or_else(|| de::Error::custom(format!("non-canonical Mersenne31 element {}", value)))
+    }
+}
+
+impl Display for Mersenne31 {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl Field for Mersenne31 {
+    /// The zero element of the field, the additive identity.
+    const ZERO: Self = Self(0);
+
+    /// The one element of the field, the multiplicative identity.
+    const ONE: Self = Self(1);
+
+    /// Returns an element chosen uniformly at random using a user-provided RNG.
+    /// Note: this sampler is not constant time!
+    fn random(mut rng: impl RngCore) -> Self {
+        loop {
+            let value = rng.next_u32() >> 1;
+            if value < MODULUS {
+                return Self(value);
+            }
+        }
+    }
+
+    /// Squares this element.
+    #[must_use]
+    fn square(&self) -> Self {
+        *self * *self
+    }
+
+    /// Doubles this element.
+    #[must_use]
+    fn double(&self) -> Self {
+        *self + *self
+    }
+
+    /// Computes the multiplicative inverse of this element,
+    /// failing if the element is zero.
+    fn invert(&self) -> CtOption<Self> {
+        // a^(p - 2), with an exponent that does not depend on a
+        CtOption::new(self.pow_vartime([(MODULUS - 2) as u64]), !self.is_zero())
+    }
+
+    /// Returns the square root of the field element, if it is
+    /// quadratic residue.
+    fn sqrt(&self) -> CtOption<Self> {
+        // p = 3 mod 4, so a^((p + 1) / 4) is a root of a if a is a square
+        let root = self.pow_vartime([1 << 29]);
+        CtOption::new(root, root.square().ct_eq(self))
+    }
+
+    /// Computes the square root of `num / div`, see `Field::sqrt_ratio`.
+    fn sqrt_ratio(num: &Self, div: &Self) -> (Choice, Self) {
+        sqrt_ratio_generic(num, div)
+    }
+}
+
+/// This represents an element of a prime field.
+impl PrimeField for Mersenne31 {
+    /// The prime field can be converted back and forth into this binary
+    /// representation, the little-endian bytes of the canonical value.
+    type Repr = [u8; 4];
+
+    const MODULUS: &'static str = "0x7fffffff";
+
+    const NUM_BITS: u32 = 31;
+
+    const CAPACITY: u32 = 30;
+
+    /// p - 1 = 2 * (2^30 - 1): the only two-adic subgroup is {1, -1}.
+    const S: u32 = 1;
+
+    const TWO_INV: Self = Self(1 << 30);
+
+    /// The smallest generator of the multiplicative group.
+    const MULTIPLICATIVE_GENERATOR: Self = Self(7);
+
+    /// 7^(2^30 - 1) = -1
+    const ROOT_OF_UNITY: Self = Self(MODULUS - 1);
+
+    const ROOT_OF_UNITY_INV: Self = Self(MODULUS - 1);
+
+    /// 7^2
+    const DELTA: Self = Self(49);
+
+    /// Fails if the input is not canonical (is not smaller than the field's
+    /// modulus).
+    fn from_repr(repr: Self::Repr) -> CtOption<Self> {
+        let value = u32::from_le_bytes(repr);
+        CtOption::new(Self::new(value), Choice::from((value < MODULUS) as u8))
+    }
+
+    fn to_repr(&self) -> Self::Repr {
+        self.0.to_le_bytes()
+    }
+
+    fn is_odd(&self) -> Choice {
+        Choice::from((self.0 & 1) as u8)
+    }
+}
+
+impl SmallField for Mersenne31 {
+    fn to_canonical_u64_vec(&self) -> Vec<u64> {
+        vec![self.0 as u64]
+    }
+}
+
+/// Reduces the input modulo p.
+impl From<u64> for Mersenne31 {
+    fn from(input: u64) -> Self {
+        reduce_u64(input)
+    }
+}
+
+/// The canonical value of the element.
+impl From<Mersenne31> for u32 {
+    fn from(input: Mersenne31) -> Self {
+        input.0
+    }
+}
+
+impl ConditionallySelectable for Mersenne31 {
+    fn conditional_select(a: &Self, b: &Self, choice: Choice) -> Self {
+        Self(u32::conditional_select(&a.0, &b.0, choice))
+    }
+}
+
+impl ConstantTimeEq for Mersenne31 {
+    fn ct_eq(&self, other: &Self) -> Choice {
+        self.0.ct_eq(&other.0)
+    }
+}
+
+impl Neg for Mersenne31 {
+    type Output = Self;
+
+    #[inline]
+    fn neg(self) -> Self {
+        Self::ZERO - self
+    }
+}
+
+impl Add for Mersenne31 {
+    type Output = Self;
+
+    #[inline]
+    fn add(self, rhs: Self) -> Self {
+        // 2p < 2^32
+        let sum = self.0 + rhs.0;
+        Self(if sum >= MODULUS { sum - MODULUS } else { sum })
+    }
+}
+
+impl Sub for Mersenne31 {
+    type Output = Self;
+
+    #[inline]
+    fn sub(self, rhs: Self) -> Self {
+        let (diff, under) = self.0.overflowing_sub(rhs.0);
+        Self(if under {
+            diff.wrapping_add(MODULUS)
+        } else {
+            diff
+        })
+    }
+}
+
+impl Mul for Mersenne31 {
+    type Output = Self;
+
+    #[inline]
+    fn mul(self, rhs: Self) -> Self {
+        reduce_u64(self.0 as u64 * rhs.0 as u64)
+    }
+}
+
+goldilocks::impl_derived_ops!(Mersenne31);
@@ -0,0 +1,152 @@
+use crate::fp::{reduce_u64, Mersenne31};
+use core::ops::{Add, Mul, Neg, Sub};
+use ff::{Field, PrimeField};
+use goldilocks::extension::{sqrt_ratio, sqrt_tonelli_shanks};
+use goldilocks::ExtensionField;
+use rand_core::RngCore;
+use serde::{Deserialize, Serialize};
+use subtle::{Choice, CtOption};
+
+/// Cubic extension of the Mersenne31 field, GF(p)[x] / (x^3 - 5).
+/// An element `a_0 + a_1 x + a_2 x^2` is stored as `[a_0, a_1, a_2]`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Mersenne31Ext3(pub(crate) [Mersenne31; 3]);
+
+/// x^3 = W
+const W: Mersenne31 = Mersenne31(5);
+
+/// x^(ip) = FROBENIUS[i] x^i, where FROBENIUS[i] = W^(i (p - 1) / 3)
+const FROBENIUS: [Mersenne31; 3] = [Mersenne31(1), Mersenne31(1513477735), Mersenne31(634005911)];
+
+/// (t - 1) / 2 where p^3 - 1 = 2 * t
+const TM1D2: [u64; 2] = [0xd00000005fffffff, 0x7ffffff];
+
+impl Mersenne31Ext3 {
+    pub const fn new(a0: Mersenne31, a1: Mersenne31, a2: Mersenne31) -> Self {
+        Self([a0, a1, a2])
+    }
+}
+
+impl Field for Mersenne31Ext3 {
+    /// The zero element of the field, the additive identity.
+    const ZERO: Self = Self([Mersenne31::ZERO; 3]);
+
+    /// The one element of the field, the multiplicative identity.
+    const ONE: Self = Self([Mersenne31::ONE, Mersenne31::ZERO, Mersenne31::ZERO]);
+
+    /// Returns an element chosen uniformly at random using a user-provided RNG.
+    /// Note: this sampler is not constant time!
+    fn random(mut rng: impl RngCore) -> Self {
+        Self(core::array::from_fn(|_| Mersenne31::random(&mut rng)))
+    }
+
+    /// Squares this element.
+    #[must_use]
+    fn square(&self) -> Self {
+        *self * *self
+    }
+
+    /// Doubles this element.
+    #[must_use]
+    fn double(&self) -> Self {
+        *self + *self
+    }
+
+    /// Computes the multiplicative inverse of this element,
+    /// failing if the element is zero.
+    fn invert(&self) -> CtOption<Self> {
+        // 1 / a = a^p a^(p^2) / N(a), where N(a) = a a^p a^(p^2) is in the base field
+        let frobenius = self.frobenius();
+        let conjugates = frobenius * frobenius.frobenius();
+        let norm = (*self * conjugates).0[0];
+        norm.invert().map(|norm_inv| conjugates * norm_inv)
+    }
+
+    /// Returns the square root of the field element, if it is
+    /// quadratic residue.
+    /// Note: this is not constant time!
+    fn sqrt(&self) -> CtOption<Self> {
+        // p^3 - 1 has the same two-adicity 1 as p - 1, so the root of unity
+        // is -1
+        sqrt_tonelli_shanks(self, &TM1D2, 1, -Self::ONE)
+    }
+
+    /// Computes the square root of `num / div`, see `Field::sqrt_ratio`.
+    /// The non-square used when `num / div` is not a square is the generator
+    /// of the base field, which stays a non-square in an extension of odd
+    /// degree.
+    fn sqrt_ratio(num: &Self, div: &Self) -> (Choice, Self) {
+        sqrt_ratio(num, div, Mersenne31::MULTIPLICATIVE_GENERATOR.into())
+    }
+}
+
+impl ExtensionField for Mersenne31Ext3 {
+    const DEGREE: usize = 3;
+
+    type BaseField = Mersenne31;
+
+    fn from_bases(bases: &[Mersenne31]) -> Self {
+        assert!(bases.len() <= 3, "too many coefficients for Mersenne31Ext3");
+        let mut res = Self::ZERO;
+        res.0[..bases.len()].copy_from_slice(bases);
+        res
+    }
+
+    fn as_bases(&self) -> &[Mersenne31] {
+        &self.0
+    }
+
+    /// `\sum_i a_i x^(ip)`, as the Frobenius map fixes the base field.
+    fn frobenius(&self) -> Self {
+        Self(core::array::from_fn(|i| self.0[i] * FROBENIUS[i]))
+    }
+}
+
+impl Neg for Mersenne31Ext3 {
+    type Output = Self;
+
+    #[inline]
+    fn neg(self) -> Self {
+        Self(self.0.map(|a| -a))
+    }
+}
+
+impl Add for Mersenne31Ext3 {
+    type Output = Self;
+
+    #[inline]
+    fn add(self, rhs: Self) -> Self::Output {
+        Self(core::array::from_fn(|i| self.0[i] + rhs.0[i]))
+    }
+}
+
+impl Sub for Mersenne31Ext3 {
+    type Output = Self;
+
+    #[inline]
+    fn sub(self, rhs: Self) -> Self {
+        Self(core::array::from_fn(|i| self.0[i] - rhs.0[i]))
+    }
+}
+
+impl Mul for Mersenne31Ext3 {
+    type Output = Self;
+
+    /// Schoolbook multiplication with lazy reduction: with the reduction
+    /// x^3 = W folded into `bw`, every coefficient is a sum of three products
+    /// below 2^62, reduced once.
+    #[inline]
+    fn mul(self, rhs: Self) -> Self {
+        let [a0, a1, a2] = self.0.map(|a| a.0 as u64);
+        let [b0, b1, b2] = rhs.0.map(|b| b.0 as u64);
+        let [bw1, bw2] = [rhs.0[1], rhs.0[2]].map(|b| (b * W).0 as u64);
+
+        Self([
+            reduce_u64(a0 * b0 + a1 * bw2 + a2 * bw1),
+            reduce_u64(a0 * b1 + a1 * b0 + a2 * bw2),
+            reduce_u64(a0 * b2 + a1 * b1 + a2 * b0),
+        ])
+    }
+}
+
+goldilocks::impl_extension_ops!(Mersenne31Ext3, Mersenne31);
@@ -0,0 +1,15 @@
+//! This crate implements the Mersenne31 field with modulus 2^31 - 1, with
+//! lazy reduction, and its cubic extension. The traits it implements,
+//! `SmallField` and `ExtensionField`, are those of the `goldilocks` crate.
+//!
+//! The two-adicity of the field is 1, so the transforms of
+//! `goldilocks::ntt` only apply to sizes up to 2.
+
+pub use fp::Mersenne31;
+pub use fp3::Mersenne31Ext3;
+
+mod fp;
+mod fp3;
+
+#[cfg(test)]
+mod tests;
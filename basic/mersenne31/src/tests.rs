@@ -0,0 +1,87 @@
+use ff::Field;
+use goldilocks::conformance::{extension_field_conformance_tests, small_field_conformance_tests};
+use goldilocks::ntt::{intt, ntt, root_of_unity};
+use goldilocks::ExtensionField;
+use rand_core::SeedableRng;
+use rand_xorshift::XorShiftRng;
+
+use crate::fp::{reduce_u64, MODULUS};
+use crate::{Mersenne31, Mersenne31Ext3};
+
+fn test_rng() -> XorShiftRng {
+    XorShiftRng::from_seed([
+        0x59, 0x62, 0xbe, 0x5d, 0x76, 0x3d, 0x31, 0x8d, 0x17, 0xdb, 0x37, 0x32, 0x54, 0x06, 0xbc,
+        0xe5,
+    ])
+}
+
+#[test]
+fn test_conformance() {
+    small_field_conformance_tests::<Mersenne31>(test_rng());
+    extension_field_conformance_tests::<Mersenne31Ext3>(test_rng());
+}
+
+#[test]
+fn test_lazy_reduction() {
+    let p = MODULUS as u64;
+    for x in [
+        0,
+        1,
+        p - 1,
+        p,
+        p + 1,
+        2 * p,
+        p * p,
+        3 * (p - 1) * (p - 1),
+        u64::MAX,
+    ] {
+        assert_eq!(reduce_u64(x).to_canonical_u32() as u64, x % p);
+    }
+    assert_eq!(Mersenne31::new(MODULUS), Mersenne31::ZERO);
+    assert_eq!(Mersenne31::new(u32::MAX), Mersenne31::ONE);
+    assert_eq!(format!("{}", -Mersenne31::ONE), "2147483646");
+
+    // the largest sums of products reduced by the extension multiplication
+    let max = Mersenne31Ext3([-Mersenne31::ONE; 3]);
+    // (-1 - x - x^2)^2 = 1 + 2x + 3x^2 + 2x^3 + x^4
+    let expected = Mersenne31Ext3([1 + 2 * 5, 2 + 5, 3].map(Mersenne31::new));
+    assert_eq!(max * max, expected);
+}
+
+#[test]
+fn test_extension_reduction() {
+    // x^3 = 5
+    let x = Mersenne31Ext3::from_bases(&[Mersenne31::ZERO, Mersenne31::ONE]);
+    assert_eq!(x.square() * x, Mersenne31Ext3::from(Mersenne31::new(5)));
+    assert_eq!(x.norm(), Mersenne31::new(5));
+}
+
+#[test]
+fn test_serdes() {
+    let mut rng = test_rng();
+    for _ in 0..1000 {
+        let a = Mersenne31Ext3::random(&mut rng);
+        let bytes = bincode::serialize(&a).unwrap();
+        assert_eq!(bytes.len(), 4 * Mersenne31Ext3::DEGREE);
+        assert_eq!(bincode::deserialize::<Mersenne31Ext3>(&bytes).unwrap(), a);
+    }
+    assert!(bincode::deserialize::<Mersenne31>(&MODULUS.to_le_bytes()).is_err());
+}
+
+#[test]
+fn test_ntt() {
+    assert_eq!(root_of_unity::<Mersenne31>(2), -Mersenne31::ONE);
+
+    let coefficients = vec![Mersenne31::new(3), Mersenne31::new(5)];
+    let mut values = coefficients.clone();
+    ntt(&mut values);
+    assert_eq!(values, [Mersenne31::new(8), -Mersenne31::new(2)]);
+    intt(&mut values);
+    assert_eq!(values, coefficients);
+}
+
+#[test]
+#[should_panic(expected = "exceeds the two-adicity")]
+fn test_ntt_too_large() {
+    ntt(&mut [Mersenne31::ONE; 4]);
+}
@@ -0,0 +1,75 @@
+use ark_std::test_rng;
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+use ff::Field;
+use goldilocks::Goldilocks;
+use multilinear_extensions::mle::DenseMultilinearExtension;
+
+const NUM_SAMPLES: usize = 10;
+const NUM_MLES: usize = 8;
+const NUM_POINTS: usize = 8;
+
+/// Compare evaluating many MLEs at one point through a shared `eq(x, r)`
+/// table against calling `evaluate` on each of them.
+fn bench_evaluate_many(c: &mut Criterion) {
+    let mut group = c.benchmark_group("evaluate_many");
+    group.sample_size(NUM_SAMPLES);
+
+    let mut rng = test_rng();
+    for nv in [12, 16, 20] {
+        let (mles, _) =
+            DenseMultilinearExtension::<Goldilocks>::random_mle_list(nv, NUM_MLES, &mut rng);
+        let mles = mles.iter().map(|mle| mle.as_ref()).collect::<Vec<_>>();
+        let point = (0..nv)
+            .map(|_| Goldilocks::random(&mut rng))
+            .collect::<Vec<_>>();
+        let parameter = format!("nv_{}_mles_{}", nv, NUM_MLES);
+
+        group.bench_function(BenchmarkId::new("repeated_evaluate", &parameter), |b| {
+            b.iter(|| {
+                mles.iter()
+                    .map(|mle| mle.evaluate(&point))
+                    .collect::<Vec<_>>()
+            })
+        });
+        group.bench_function(BenchmarkId::new("evaluate_many", &parameter), |b| {
+            b.iter(|| DenseMultilinearExtension::evaluate_many(&mles, &point))
+        });
+    }
+    group.finish();
+}
+
+/// Compare evaluating one MLE at many points in parallel against calling
+/// `evaluate` for each point in turn.
+fn bench_evaluate_at_points(c: &mut Criterion) {
+    let mut group = c.benchmark_group("evaluate_at_points");
+    group.sample_size(NUM_SAMPLES);
+
+    let mut rng = test_rng();
+    for nv in [12, 16, 20] {
+        let (mles, _) = DenseMultilinearExtension::<Goldilocks>::random_mle_list(nv, 1, &mut rng);
+        let points = (0..NUM_POINTS)
+            .map(|_| {
+                (0..nv)
+                    .map(|_| Goldilocks::random(&mut rng))
+                    .collect::<Vec<_>>()
+            })
+            .collect::<Vec<_>>();
+        let parameter = format!("nv_{}_points_{}", nv, NUM_POINTS);
+
+        group.bench_function(BenchmarkId::new("repeated_evaluate", &parameter), |b| {
+            b.iter(|| {
+                points
+                    .iter()
+                    .map(|point| mles[0].evaluate(point))
+                    .collect::<Vec<_>>()
+            })
+        });
+        group.bench_function(BenchmarkId::new("evaluate_at_points", &parameter), |b| {
+            b.iter(|| mles[0].evaluate_at_points(&points))
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, bench_evaluate_many, bench_evaluate_at_points);
+criterion_main!(benches);
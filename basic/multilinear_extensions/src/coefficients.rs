@@ -0,0 +1,115 @@
+//! Conversion between the evaluation form of a multilinear polynomial and its
+//! coefficient form
+//!
+//!   f(x) = \sum_S c_S \prod_{i \in S} x_i
+//!
+//! where a subset `S` of the variables is indexed like a point of the boolean
+//! hypercube, i.e. bit `i` of the index is set iff `x_i` is in `S`.
+
+use ff::Field;
+use rayon::{
+    prelude::{
+        IndexedParallelIterator, IntoParallelRefIterator, IntoParallelRefMutIterator,
+        ParallelIterator,
+    },
+    slice::{ParallelSlice, ParallelSliceMut},
+};
+
+use crate::mle::DenseMultilinearExtension;
+
+/// Number of pairs below which a butterfly layer is not split further
+/// between rayon tasks.
+const MIN_LEN: usize = 1 << 10;
+
+/// Turn the evaluations of a multilinear polynomial over the boolean
+/// hypercube into its coefficients, in place. This is the Möbius transform
+/// over the subset lattice.
+pub fn evaluations_to_coefficients_in_place<F: Field>(values: &mut [F]) {
+    assert!(
+        values.len().is_power_of_two(),
+        "The size of evaluations should be 2^num_vars."
+    );
+    // for every variable, c(.., x_i = 1, ..) -= c(.., x_i = 0, ..)
+    butterfly(values, |low, high| *high -= low);
+}
+
+/// Turn the coefficients of a multilinear polynomial into its evaluations
+/// over the boolean hypercube, in place. This is the zeta transform over the
+/// subset lattice and inverts `evaluations_to_coefficients_in_place`.
+pub fn coefficients_to_evaluations_in_place<F: Field>(values: &mut [F]) {
+    assert!(
+        values.len().is_power_of_two(),
+        "The size of coefficients should be 2^num_vars."
+    );
+    // for every variable, f(.., x_i = 1, ..) += f(.., x_i = 0, ..)
+    butterfly(values, |low, high| *high += low);
+}
+
+/// Apply `op` to every pair of entries that differ in one bit only, for
+/// every bit in turn.
+fn butterfly<F: Field>(values: &mut [F], op: impl Fn(&F, &mut F) + Sync) {
+    let mut half = 1;
+    while half < values.len() {
+        values
+            .par_chunks_mut(half << 1)
+            .with_min_len(MIN_LEN / half + 1)
+            .for_each(|chunk| {
+                let (low, high) = chunk.split_at_mut(half);
+                // the top layers have few chunks, so their pairs are split
+                // between tasks too
+                if half >= MIN_LEN {
+                    low.par_iter()
+                        .zip(high.par_iter_mut())
+                        .with_min_len(MIN_LEN)
+                        .for_each(|(low, high)| op(low, high));
+                } else {
+                    low.iter()
+                        .zip(high.iter_mut())
+                        .for_each(|(low, high)| op(low, high));
+                }
+            });
+        half <<= 1;
+    }
+}
+
+/// Evaluate a multilinear polynomial at `point` directly from its
+/// coefficients.
+pub fn evaluate_coefficients<F: Field>(coefficients: &[F], point: &[F]) -> F {
+    assert_eq!(
+        coefficients.len(),
+        1 << point.len(),
+        "The size of coefficients should be 2^num_vars."
+    );
+    // f(x_1, x_2, ..) = f_0(x_2, ..) + x_1 * f_1(x_2, ..), where f_0 and f_1
+    // have the even and the odd coefficients of f.
+    let mut coefficients = coefficients.to_vec();
+    for x in point.iter() {
+        coefficients = coefficients
+            .par_chunks(2)
+            .map(|pair| pair[0] + pair[1] * x)
+            .collect();
+    }
+    coefficients[0]
+}
+
+impl<F: Field> DenseMultilinearExtension<F> {
+    /// Construct a new polynomial from its coefficients, where the index of a
+    /// coefficient is the set of variables of its monomial.
+    pub fn from_coefficients(num_vars: usize, mut coefficients: Vec<F>) -> Self {
+        assert_eq!(
+            coefficients.len(),
+            1 << num_vars,
+            "The size of coefficients should be 2^num_vars."
+        );
+        coefficients_to_evaluations_in_place(&mut coefficients);
+        Self::from_evaluations_vec(num_vars, coefficients)
+    }
+
+    /// The coefficients of the polynomial, where the index of a coefficient
+    /// is the set of variables of its monomial.
+    pub fn to_coefficients(&self) -> Vec<F> {
+        let mut coefficients = self.evaluations.clone();
+        evaluations_to_coefficients_in_place(&mut coefficients);
+        coefficients
+    }
+}
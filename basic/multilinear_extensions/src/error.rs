@@ -0,0 +1,85 @@
+use std::fmt::{Display, Formatter};
+
+/// An error raised on malformed input to the multilinear extension and
+/// virtual polynomial APIs.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum MleError {
+    /// The number of evaluations is not 2^num_vars.
+    InvalidEvaluationsSize { expected: usize, actual: usize },
+    /// A point does not have one coordinate per variable.
+    PointSizeMismatch { expected: usize, actual: usize },
+    /// A partial point has more coordinates than there are variables.
+    PartialPointTooLong { max: usize, actual: usize },
+    /// An evaluation index lies outside of the boolean hypercube.
+    IndexOutOfRange { index: usize, size: usize },
+    /// An evaluation index is given more than once.
+    DuplicateIndex(usize),
+    /// A product has no multiplicands.
+    EmptyProduct,
+    /// A multiplicand has a different number of variables than the
+    /// polynomial it is added to.
+    NumVarsMismatch { expected: usize, actual: usize },
+    /// A handle does not refer to an MLE registered with the polynomial.
+    UnknownHandle(usize),
+    /// An expression has a non-zero constant term.
+    ConstantTerm,
+    /// A product has more multiplicands than the maximal degree of the
+    /// polynomial.
+    DegreeTooHigh { max_degree: usize, degree: usize },
+    /// The boolean hypercube over this many variables has more points than
+    /// fit in a `usize`.
+    TooManyVariables(usize),
+}
+
+impl Display for MleError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            MleError::InvalidEvaluationsSize { expected, actual } => write!(
+                f,
+                "The size of evaluations should be 2^num_vars: {} vs {}",
+                actual, expected
+            ),
+            MleError::PointSizeMismatch { expected, actual } => {
+                write!(f, "wrong number of variables {} vs {}", expected, actual)
+            }
+            MleError::PartialPointTooLong { max, actual } => write!(
+                f,
+                "invalid size of partial point: {} for {} variables",
+                actual, max
+            ),
+            MleError::IndexOutOfRange { index, size } => write!(
+                f,
+                "evaluation index {} is out of range for {} points",
+                index, size
+            ),
+            MleError::DuplicateIndex(index) => {
+                write!(f, "evaluation index {} is given more than once", index)
+            }
+            MleError::EmptyProduct => write!(f, "input mle_list is empty"),
+            MleError::NumVarsMismatch { expected, actual } => write!(
+                f,
+                "product has a multiplicand with wrong number of variables {} vs {}",
+                actual, expected
+            ),
+            MleError::UnknownHandle(index) => write!(f, "unknown mle handle {}", index),
+            MleError::ConstantTerm => write!(f, "expression has a non-zero constant term"),
+            MleError::DegreeTooHigh { max_degree, degree } => write!(
+                f,
+                "product has {} multiplicands but the max degree is {}",
+                degree, max_degree
+            ),
+            MleError::TooManyVariables(num_vars) => {
+                write!(f, "{} variables do not fit in memory", num_vars)
+            }
+        }
+    }
+}
+
+impl std::error::Error for MleError {}
+
+/// Malformed input to the file backed APIs, which report `io::Error`s.
+impl From<MleError> for std::io::Error {
+    fn from(e: MleError) -> Self {
+        std::io::Error::new(std::io::ErrorKind::InvalidInput, e)
+    }
+}
@@ -0,0 +1,189 @@
+//! Symbolic expressions over the MLEs registered in a `VirtualPolynomial`.
+//!
+//! An expression such as `q_l * w1 + q_r * w2 + q_m * w1 * w2 - q_o * w3 + q_c`
+//! is written with the usual operators on `Expression`s, and is expanded
+//! into a sum of products when it is added to a virtual polynomial.
+
+use std::{
+    collections::BTreeMap,
+    ops::{Add, Mul, Neg, Sub},
+};
+
+use ff::PrimeField;
+use serde::{Deserialize, Serialize};
+
+use crate::virtual_poly::MleHandle;
+
+/// A polynomial expression over registered MLEs.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Expression<F: PrimeField> {
+    /// A constant
+    Constant(F),
+    /// A registered MLE
+    Mle(MleHandle),
+    /// The sum of two expressions
+    Sum(Box<Expression<F>>, Box<Expression<F>>),
+    /// The product of two expressions
+    Product(Box<Expression<F>>, Box<Expression<F>>),
+    /// The negation of an expression
+    Negated(Box<Expression<F>>),
+    /// An expression raised to a power
+    Pow(Box<Expression<F>>, usize),
+}
+
+impl<F: PrimeField> Expression<F> {
+    pub fn constant(value: F) -> Self {
+        Expression::Constant(value)
+    }
+
+    pub fn mle(handle: MleHandle) -> Self {
+        Expression::Mle(handle)
+    }
+
+    /// Raise self to the power `exponent`.
+    pub fn pow(self, exponent: usize) -> Self {
+        Expression::Pow(Box::new(self), exponent)
+    }
+
+    /// The total degree of the expression, counting every MLE as degree one.
+    /// This is the `max_degree` it contributes to a virtual polynomial.
+    pub fn degree(&self) -> usize {
+        match self {
+            Expression::Constant(_) => 0,
+            Expression::Mle(_) => 1,
+            Expression::Sum(a, b) => a.degree().max(b.degree()),
+            Expression::Product(a, b) => a.degree() + b.degree(),
+            Expression::Negated(a) => a.degree(),
+            Expression::Pow(a, exponent) => a.degree() * exponent,
+        }
+    }
+
+    /// Evaluate the expression given the evaluation of every MLE it refers to.
+    pub fn evaluate(&self, mle_evaluation: &impl Fn(MleHandle) -> F) -> F {
+        match self {
+            Expression::Constant(value) => *value,
+            Expression::Mle(handle) => mle_evaluation(*handle),
+            Expression::Sum(a, b) => a.evaluate(mle_evaluation) + b.evaluate(mle_evaluation),
+            Expression::Product(a, b) => a.evaluate(mle_evaluation) * b.evaluate(mle_evaluation),
+            Expression::Negated(a) => -a.evaluate(mle_evaluation),
+            Expression::Pow(a, exponent) => a.evaluate(mle_evaluation).pow([*exponent as u64]),
+        }
+    }
+
+    /// Expand the expression into a sum of products `(coefficient, handles)`.
+    /// Equal products are merged and products with a zero coefficient are
+    /// dropped; a product without handles is a constant term.
+    pub fn expand(&self) -> Vec<(F, Vec<MleHandle>)> {
+        self.expand_terms()
+            .into_iter()
+            .filter(|(_, coefficient)| *coefficient != F::ZERO)
+            .map(|(handles, coefficient)| (coefficient, handles))
+            .collect()
+    }
+
+    /// Expand the expression into a map from the sorted handles of every
+    /// product to its coefficient.
+    fn expand_terms(&self) -> BTreeMap<Vec<MleHandle>, F> {
+        match self {
+            Expression::Constant(value) => BTreeMap::from([(vec![], *value)]),
+            Expression::Mle(handle) => BTreeMap::from([(vec![*handle], F::ONE)]),
+            Expression::Sum(a, b) => {
+                let mut terms = a.expand_terms();
+                for (handles, coefficient) in b.expand_terms() {
+                    *terms.entry(handles).or_insert(F::ZERO) += coefficient;
+                }
+                terms
+            }
+            Expression::Product(a, b) => multiply_terms(&a.expand_terms(), &b.expand_terms()),
+            Expression::Negated(a) => a
+                .expand_terms()
+                .into_iter()
+                .map(|(handles, coefficient)| (handles, -coefficient))
+                .collect(),
+            Expression::Pow(a, exponent) => {
+                let base = a.expand_terms();
+                (0..*exponent).fold(BTreeMap::from([(vec![], F::ONE)]), |acc, _| {
+                    multiply_terms(&acc, &base)
+                })
+            }
+        }
+    }
+}
+
+/// Multiply two sums of products by distributing every pair of products.
+fn multiply_terms<F: PrimeField>(
+    a: &BTreeMap<Vec<MleHandle>, F>,
+    b: &BTreeMap<Vec<MleHandle>, F>,
+) -> BTreeMap<Vec<MleHandle>, F> {
+    let mut terms = BTreeMap::new();
+    for (a_handles, a_coefficient) in a.iter() {
+        for (b_handles, b_coefficient) in b.iter() {
+            let mut handles = [a_handles.as_slice(), b_handles.as_slice()].concat();
+            handles.sort();
+            *terms.entry(handles).or_insert(F::ZERO) += *a_coefficient * b_coefficient;
+        }
+    }
+    terms
+}
+
+impl<F: PrimeField> From<MleHandle> for Expression<F> {
+    fn from(handle: MleHandle) -> Self {
+        Expression::Mle(handle)
+    }
+}
+
+impl<F: PrimeField> Neg for Expression<F> {
+    type Output = Expression<F>;
+    fn neg(self) -> Self::Output {
+        Expression::Negated(Box::new(self))
+    }
+}
+
+impl<F: PrimeField> Neg for &Expression<F> {
+    type Output = Expression<F>;
+    fn neg(self) -> Self::Output {
+        -self.clone()
+    }
+}
+
+macro_rules! impl_binary_op {
+    ($trait:ident, $method:ident, $build:expr) => {
+        impl<F: PrimeField> $trait<Expression<F>> for Expression<F> {
+            type Output = Expression<F>;
+            fn $method(self, other: Expression<F>) -> Self::Output {
+                $build(self, other)
+            }
+        }
+
+        impl<F: PrimeField> $trait<&Expression<F>> for Expression<F> {
+            type Output = Expression<F>;
+            fn $method(self, other: &Expression<F>) -> Self::Output {
+                $build(self, other.clone())
+            }
+        }
+
+        impl<F: PrimeField> $trait<Expression<F>> for &Expression<F> {
+            type Output = Expression<F>;
+            fn $method(self, other: Expression<F>) -> Self::Output {
+                $build(self.clone(), other)
+            }
+        }
+
+        impl<F: PrimeField> $trait<&Expression<F>> for &Expression<F> {
+            type Output = Expression<F>;
+            fn $method(self, other: &Expression<F>) -> Self::Output {
+                $build(self.clone(), other.clone())
+            }
+        }
+    };
+}
+
+impl_binary_op!(Add, add, |a, b| Expression::Sum(Box::new(a), Box::new(b)));
+impl_binary_op!(Sub, sub, |a, b: Expression<F>| Expression::Sum(
+    Box::new(a),
+    Box::new(-b)
+));
+impl_binary_op!(Mul, mul, |a, b| Expression::Product(
+    Box::new(a),
+    Box::new(b)
+));
@@ -0,0 +1,215 @@
+//! Structured multilinear polynomials that are evaluated from a closed form
+//! instead of being stored as a table.
+
+use std::sync::Arc;
+
+use ff::PrimeField;
+
+use crate::{
+    mle::{DenseMultilinearExtension, MultilinearPolynomial},
+    virtual_poly::{build_eq_x_r_vec, eq_eval},
+};
+
+/// The polynomial `eq(x, r)` for a fixed `r`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct EqPolynomial<F: PrimeField> {
+    /// the point `r`
+    pub point: Vec<F>,
+}
+
+impl<F: PrimeField> EqPolynomial<F> {
+    pub fn new(point: &[F]) -> Self {
+        Self {
+            point: point.to_vec(),
+        }
+    }
+}
+
+impl<F: PrimeField> MultilinearPolynomial<F> for EqPolynomial<F> {
+    fn num_vars(&self) -> usize {
+        self.point.len()
+    }
+
+    fn evaluate(&self, point: &[F]) -> F {
+        eq_eval(&self.point, point)
+    }
+
+    fn fix_variables(&self, partial_point: &[F]) -> DenseMultilinearExtension<F> {
+        assert!(
+            partial_point.len() <= self.point.len(),
+            "invalid size of partial point"
+        );
+        // eq((p, x), (r_1, r_2)) = eq(p, r_1) * eq(x, r_2)
+        let (fixed, free) = self.point.split_at(partial_point.len());
+        let scalar = eq_eval(fixed, partial_point);
+        let evaluations = if free.is_empty() {
+            vec![scalar]
+        } else {
+            build_eq_x_r_vec(free)
+                .into_iter()
+                .map(|eval| eval * scalar)
+                .collect()
+        };
+        DenseMultilinearExtension::from_evaluations_vec(free.len(), evaluations)
+    }
+
+    fn evaluations(&self) -> Box<dyn Iterator<Item = F> + '_> {
+        Box::new(self.fix_variables(&[]).evaluations.into_iter())
+    }
+
+    fn evaluation(&self, index: usize) -> F {
+        self.point
+            .iter()
+            .enumerate()
+            .map(|(i, r_i)| {
+                if (index >> i) & 1 == 1 {
+                    *r_i
+                } else {
+                    F::ONE - r_i
+                }
+            })
+            .product()
+    }
+
+    fn into_shared(self: Arc<Self>) -> Arc<dyn MultilinearPolynomial<F>> {
+        self
+    }
+}
+
+/// The identity polynomial `id(x) = \sum_i 2^i x_i`, which evaluates to the
+/// index of every point of the boolean hypercube.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct IdentityPolynomial {
+    /// Number of variables
+    pub num_vars: usize,
+}
+
+impl IdentityPolynomial {
+    pub fn new(num_vars: usize) -> Self {
+        Self { num_vars }
+    }
+}
+
+impl<F: PrimeField> MultilinearPolynomial<F> for IdentityPolynomial {
+    fn num_vars(&self) -> usize {
+        self.num_vars
+    }
+
+    fn evaluate(&self, point: &[F]) -> F {
+        assert_eq!(
+            self.num_vars,
+            point.len(),
+            "MLE size does not match the point"
+        );
+        point
+            .iter()
+            .rev()
+            .fold(F::ZERO, |acc, x_i| acc.double() + x_i)
+    }
+
+    fn fix_variables(&self, partial_point: &[F]) -> DenseMultilinearExtension<F> {
+        assert!(
+            partial_point.len() <= self.num_vars,
+            "invalid size of partial point"
+        );
+        // id(p, x) = id(p) + 2^k * id(x) for k = |p|
+        let offset = MultilinearPolynomial::<F>::evaluate(
+            &IdentityPolynomial::new(partial_point.len()),
+            partial_point,
+        );
+        let scale = F::from(1 << partial_point.len());
+        let nv = self.num_vars - partial_point.len();
+        DenseMultilinearExtension::from_evaluations_vec(
+            nv,
+            (0..1u64 << nv)
+                .map(|index| offset + scale * F::from(index))
+                .collect(),
+        )
+    }
+
+    fn evaluations(&self) -> Box<dyn Iterator<Item = F> + '_> {
+        Box::new((0..1u64 << self.num_vars).map(F::from))
+    }
+
+    fn evaluation(&self, index: usize) -> F {
+        F::from(index as u64)
+    }
+
+    fn into_shared(self: Arc<Self>) -> Arc<dyn MultilinearPolynomial<F>> {
+        self
+    }
+}
+
+/// The selector polynomial that is one on the first `num_instances` points
+/// of the boolean hypercube and zero on the rest.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SelectorPolynomial {
+    /// Number of variables
+    pub num_vars: usize,
+    /// Number of points, in index order, at which the selector is one
+    pub num_instances: usize,
+}
+
+impl SelectorPolynomial {
+    pub fn new(num_vars: usize, num_instances: usize) -> Self {
+        assert!(
+            num_instances <= 1 << num_vars,
+            "cannot select more than 2^num_vars instances"
+        );
+        Self {
+            num_vars,
+            num_instances,
+        }
+    }
+}
+
+impl<F: PrimeField> MultilinearPolynomial<F> for SelectorPolynomial {
+    fn num_vars(&self) -> usize {
+        self.num_vars
+    }
+
+    fn evaluate(&self, point: &[F]) -> F {
+        assert_eq!(
+            self.num_vars,
+            point.len(),
+            "MLE size does not match the point"
+        );
+        // walk from the most significant variable down: if the remaining
+        // instances cover the lower half, the x_i = 0 half is all ones and the
+        // x_i = 1 half selects what is left; otherwise only the x_i = 0 half
+        // selects anything.
+        let mut res = F::ONE;
+        let mut acc = F::ZERO;
+        let mut remaining = self.num_instances;
+        for (i, x_i) in point.iter().enumerate().rev() {
+            let half = 1 << i;
+            if remaining >= half {
+                acc += res * (F::ONE - x_i);
+                res *= x_i;
+                remaining -= half;
+            } else {
+                res *= F::ONE - x_i;
+            }
+        }
+        if remaining == 1 {
+            acc += res;
+        }
+        acc
+    }
+
+    fn evaluations(&self) -> Box<dyn Iterator<Item = F> + '_> {
+        Box::new((0..1 << self.num_vars).map(|index| F::from((index < self.num_instances) as u64)))
+    }
+
+    fn evaluation(&self, index: usize) -> F {
+        F::from((index < self.num_instances) as u64)
+    }
+
+    fn support(&self) -> Option<Box<dyn Iterator<Item = usize> + '_>> {
+        Some(Box::new(0..self.num_instances))
+    }
+
+    fn into_shared(self: Arc<Self>) -> Arc<dyn MultilinearPolynomial<F>> {
+        self
+    }
+}
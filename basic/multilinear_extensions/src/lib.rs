@@ -1,4 +1,10 @@
+pub mod coefficients;
+pub mod error;
+pub mod expression;
+pub mod lazy_mle;
+pub mod merge;
 pub mod mle;
+pub mod mmap_mle;
 pub mod util;
 pub mod virtual_poly;
 
@@ -0,0 +1,108 @@
+//! Merging `k` MLEs of `nv` variables into a single MLE of
+//! `nv + ceil(log k)` variables, for batching them in one commitment or
+//! sumcheck.
+//!
+//! Part `j` occupies the `j`-th block of `2^nv` evaluations of the merged
+//! table, so the selector variables are the most significant ones:
+//!
+//!   merged(x, s) = \sum_j eq(s, j) * f_j(x)
+//!
+//! When `k` is not a power of two the missing parts are zero.
+
+use ff::PrimeField;
+
+use crate::{mle::DenseMultilinearExtension, virtual_poly::build_eq_x_r_vec};
+
+/// Number of selector variables needed to merge `num_parts` MLEs.
+pub fn num_selector_vars(num_parts: usize) -> usize {
+    assert_ne!(num_parts, 0, "cannot merge zero MLEs");
+    num_parts.next_power_of_two().trailing_zeros() as usize
+}
+
+/// Merge MLEs of the same number of variables into one, with part `j` at
+/// the selector index `j`.
+pub fn merge<F: PrimeField>(
+    mles: &[&DenseMultilinearExtension<F>],
+) -> DenseMultilinearExtension<F> {
+    let selector_nv = num_selector_vars(mles.len());
+    let nv = mles[0].num_vars;
+    assert!(
+        mles.iter().all(|mle| mle.num_vars == nv),
+        "cannot merge MLEs with different number of variables"
+    );
+
+    let mut evaluations = Vec::with_capacity(1 << (nv + selector_nv));
+    for mle in mles {
+        evaluations.extend_from_slice(&mle.evaluations);
+    }
+    evaluations.resize(1 << (nv + selector_nv), F::ZERO);
+    DenseMultilinearExtension::from_evaluations_vec(nv + selector_nv, evaluations)
+}
+
+/// Split a merged MLE back into its first `num_parts` parts.
+pub fn split<F: PrimeField>(
+    merged: &DenseMultilinearExtension<F>,
+    num_parts: usize,
+) -> Vec<DenseMultilinearExtension<F>> {
+    let selector_nv = num_selector_vars(num_parts);
+    assert!(
+        selector_nv <= merged.num_vars,
+        "too many parts {} for {} variables",
+        num_parts,
+        merged.num_vars
+    );
+    let nv = merged.num_vars - selector_nv;
+    merged
+        .evaluations
+        .chunks(1 << nv)
+        .take(num_parts)
+        .map(|evaluations| DenseMultilinearExtension::from_evaluations_slice(nv, evaluations))
+        .collect()
+}
+
+/// The point of the merged MLE at which it evaluates to part `part` at
+/// `point`.
+pub fn part_point<F: PrimeField>(point: &[F], part: usize, selector_nv: usize) -> Vec<F> {
+    assert!(part < 1 << selector_nv, "part index out of range");
+    point
+        .iter()
+        .copied()
+        .chain((0..selector_nv).map(|i| F::from(((part >> i) & 1) as u64)))
+        .collect()
+}
+
+/// Evaluate the merged MLE at `(point, selector_point)` given the evaluation
+/// of every part at `point`.
+pub fn merge_evaluations<F: PrimeField>(part_evaluations: &[F], selector_point: &[F]) -> F {
+    assert!(
+        part_evaluations.len() <= 1 << selector_point.len(),
+        "too many parts {} for {} selector variables",
+        part_evaluations.len(),
+        selector_point.len()
+    );
+    if selector_point.is_empty() {
+        return part_evaluations.first().copied().unwrap_or(F::ZERO);
+    }
+    build_eq_x_r_vec(selector_point)
+        .iter()
+        .zip(part_evaluations.iter())
+        .map(|(weight, evaluation)| *weight * evaluation)
+        .sum()
+}
+
+/// The evaluations at `point` of the first `num_parts` parts of a merged MLE,
+/// read off the merged MLE with its low variables fixed at `point`.
+pub fn split_evaluations<F: PrimeField>(
+    merged: &DenseMultilinearExtension<F>,
+    point: &[F],
+    num_parts: usize,
+) -> Vec<F> {
+    assert_eq!(
+        point.len() + num_selector_vars(num_parts),
+        merged.num_vars,
+        "point does not leave exactly the selector variables free"
+    );
+    let mut evaluations = merged.fix_variables(point).evaluations;
+    evaluations.truncate(num_parts);
+    evaluations
+}
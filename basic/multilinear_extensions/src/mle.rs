@@ -1,9 +1,119 @@
-use std::sync::Arc;
+use std::{fmt::Debug, ops::Add, sync::Arc};
 
 use ark_std::{end_timer, rand::RngCore, start_timer};
-use ff::Field;
+use ff::{Field, PrimeField};
+use goldilocks::{
+    packed::{
+        as_goldilocks, as_goldilocks_slice, as_goldilocks_slice_mut, Backend, PackedArith,
+        PackedGoldilocks, PackedKernel, WIDTH,
+    },
+    Goldilocks,
+};
+use rayon::{
+    prelude::{
+        IndexedParallelIterator, IntoParallelRefIterator, IntoParallelRefMutIterator,
+        ParallelIterator,
+    },
+    slice::{ParallelSlice, ParallelSliceMut},
+};
 use serde::{Deserialize, Serialize};
 
+use crate::{error::MleError, virtual_poly::build_eq_x_r_vec};
+
+/// Number of `PackedGoldilocks` a rayon task folds in one `Backend::run`.
+const PACKED_MIN_LEN: usize = 64;
+
+/// `fix_variables_in_place` folds chunks of 2^`IN_PLACE_CHUNK_VARS`
+/// evaluations in parallel.
+const IN_PLACE_CHUNK_VARS: usize = 12;
+
+/// `res[i] = data[2i] + (data[2i + 1] - data[2i]) * point`, `WIDTH` entries
+/// of `res` at a time.
+struct FoldKernel<'a> {
+    data: &'a [Goldilocks],
+    point: Goldilocks,
+    res: &'a mut [Goldilocks],
+}
+
+impl PackedKernel for FoldKernel<'_> {
+    type Output = ();
+
+    #[inline(always)]
+    fn run<A: PackedArith>(self, arith: A) {
+        let point = PackedGoldilocks::broadcast(self.point);
+        self.res
+            .chunks_exact_mut(WIDTH)
+            .zip(self.data.chunks_exact(2 * WIDTH))
+            .for_each(|(res, data)| {
+                let (low, high) = PackedGoldilocks::deinterleave(data);
+                let folded = arith.add(low, arith.mul(arith.sub(high, low), point));
+                res.copy_from_slice(folded.as_slice());
+            });
+    }
+}
+
+/// A multilinear polynomial over {0,1}^`num_vars`, however it is stored.
+///
+/// Points of the boolean hypercube are indexed in little endian form, so the
+/// evaluations are listed with the first variable varying fastest.
+pub trait MultilinearPolynomial<F: Field>: Debug + Send + Sync {
+    /// Number of variables
+    fn num_vars(&self) -> usize;
+
+    /// Evaluate the polynomial at a give point.
+    fn evaluate(&self, point: &[F]) -> F;
+
+    /// Reduce the number of variables by fixing the first
+    /// `partial_point.len()` variables at `partial_point`, and return the
+    /// result as a dense table.
+    fn fix_variables(&self, partial_point: &[F]) -> DenseMultilinearExtension<F> {
+        self.to_dense().fix_variables(partial_point)
+    }
+
+    /// Same as `fix_variables`, but keeps the result in the form of `self`
+    /// where it can, so that a sparse polynomial stays sparse.
+    fn fix_variables_shared(&self, partial_point: &[F]) -> Arc<dyn MultilinearPolynomial<F>> {
+        Arc::new(self.fix_variables(partial_point))
+    }
+
+    /// The evaluations over {0,1}^`num_vars`, in index order.
+    fn evaluations(&self) -> Box<dyn Iterator<Item = F> + '_>;
+
+    /// The evaluation at the point of {0,1}^`num_vars` with the given index.
+    fn evaluation(&self, index: usize) -> F;
+
+    /// The indices, in increasing order, of the only points at which the
+    /// polynomial can be non-zero, or `None` if it is not sparse.
+    fn support(&self) -> Option<Box<dyn Iterator<Item = usize> + '_>> {
+        None
+    }
+
+    /// The dense table of the polynomial, if it is stored as one.
+    fn as_dense(&self) -> Option<&DenseMultilinearExtension<F>> {
+        None
+    }
+
+    /// Expand the polynomial into its dense form.
+    fn to_dense(&self) -> DenseMultilinearExtension<F> {
+        DenseMultilinearExtension::from_evaluations_vec(
+            self.num_vars(),
+            self.evaluations().collect(),
+        )
+    }
+
+    /// Expand a shared polynomial into its dense form, without copying the
+    /// table if it already is dense.
+    fn into_dense(self: Arc<Self>) -> Arc<DenseMultilinearExtension<F>> {
+        Arc::new(self.to_dense())
+    }
+
+    /// Share the polynomial as a `dyn MultilinearPolynomial`, without
+    /// copying it if its type allows.
+    fn into_shared(self: Arc<Self>) -> Arc<dyn MultilinearPolynomial<F>> {
+        self.fix_variables_shared(&[])
+    }
+}
+
 /// Stores a multilinear polynomial in dense evaluation form.
 #[derive(Clone, PartialEq, Eq, Hash, Default, Debug, Serialize, Deserialize)]
 pub struct DenseMultilinearExtension<F: Field> {
@@ -25,40 +135,64 @@ impl<F: Field> DenseMultilinearExtension<F> {
     /// represents a point in {0,1}^`num_vars` in little endian form. For
     /// example, `0b1011` represents `P(1,1,0,1)`
     pub fn from_evaluations_vec(num_vars: usize, evaluations: Vec<F>) -> Self {
-        // assert that the number of variables matches the size of evaluations
-        // TODO: return error.
-        assert_eq!(
-            evaluations.len(),
-            1 << num_vars,
-            "The size of evaluations should be 2^num_vars."
-        );
+        Self::try_from_evaluations_vec(num_vars, evaluations).unwrap_or_else(|e| panic!("{}", e))
+    }
 
-        Self {
+    /// Same as `from_evaluations_vec`, but returns an error if the size of
+    /// evaluations is not 2^`num_vars`.
+    pub fn try_from_evaluations_vec(
+        num_vars: usize,
+        evaluations: Vec<F>,
+    ) -> Result<Self, MleError> {
+        // check that the number of variables matches the size of evaluations
+        let size = hypercube_size(num_vars)?;
+        if evaluations.len() != size {
+            return Err(MleError::InvalidEvaluationsSize {
+                expected: size,
+                actual: evaluations.len(),
+            });
+        }
+
+        Ok(Self {
             num_vars,
             evaluations,
-        }
+        })
     }
 
     /// Evaluate the MLE at a give point.
-    /// Returns an error if the MLE length does not match the point.
+    /// Panics if the MLE length does not match the point; see `try_evaluate`.
     pub fn evaluate(&self, point: &[F]) -> F {
-        // TODO: return error.
-        assert_eq!(
-            self.num_vars,
-            point.len(),
-            "MLE size does not match the point"
-        );
-        self.fix_variables(point).evaluations[0]
+        self.try_evaluate(point).unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    /// Evaluate the MLE at a give point.
+    /// Returns an error if the MLE length does not match the point.
+    pub fn try_evaluate(&self, point: &[F]) -> Result<F, MleError> {
+        if self.num_vars != point.len() {
+            return Err(MleError::PointSizeMismatch {
+                expected: self.num_vars,
+                actual: point.len(),
+            });
+        }
+        Ok(self.fix_variables(point).evaluations[0])
     }
 
     /// Reduce the number of variables of `self` by fixing the
     /// `partial_point.len()` variables at `partial_point`.
+    /// Panics if the partial point is too long; see `try_fix_variables`.
     pub fn fix_variables(&self, partial_point: &[F]) -> DenseMultilinearExtension<F> {
-        // TODO: return error.
-        assert!(
-            partial_point.len() <= self.num_vars,
-            "invalid size of partial point"
-        );
+        self.try_fix_variables(partial_point)
+            .unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    /// Reduce the number of variables of `self` by fixing the
+    /// `partial_point.len()` variables at `partial_point`.
+    /// Returns an error if the partial point is longer than `num_vars`.
+    pub fn try_fix_variables(
+        &self,
+        partial_point: &[F],
+    ) -> Result<DenseMultilinearExtension<F>, MleError> {
+        self.check_partial_point(partial_point)?;
         let nv = self.num_vars;
         let mut poly = self.evaluations.to_vec();
         let dim = partial_point.len();
@@ -67,20 +201,39 @@ impl<F: Field> DenseMultilinearExtension<F> {
             poly = Self::fix_one_variable_helper(&poly, nv - i, point);
         }
 
-        Self::from_evaluations_slice(nv - dim, &poly[..(1 << (nv - dim))])
+        Ok(Self::from_evaluations_slice(
+            nv - dim,
+            &poly[..(1 << (nv - dim))],
+        ))
     }
 
     /// Helper function. Fix 1 variable.
     fn fix_one_variable_helper(data: &[F], nv: usize, point: &F) -> Vec<F> {
         let mut res = vec![F::ZERO; 1 << (nv - 1)];
 
-        // evaluate single variable of partial point from left to right
-        #[cfg(not(feature = "parallel"))]
-        for i in 0..(1 << (nv - 1)) {
-            res[i] = data[i] + (data[(i << 1) + 1] - data[i << 1]) * point;
+        // fast path for Goldilocks, `WIDTH` pairs of evaluations at a time
+        if res.len() >= WIDTH {
+            if let (Some(data), Some(point), Some(packed_res)) = (
+                as_goldilocks_slice(data),
+                as_goldilocks(point),
+                as_goldilocks_slice_mut(&mut res),
+            ) {
+                let backend = Backend::detect();
+                packed_res
+                    .par_chunks_mut(PACKED_MIN_LEN * WIDTH)
+                    .zip(data.par_chunks(PACKED_MIN_LEN * 2 * WIDTH))
+                    .for_each(|(res, data)| {
+                        backend.run(FoldKernel {
+                            data,
+                            point: *point,
+                            res,
+                        })
+                    });
+                return res;
+            }
         }
 
-        #[cfg(feature = "parallel")]
+        // evaluate single variable of partial point from left to right
         res.par_iter_mut().enumerate().for_each(|(i, x)| {
             *x = data[i << 1] + (data[(i << 1) + 1] - data[i << 1]) * point;
         });
@@ -88,6 +241,121 @@ impl<F: Field> DenseMultilinearExtension<F> {
         res
     }
 
+    /// Reduce the number of variables of `self` by fixing the last
+    /// `partial_point.len()` variables at `partial_point`, i.e. the most
+    /// significant bits of an index. For `point = (x, p)`,
+    /// `self.evaluate(point) == self.fix_high_variables(p).evaluate(x)`.
+    /// Panics if the partial point is too long; see `try_fix_high_variables`.
+    pub fn fix_high_variables(&self, partial_point: &[F]) -> DenseMultilinearExtension<F> {
+        self.try_fix_high_variables(partial_point)
+            .unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    /// Same as `fix_high_variables`, but returns an error if the partial
+    /// point is longer than `num_vars`.
+    pub fn try_fix_high_variables(
+        &self,
+        partial_point: &[F],
+    ) -> Result<DenseMultilinearExtension<F>, MleError> {
+        self.check_partial_point(partial_point)?;
+        let Some((last, rest)) = partial_point.split_last() else {
+            return Ok(self.clone());
+        };
+        // fold the highest variable, the last coordinate of the point, out of
+        // place to avoid copying the table; the others are folded in place
+        let half = 1 << (self.num_vars - 1);
+        let (low, high) = self.evaluations.split_at(half);
+        let mut res = Self::from_evaluations_vec(
+            self.num_vars - 1,
+            low.par_iter()
+                .zip(high.par_iter())
+                .map(|(low, high)| *low + (*high - low) * last)
+                .collect(),
+        );
+        res.fix_high_variables_in_place(rest);
+        Ok(res)
+    }
+
+    /// Same as `fix_variables`, but folds the table of `self` in place.
+    /// Panics if the partial point is too long; see
+    /// `try_fix_variables_in_place`.
+    pub fn fix_variables_in_place(&mut self, partial_point: &[F]) {
+        self.try_fix_variables_in_place(partial_point)
+            .unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    /// Same as `fix_variables_in_place`, but returns an error if the partial
+    /// point is longer than `num_vars`, in which case self is left unchanged.
+    pub fn try_fix_variables_in_place(&mut self, partial_point: &[F]) -> Result<(), MleError> {
+        self.check_partial_point(partial_point)?;
+        // the lowest variables of an index stay within its chunk of the
+        // table, so up to `IN_PLACE_CHUNK_VARS` of them are folded at a time
+        // in every chunk in parallel, each chunk into its own front. The
+        // folded fronts are then moved together.
+        for points in partial_point.chunks(IN_PLACE_CHUNK_VARS) {
+            let chunk_size = 1 << self.num_vars.min(IN_PLACE_CHUNK_VARS);
+            let folded_size = chunk_size >> points.len();
+            self.evaluations
+                .par_chunks_mut(chunk_size)
+                .for_each(|chunk| {
+                    for (j, point) in points.iter().enumerate() {
+                        // entry i is written after entries 2i and 2i + 1 are
+                        // read, and never before any entry it is read from
+                        for i in 0..chunk_size >> (j + 1) {
+                            chunk[i] =
+                                chunk[i << 1] + (chunk[(i << 1) + 1] - chunk[i << 1]) * point;
+                        }
+                    }
+                });
+            for k in 1..self.evaluations.len() / chunk_size {
+                let start = k * chunk_size;
+                self.evaluations
+                    .copy_within(start..start + folded_size, k * folded_size);
+            }
+            self.num_vars -= points.len();
+            self.evaluations.truncate(1 << self.num_vars);
+        }
+        Ok(())
+    }
+
+    /// Same as `fix_high_variables`, but folds the table of `self` in place.
+    /// Panics if the partial point is too long; see
+    /// `try_fix_high_variables_in_place`.
+    pub fn fix_high_variables_in_place(&mut self, partial_point: &[F]) {
+        self.try_fix_high_variables_in_place(partial_point)
+            .unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    /// Same as `fix_high_variables_in_place`, but returns an error if the
+    /// partial point is longer than `num_vars`, in which case self is left
+    /// unchanged.
+    pub fn try_fix_high_variables_in_place(&mut self, partial_point: &[F]) -> Result<(), MleError> {
+        self.check_partial_point(partial_point)?;
+        // evaluate single variable of partial point from right to left
+        for point in partial_point.iter().rev() {
+            let half = 1 << (self.num_vars - 1);
+            let (low, high) = self.evaluations.split_at_mut(half);
+            low.par_iter_mut()
+                .zip(high.par_iter())
+                .for_each(|(low, high)| *low += (*high - *low) * point);
+            self.evaluations.truncate(half);
+            self.num_vars -= 1;
+        }
+        Ok(())
+    }
+
+    /// Returns an error if `partial_point` has more than `num_vars`
+    /// coordinates.
+    fn check_partial_point(&self, partial_point: &[F]) -> Result<(), MleError> {
+        if partial_point.len() > self.num_vars {
+            return Err(MleError::PartialPointTooLong {
+                max: self.num_vars,
+                actual: partial_point.len(),
+            });
+        }
+        Ok(())
+    }
+
     /// Sample a random list of multilinear polynomials.
     /// Returns
     /// - the list of polynomials,
@@ -152,3 +420,376 @@ impl<F: Field> DenseMultilinearExtension<F> {
         list
     }
 }
+
+impl<F: Field> MultilinearPolynomial<F> for DenseMultilinearExtension<F> {
+    fn num_vars(&self) -> usize {
+        self.num_vars
+    }
+
+    fn evaluate(&self, point: &[F]) -> F {
+        DenseMultilinearExtension::evaluate(self, point)
+    }
+
+    fn fix_variables(&self, partial_point: &[F]) -> DenseMultilinearExtension<F> {
+        DenseMultilinearExtension::fix_variables(self, partial_point)
+    }
+
+    fn evaluations(&self) -> Box<dyn Iterator<Item = F> + '_> {
+        Box::new(self.evaluations.iter().copied())
+    }
+
+    fn evaluation(&self, index: usize) -> F {
+        self.evaluations[index]
+    }
+
+    fn as_dense(&self) -> Option<&DenseMultilinearExtension<F>> {
+        Some(self)
+    }
+
+    fn to_dense(&self) -> DenseMultilinearExtension<F> {
+        self.clone()
+    }
+
+    fn into_dense(self: Arc<Self>) -> Arc<DenseMultilinearExtension<F>> {
+        self
+    }
+
+    fn into_shared(self: Arc<Self>) -> Arc<dyn MultilinearPolynomial<F>> {
+        self
+    }
+}
+
+impl<F: PrimeField> DenseMultilinearExtension<F> {
+    /// Evaluate several MLEs of the same number of variables at one point.
+    /// `eq(x, point)` is built once and every evaluation is its inner product
+    /// with an evaluation table.
+    pub fn evaluate_many(mles: &[&Self], point: &[F]) -> Vec<F> {
+        assert!(
+            mles.iter().all(|mle| mle.num_vars == point.len()),
+            "MLE size does not match the point"
+        );
+        let eq = eq_table(point);
+        mles.par_iter()
+            .map(|mle| {
+                mle.evaluations
+                    .par_iter()
+                    .zip(eq.par_iter())
+                    .map(|(eval, weight)| *eval * weight)
+                    .sum()
+            })
+            .collect()
+    }
+
+    /// Evaluate the MLE at several points in parallel.
+    ///
+    /// Each point is split into a low and a high half, and the evaluation is
+    /// `\sum_h eq(h, high) \sum_l eq(l, low) f(l, h)`, which needs two eq
+    /// tables of `2^(nv / 2)` entries instead of a copy of the table.
+    pub fn evaluate_at_points(&self, points: &[Vec<F>]) -> Vec<F> {
+        assert!(
+            points.iter().all(|point| point.len() == self.num_vars),
+            "MLE size does not match the point"
+        );
+        points
+            .par_iter()
+            .map(|point| {
+                let (low, high) = point.split_at(self.num_vars / 2);
+                let (eq_low, eq_high) = (eq_table(low), eq_table(high));
+                self.evaluations
+                    .par_chunks(eq_low.len())
+                    .zip(eq_high.par_iter())
+                    .map(|(chunk, weight)| {
+                        chunk
+                            .iter()
+                            .zip(eq_low.iter())
+                            .map(|(eval, eq_low)| *eval * eq_low)
+                            .sum::<F>()
+                            * weight
+                    })
+                    .sum()
+            })
+            .collect()
+    }
+}
+
+/// The evaluations of `eq(x, point)` over the boolean hypercube, including
+/// the constant one for an empty point.
+pub(crate) fn eq_table<F: PrimeField>(point: &[F]) -> Vec<F> {
+    if point.is_empty() {
+        vec![F::ONE]
+    } else {
+        build_eq_x_r_vec(point)
+    }
+}
+
+/// The number of points of {0,1}^`num_vars`, or an error if it does not fit
+/// in a `usize`.
+pub(crate) fn hypercube_size(num_vars: usize) -> Result<usize, MleError> {
+    u32::try_from(num_vars)
+        .ok()
+        .and_then(|shift| 1usize.checked_shl(shift))
+        .ok_or(MleError::TooManyVariables(num_vars))
+}
+
+/// Stores a multilinear polynomial in sparse evaluation form: only the points
+/// of {0,1}^`num_vars` with a non-zero evaluation are kept.
+#[derive(Clone, PartialEq, Eq, Hash, Default, Debug, Serialize, Deserialize)]
+pub struct SparseMultilinearExtension<F: Field> {
+    /// The non-zero evaluations as `(index, value)` pairs sorted by index,
+    /// where the index represents a point in {0,1}^`num_vars` in little endian
+    /// form.
+    pub evaluations: Vec<(usize, F)>,
+    /// Number of variables
+    pub num_vars: usize,
+}
+
+impl<F: Field> SparseMultilinearExtension<F> {
+    /// Construct a new polynomial from a list of `(index, value)` pairs, where
+    /// every index not in the list evaluates to zero. The pairs may be given
+    /// in any order, but an index may appear at most once.
+    pub fn from_evaluations(
+        num_vars: usize,
+        evaluations: impl IntoIterator<Item = (usize, F)>,
+    ) -> Self {
+        Self::try_from_evaluations(num_vars, evaluations).unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    /// Same as `from_evaluations`, but returns an error if an index is out of
+    /// range or given more than once.
+    pub fn try_from_evaluations(
+        num_vars: usize,
+        evaluations: impl IntoIterator<Item = (usize, F)>,
+    ) -> Result<Self, MleError> {
+        let mut evaluations: Vec<(usize, F)> = evaluations.into_iter().collect();
+        evaluations.sort_by_key(|(index, _)| *index);
+        if let Some(pair) = evaluations.windows(2).find(|pair| pair[0].0 == pair[1].0) {
+            return Err(MleError::DuplicateIndex(pair[0].0));
+        }
+        let size = hypercube_size(num_vars)?;
+        if let Some((index, _)) = evaluations.last() {
+            if *index >= size {
+                return Err(MleError::IndexOutOfRange {
+                    index: *index,
+                    size,
+                });
+            }
+        }
+        // zeros are checked like every other pair, but not stored
+        evaluations.retain(|(_, value)| *value != F::ZERO);
+
+        Ok(Self {
+            num_vars,
+            evaluations,
+        })
+    }
+
+    /// Construct the sparse form of a dense polynomial.
+    pub fn from_dense(dense: &DenseMultilinearExtension<F>) -> Self {
+        Self {
+            num_vars: dense.num_vars,
+            evaluations: dense
+                .evaluations
+                .iter()
+                .enumerate()
+                .filter(|(_, value)| **value != F::ZERO)
+                .map(|(index, value)| (index, *value))
+                .collect(),
+        }
+    }
+
+    /// Expand the polynomial into its dense form.
+    pub fn to_dense(&self) -> DenseMultilinearExtension<F> {
+        let mut evaluations = vec![F::ZERO; 1 << self.num_vars];
+        for (index, value) in self.evaluations.iter() {
+            evaluations[*index] = *value;
+        }
+        DenseMultilinearExtension::from_evaluations_vec(self.num_vars, evaluations)
+    }
+
+    /// Evaluate the MLE at a give point.
+    /// Panics if the MLE length does not match the point; see `try_evaluate`.
+    pub fn evaluate(&self, point: &[F]) -> F {
+        self.try_evaluate(point).unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    /// Evaluate the MLE at a give point.
+    /// Returns an error if the MLE length does not match the point.
+    pub fn try_evaluate(&self, point: &[F]) -> Result<F, MleError> {
+        if self.num_vars != point.len() {
+            return Err(MleError::PointSizeMismatch {
+                expected: self.num_vars,
+                actual: point.len(),
+            });
+        }
+        Ok(self
+            .fix_variables(point)
+            .evaluations
+            .first()
+            .map_or(F::ZERO, |(_, value)| *value))
+    }
+
+    /// Reduce the number of variables of `self` by fixing the
+    /// `partial_point.len()` variables at `partial_point`. The result stays
+    /// sparse, with at most as many non-zero evaluations as `self`.
+    /// Panics if the partial point is too long; see `try_fix_variables`.
+    pub fn fix_variables(&self, partial_point: &[F]) -> SparseMultilinearExtension<F> {
+        self.try_fix_variables(partial_point)
+            .unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    /// Reduce the number of variables of `self` by fixing the
+    /// `partial_point.len()` variables at `partial_point`.
+    /// Returns an error if the partial point is longer than `num_vars`.
+    pub fn try_fix_variables(
+        &self,
+        partial_point: &[F],
+    ) -> Result<SparseMultilinearExtension<F>, MleError> {
+        if partial_point.len() > self.num_vars {
+            return Err(MleError::PartialPointTooLong {
+                max: self.num_vars,
+                actual: partial_point.len(),
+            });
+        }
+        let mut evaluations = self.evaluations.clone();
+        // evaluate single variable of partial point from left to right
+        for point in partial_point.iter() {
+            evaluations = Self::fix_one_variable_helper(&evaluations, point);
+        }
+
+        Ok(Self {
+            num_vars: self.num_vars - partial_point.len(),
+            evaluations,
+        })
+    }
+
+    /// Helper function. Fix 1 variable.
+    fn fix_one_variable_helper(data: &[(usize, F)], point: &F) -> Vec<(usize, F)> {
+        let mut res: Vec<(usize, F)> = Vec::with_capacity(data.len());
+        // the entries 2i and 2i + 1 are adjacent in the sorted list, and both
+        // fold into entry i; a missing entry is zero.
+        for (index, value) in data.iter() {
+            let folded = if index & 1 == 0 {
+                *value - *value * point
+            } else {
+                *value * point
+            };
+            match res.last_mut() {
+                Some((last, last_value)) if *last == index >> 1 => *last_value += folded,
+                _ => res.push((index >> 1, folded)),
+            }
+        }
+        res.retain(|(_, value)| *value != F::ZERO);
+        res
+    }
+
+    /// Sample a random polynomial with `num_nonzero` non-zero evaluations at
+    /// random points.
+    pub fn random(nv: usize, num_nonzero: usize, mut rng: &mut impl RngCore) -> Self {
+        assert!(
+            num_nonzero <= 1 << nv,
+            "cannot have more than 2^nv non-zero evaluations"
+        );
+        let mut indices = Vec::with_capacity(num_nonzero);
+        while indices.len() < num_nonzero {
+            let index = (rng.next_u64() as usize) & ((1 << nv) - 1);
+            if !indices.contains(&index) {
+                indices.push(index);
+            }
+        }
+        Self::from_evaluations(
+            nv,
+            indices
+                .into_iter()
+                .map(|index| (index, F::random(&mut rng)))
+                .collect::<Vec<_>>(),
+        )
+    }
+}
+
+impl<F: Field> MultilinearPolynomial<F> for SparseMultilinearExtension<F> {
+    fn num_vars(&self) -> usize {
+        self.num_vars
+    }
+
+    fn evaluate(&self, point: &[F]) -> F {
+        SparseMultilinearExtension::evaluate(self, point)
+    }
+
+    fn fix_variables(&self, partial_point: &[F]) -> DenseMultilinearExtension<F> {
+        SparseMultilinearExtension::fix_variables(self, partial_point).to_dense()
+    }
+
+    fn fix_variables_shared(&self, partial_point: &[F]) -> Arc<dyn MultilinearPolynomial<F>> {
+        Arc::new(SparseMultilinearExtension::fix_variables(
+            self,
+            partial_point,
+        ))
+    }
+
+    fn evaluations(&self) -> Box<dyn Iterator<Item = F> + '_> {
+        let mut non_zero = self.evaluations.iter().peekable();
+        Box::new((0..1 << self.num_vars).map(move |index| {
+            match non_zero.next_if(|(non_zero_index, _)| *non_zero_index == index) {
+                Some((_, value)) => *value,
+                None => F::ZERO,
+            }
+        }))
+    }
+
+    fn evaluation(&self, index: usize) -> F {
+        self.evaluations
+            .binary_search_by_key(&index, |(index, _)| *index)
+            .map_or(F::ZERO, |position| self.evaluations[position].1)
+    }
+
+    fn support(&self) -> Option<Box<dyn Iterator<Item = usize> + '_>> {
+        Some(Box::new(self.evaluations.iter().map(|(index, _)| *index)))
+    }
+
+    fn to_dense(&self) -> DenseMultilinearExtension<F> {
+        SparseMultilinearExtension::to_dense(self)
+    }
+
+    fn into_shared(self: Arc<Self>) -> Arc<dyn MultilinearPolynomial<F>> {
+        self
+    }
+}
+
+impl<F: Field> Add for &SparseMultilinearExtension<F> {
+    type Output = SparseMultilinearExtension<F>;
+    fn add(self, other: &SparseMultilinearExtension<F>) -> Self::Output {
+        assert_eq!(
+            self.num_vars, other.num_vars,
+            "cannot add polynomials with different number of variables {} vs {}",
+            self.num_vars, other.num_vars
+        );
+        let mut evaluations = Vec::with_capacity(self.evaluations.len() + other.evaluations.len());
+        let (mut lhs, mut rhs) = (
+            self.evaluations.iter().peekable(),
+            other.evaluations.iter().peekable(),
+        );
+        // merge the two sorted lists
+        loop {
+            let next = match (lhs.peek(), rhs.peek()) {
+                (Some((i, a)), Some((j, b))) if i == j => {
+                    let sum = (*i, *a + b);
+                    lhs.next();
+                    rhs.next();
+                    sum
+                }
+                (Some((i, _)), Some((j, _))) if i > j => *rhs.next().unwrap(),
+                (Some(_), _) => *lhs.next().unwrap(),
+                (None, Some(_)) => *rhs.next().unwrap(),
+                (None, None) => break,
+            };
+            if next.1 != F::ZERO {
+                evaluations.push(next);
+            }
+        }
+
+        SparseMultilinearExtension {
+            num_vars: self.num_vars,
+            evaluations,
+        }
+    }
+}
@@ -0,0 +1,666 @@
+//! Multilinear polynomials whose evaluation tables live in a file on disk and
+//! are memory-mapped, for tables that do not fit in memory.
+//!
+//! The file holds the evaluations in index order, each one in the
+//! `PrimeField::Repr` encoding of the field, without any header. Fixing
+//! variables reads the table in blocks; `fix_variables` loads the result into
+//! a `DenseMultilinearExtension` if it has at most `MAX_IN_MEMORY_VARS`
+//! variables, and writes it to a new file if it has more.
+//!
+//! The serde encoding is the one of `DenseMultilinearExtension`, so a
+//! checkpoint written from either can be read back as either; use
+//! `MmapMleSeed` to read one back into a file without holding the table in
+//! memory.
+
+use std::{
+    fmt::{Debug, Formatter},
+    fs::{self, File},
+    io::{self, BufWriter, Write},
+    marker::PhantomData,
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+use ff::PrimeField;
+use memmap2::Mmap;
+use rayon::{
+    prelude::{IntoParallelIterator, ParallelIterator},
+    slice::ParallelSlice,
+};
+use serde::{
+    de::{self, DeserializeSeed, MapAccess, SeqAccess, Visitor},
+    ser::{SerializeSeq, SerializeStruct},
+    Deserialize, Deserializer, Serialize, Serializer,
+};
+
+use crate::{
+    error::MleError,
+    mle::{eq_table, hypercube_size, DenseMultilinearExtension, MultilinearPolynomial},
+};
+
+/// Stores a multilinear polynomial in dense evaluation form, in a
+/// memory-mapped file.
+pub struct MmapMultilinearExtension<F: PrimeField> {
+    /// The evaluation over {0,1}^`num_vars`, encoded
+    mmap: Mmap,
+    /// Number of variables
+    pub num_vars: usize,
+    phantom: PhantomData<F>,
+}
+
+/// Number of bytes of an encoded field element.
+fn repr_size<F: PrimeField>() -> usize {
+    F::Repr::default().as_ref().len()
+}
+
+impl<F: PrimeField> MmapMultilinearExtension<F> {
+    /// Write the evaluations to a new file at `path` and map it. The index of
+    /// an evaluation represents a point in {0,1}^`num_vars` in little endian
+    /// form.
+    /// Returns an error if the file cannot be written, or if there are not
+    /// 2^`num_vars` evaluations. In that case no file is left at `path`.
+    pub fn create(
+        path: impl AsRef<Path>,
+        num_vars: usize,
+        evaluations: impl IntoIterator<Item = F>,
+    ) -> io::Result<Self> {
+        let size = hypercube_size(num_vars)?;
+        let path = path.as_ref();
+        let file = File::create(path)?;
+        let created =
+            Self::write_evaluations(file, size, evaluations).and_then(|()| Self::open(path));
+        if created.is_err() {
+            // do not leave a partial or oversized table behind
+            let _ = fs::remove_file(path);
+        }
+        created
+    }
+
+    /// Write exactly `size` evaluations to `file`.
+    /// Returns an error if the file cannot be written, or if there are fewer
+    /// or more evaluations. Writing stops at the first evaluation too many,
+    /// and the rest of `evaluations` is not consumed.
+    fn write_evaluations(
+        file: File,
+        size: usize,
+        evaluations: impl IntoIterator<Item = F>,
+    ) -> io::Result<()> {
+        let mut writer = BufWriter::new(file);
+        let mut count = 0usize;
+        for evaluation in evaluations {
+            if count == size {
+                return Err(MleError::InvalidEvaluationsSize {
+                    expected: size,
+                    actual: count + 1,
+                }
+                .into());
+            }
+            writer.write_all(evaluation.to_repr().as_ref())?;
+            count += 1;
+        }
+        writer.flush()?;
+        if count != size {
+            return Err(MleError::InvalidEvaluationsSize {
+                expected: size,
+                actual: count,
+            }
+            .into());
+        }
+        Ok(())
+    }
+
+    /// Write a dense polynomial to a new file at `path` and map it.
+    pub fn from_dense(
+        path: impl AsRef<Path>,
+        dense: &DenseMultilinearExtension<F>,
+    ) -> io::Result<Self> {
+        Self::create(path, dense.num_vars, dense.evaluations.iter().copied())
+    }
+
+    /// Map an existing file of evaluations. The number of variables is read
+    /// off the size of the file.
+    /// Returns an error if the size of the file is not that of 2^`num_vars`
+    /// evaluations, or if it holds an encoding that is not a canonical field
+    /// element.
+    ///
+    /// The file must not be modified while it is mapped.
+    pub fn open(path: impl AsRef<Path>) -> io::Result<Self> {
+        let file = File::open(path)?;
+        let len = file.metadata()?.len() as usize;
+        let size = len / repr_size::<F>();
+        if len % repr_size::<F>() != 0 || !size.is_power_of_two() {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                MleError::InvalidEvaluationsSize {
+                    expected: size.next_power_of_two(),
+                    actual: size,
+                },
+            ));
+        }
+        // SAFETY: the mapping is read-only, and the file is not modified for
+        // as long as it is mapped, as required above.
+        let mmap = unsafe { Mmap::map(&file)? };
+        if !mmap
+            .par_chunks(repr_size::<F>())
+            .all(|bytes| decode::<F>(bytes).is_some())
+        {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "invalid field element in evaluation file",
+            ));
+        }
+        Ok(Self {
+            mmap,
+            num_vars: size.trailing_zeros() as usize,
+            phantom: PhantomData,
+        })
+    }
+
+    /// The evaluation at index `index`.
+    pub fn get(&self, index: usize) -> F {
+        let size = repr_size::<F>();
+        decode_checked(&self.mmap[index * size..(index + 1) * size])
+    }
+
+    /// Write the evaluations at indices `start..start + buf.len()` into `buf`.
+    pub fn read(&self, start: usize, buf: &mut [F]) {
+        let size = repr_size::<F>();
+        self.mmap[start * size..(start + buf.len()) * size]
+            .chunks_exact(size)
+            .zip(buf.iter_mut())
+            .for_each(|(bytes, evaluation)| *evaluation = decode_checked(bytes));
+    }
+
+    /// Load the whole table into memory.
+    pub fn to_dense(&self) -> DenseMultilinearExtension<F> {
+        let mut evaluations = vec![F::ZERO; 1 << self.num_vars];
+        self.read(0, &mut evaluations);
+        DenseMultilinearExtension::from_evaluations_vec(self.num_vars, evaluations)
+    }
+
+    /// Evaluate the MLE at a give point.
+    /// Panics if the MLE length does not match the point; see `try_evaluate`.
+    pub fn evaluate(&self, point: &[F]) -> F {
+        self.try_evaluate(point).unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    /// Evaluate the MLE at a give point.
+    /// Returns an error if the MLE length does not match the point.
+    pub fn try_evaluate(&self, point: &[F]) -> Result<F, MleError> {
+        if self.num_vars != point.len() {
+            return Err(MleError::PointSizeMismatch {
+                expected: self.num_vars,
+                actual: point.len(),
+            });
+        }
+        // a single block: spread its rows over the threads instead
+        let (eq_low, eq_high) = split_eq_tables(point);
+        Ok((0..eq_high.len())
+            .into_par_iter()
+            .map_init(
+                || vec![F::ZERO; eq_low.len()],
+                |buf, i| self.fold_row(i * eq_low.len(), &eq_low, buf) * eq_high[i],
+            )
+            .sum())
+    }
+
+    /// Reduce the number of variables of `self` by fixing the
+    /// `partial_point.len()` variables at `partial_point`.
+    ///
+    /// The result is loaded into memory if it has at most
+    /// `MAX_IN_MEMORY_VARS` variables, and written to a new file at `path`
+    /// otherwise.
+    /// Returns an error if the partial point is longer than `num_vars`, or if
+    /// the file cannot be written.
+    pub fn fix_variables(
+        &self,
+        partial_point: &[F],
+        path: impl AsRef<Path>,
+    ) -> io::Result<FixedMultilinearExtension<F>> {
+        if self.fixed_num_vars(partial_point)? <= MAX_IN_MEMORY_VARS {
+            Ok(FixedMultilinearExtension::Dense(
+                self.fix_variables_in_memory(partial_point)?,
+            ))
+        } else {
+            self.fix_variables_to_file(partial_point, path)
+                .map(FixedMultilinearExtension::Mmap)
+        }
+    }
+
+    /// Same as `fix_variables`, but always loads the result into memory,
+    /// which holds 2^(`num_vars` - `partial_point.len()`) field elements.
+    /// Returns an error if the partial point is longer than `num_vars`.
+    pub fn fix_variables_in_memory(
+        &self,
+        partial_point: &[F],
+    ) -> Result<DenseMultilinearExtension<F>, MleError> {
+        let nv = self.fixed_num_vars(partial_point)?;
+        let (eq_low, eq_high) = split_eq_tables(partial_point);
+        let evaluations = (0..1 << nv)
+            .into_par_iter()
+            .map_init(
+                || vec![F::ZERO; eq_low.len()],
+                |buf, index| self.fold_block(index, &eq_low, &eq_high, buf),
+            )
+            .collect();
+        Ok(DenseMultilinearExtension::from_evaluations_vec(
+            nv,
+            evaluations,
+        ))
+    }
+
+    /// Same as `fix_variables`, but always writes the result to a new file at
+    /// `path`.
+    /// Returns an error if the partial point is longer than `num_vars`, or if
+    /// the file cannot be written.
+    pub fn fix_variables_to_file(
+        &self,
+        partial_point: &[F],
+        path: impl AsRef<Path>,
+    ) -> io::Result<Self> {
+        let nv = self.fixed_num_vars(partial_point)?;
+        let (eq_low, eq_high) = split_eq_tables(partial_point);
+        // fold chunks of the result in parallel, and write them in order
+        let chunk_size = 1 << nv.min(FOLD_CHUNK_VARS);
+        Self::create(
+            path,
+            nv,
+            (0..1 << nv).step_by(chunk_size).flat_map(|start| {
+                (start..start + chunk_size)
+                    .into_par_iter()
+                    .map_init(
+                        || vec![F::ZERO; eq_low.len()],
+                        |buf, index| self.fold_block(index, &eq_low, &eq_high, buf),
+                    )
+                    .collect::<Vec<_>>()
+            }),
+        )
+    }
+
+    /// The number of variables left after fixing `partial_point`.
+    fn fixed_num_vars(&self, partial_point: &[F]) -> Result<usize, MleError> {
+        self.num_vars
+            .checked_sub(partial_point.len())
+            .ok_or(MleError::PartialPointTooLong {
+                max: self.num_vars,
+                actual: partial_point.len(),
+            })
+    }
+
+    /// Fold the `index`-th block of `2^k` evaluations, for `k` fixed
+    /// variables, into the evaluation of `self` at `(partial_point, index)`,
+    /// where `eq_low` and `eq_high` are the tables of `split_eq_tables`, and
+    /// `buf` holds `eq_low.len()` elements.
+    fn fold_block(&self, index: usize, eq_low: &[F], eq_high: &[F], buf: &mut [F]) -> F {
+        let offset = index * eq_low.len() * eq_high.len();
+        eq_high
+            .iter()
+            .enumerate()
+            .map(|(i, weight)| self.fold_row(offset + i * eq_low.len(), eq_low, buf) * weight)
+            .sum()
+    }
+
+    /// The evaluations at `start..start + eq_low.len()`, weighted by `eq_low`.
+    fn fold_row(&self, start: usize, eq_low: &[F], buf: &mut [F]) -> F {
+        self.read(start, buf);
+        buf.iter()
+            .zip(eq_low.iter())
+            .map(|(evaluation, weight)| *evaluation * weight)
+            .sum()
+    }
+}
+
+/// Number of variables above which `fix_variables` writes its result to a
+/// file instead of loading it into memory.
+pub const MAX_IN_MEMORY_VARS: usize = 20;
+
+/// `fix_variables_to_file` folds the result in chunks of 2^`FOLD_CHUNK_VARS`
+/// evaluations.
+const FOLD_CHUNK_VARS: usize = 12;
+
+/// The tables of `eq(x, point)` over the low and the high half of the
+/// variables of `point`, whose product is the table of `eq(x, point)`: only
+/// `O(2^(k / 2))` field elements are held in memory for `k` fixed variables.
+fn split_eq_tables<F: PrimeField>(point: &[F]) -> (Vec<F>, Vec<F>) {
+    let (low, high) = point.split_at(point.len() / 2);
+    (eq_table(low), eq_table(high))
+}
+
+/// Decode a field element from its `Repr` encoding, failing if it is not
+/// canonical.
+fn decode<F: PrimeField>(bytes: &[u8]) -> Option<F> {
+    let mut repr = F::Repr::default();
+    repr.as_mut().copy_from_slice(bytes);
+    F::from_repr(repr).into()
+}
+
+/// Decode a field element from a file that `open` has checked.
+fn decode_checked<F: PrimeField>(bytes: &[u8]) -> F {
+    decode(bytes).expect("evaluation file was modified while mapped")
+}
+
+/// The result of `MmapMultilinearExtension::fix_variables`: an in-memory
+/// table if it is small enough, and a mapped file otherwise.
+#[derive(Debug)]
+pub enum FixedMultilinearExtension<F: PrimeField> {
+    Dense(DenseMultilinearExtension<F>),
+    Mmap(MmapMultilinearExtension<F>),
+}
+
+impl<F: PrimeField> FixedMultilinearExtension<F> {
+    /// Load the whole table into memory, if it is not already.
+    pub fn into_dense(self) -> DenseMultilinearExtension<F> {
+        match self {
+            FixedMultilinearExtension::Dense(dense) => dense,
+            FixedMultilinearExtension::Mmap(mmap) => mmap.to_dense(),
+        }
+    }
+}
+
+impl<F: PrimeField> MultilinearPolynomial<F> for FixedMultilinearExtension<F> {
+    fn num_vars(&self) -> usize {
+        match self {
+            FixedMultilinearExtension::Dense(dense) => dense.num_vars,
+            FixedMultilinearExtension::Mmap(mmap) => mmap.num_vars,
+        }
+    }
+
+    fn evaluate(&self, point: &[F]) -> F {
+        match self {
+            FixedMultilinearExtension::Dense(dense) => dense.evaluate(point),
+            FixedMultilinearExtension::Mmap(mmap) => mmap.evaluate(point),
+        }
+    }
+
+    fn fix_variables(&self, partial_point: &[F]) -> DenseMultilinearExtension<F> {
+        match self {
+            FixedMultilinearExtension::Dense(dense) => dense.fix_variables(partial_point),
+            FixedMultilinearExtension::Mmap(mmap) => {
+                MultilinearPolynomial::fix_variables(mmap, partial_point)
+            }
+        }
+    }
+
+    fn evaluations(&self) -> Box<dyn Iterator<Item = F> + '_> {
+        match self {
+            FixedMultilinearExtension::Dense(dense) => dense.evaluations(),
+            FixedMultilinearExtension::Mmap(mmap) => mmap.evaluations(),
+        }
+    }
+
+    fn evaluation(&self, index: usize) -> F {
+        match self {
+            FixedMultilinearExtension::Dense(dense) => dense.evaluations[index],
+            FixedMultilinearExtension::Mmap(mmap) => mmap.get(index),
+        }
+    }
+
+    fn as_dense(&self) -> Option<&DenseMultilinearExtension<F>> {
+        match self {
+            FixedMultilinearExtension::Dense(dense) => Some(dense),
+            FixedMultilinearExtension::Mmap(_) => None,
+        }
+    }
+
+    fn to_dense(&self) -> DenseMultilinearExtension<F> {
+        match self {
+            FixedMultilinearExtension::Dense(dense) => dense.clone(),
+            FixedMultilinearExtension::Mmap(mmap) => mmap.to_dense(),
+        }
+    }
+    fn into_shared(self: Arc<Self>) -> Arc<dyn MultilinearPolynomial<F>> {
+        self
+    }
+}
+
+impl<F: PrimeField> Debug for MmapMultilinearExtension<F> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("MmapMultilinearExtension")
+            .field("num_vars", &self.num_vars)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<F: PrimeField> MultilinearPolynomial<F> for MmapMultilinearExtension<F> {
+    fn num_vars(&self) -> usize {
+        self.num_vars
+    }
+
+    fn evaluate(&self, point: &[F]) -> F {
+        self.evaluate(point)
+    }
+
+    /// The trait asks for a dense table, so the result is always loaded into
+    /// memory; see the inherent `fix_variables` to keep large results on disk.
+    fn fix_variables(&self, partial_point: &[F]) -> DenseMultilinearExtension<F> {
+        self.fix_variables_in_memory(partial_point)
+            .unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    fn evaluations(&self) -> Box<dyn Iterator<Item = F> + '_> {
+        Box::new((0..1 << self.num_vars).map(|index| self.get(index)))
+    }
+
+    fn evaluation(&self, index: usize) -> F {
+        self.get(index)
+    }
+
+    fn to_dense(&self) -> DenseMultilinearExtension<F> {
+        self.to_dense()
+    }
+    fn into_shared(self: Arc<Self>) -> Arc<dyn MultilinearPolynomial<F>> {
+        self
+    }
+}
+
+/// The evaluations of a mapped table, serialized as a sequence one element at
+/// a time.
+struct EvaluationsRef<'a, F: PrimeField>(&'a MmapMultilinearExtension<F>);
+
+impl<F: PrimeField + Serialize> Serialize for EvaluationsRef<'_, F> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut seq = serializer.serialize_seq(Some(1 << self.0.num_vars))?;
+        for index in 0..1 << self.0.num_vars {
+            seq.serialize_element(&self.0.get(index))?;
+        }
+        seq.end()
+    }
+}
+
+impl<F: PrimeField + Serialize> Serialize for MmapMultilinearExtension<F> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut state = serializer.serialize_struct("DenseMultilinearExtension", 2)?;
+        state.serialize_field("evaluations", &EvaluationsRef(self))?;
+        state.serialize_field("num_vars", &self.num_vars)?;
+        state.end()
+    }
+}
+
+/// Deserializes a serialized `DenseMultilinearExtension` or
+/// `MmapMultilinearExtension` into a new file at `path`, streaming the
+/// evaluations to disk as they are read.
+pub struct MmapMleSeed<F> {
+    path: PathBuf,
+    phantom: PhantomData<F>,
+}
+
+impl<F> MmapMleSeed<F> {
+    pub fn new(path: impl AsRef<Path>) -> Self {
+        Self {
+            path: path.as_ref().to_path_buf(),
+            phantom: PhantomData,
+        }
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(field_identifier, rename_all = "snake_case")]
+enum FieldName {
+    Evaluations,
+    NumVars,
+}
+
+const FIELDS: &[&str] = &["evaluations", "num_vars"];
+
+/// Number of variables above which `MmapMleSeed` rejects a serialized table,
+/// so that a corrupt checkpoint cannot fill the disk.
+pub const MAX_SERIALIZED_VARS: usize = 40;
+
+impl<'de, F: PrimeField + Deserialize<'de>> DeserializeSeed<'de> for MmapMleSeed<F> {
+    type Value = MmapMultilinearExtension<F>;
+
+    fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<Self::Value, D::Error> {
+        let file = File::create(&self.path).map_err(de::Error::custom)?;
+        let restored = Self::restore(&self.path, file, deserializer);
+        if restored.is_err() {
+            // do not leave a partial table behind
+            let _ = fs::remove_file(&self.path);
+        }
+        restored
+    }
+}
+
+impl<F: PrimeField> MmapMleSeed<F> {
+    /// Write the evaluations to `file`, which was created at `path`, and map
+    /// it.
+    fn restore<'de, D: Deserializer<'de>>(
+        path: &Path,
+        file: File,
+        deserializer: D,
+    ) -> Result<MmapMultilinearExtension<F>, D::Error>
+    where
+        F: Deserialize<'de>,
+    {
+        let mut writer = BufWriter::new(file);
+        let num_vars = deserializer.deserialize_struct(
+            "DenseMultilinearExtension",
+            FIELDS,
+            MleVisitor {
+                writer: &mut writer,
+                phantom: PhantomData::<F>,
+            },
+        )?;
+        writer.flush().map_err(de::Error::custom)?;
+        drop(writer);
+
+        let mle = MmapMultilinearExtension::open(path).map_err(de::Error::custom)?;
+        if mle.num_vars != num_vars {
+            return Err(de::Error::custom(MleError::NumVarsMismatch {
+                expected: num_vars,
+                actual: mle.num_vars,
+            }));
+        }
+        Ok(mle)
+    }
+}
+
+/// Visits the fields of a serialized MLE, writing the evaluations to `writer`
+/// and returning the number of variables.
+struct MleVisitor<'a, F> {
+    writer: &'a mut BufWriter<File>,
+    phantom: PhantomData<F>,
+}
+
+impl<'de, F: PrimeField + Deserialize<'de>> Visitor<'de> for MleVisitor<'_, F> {
+    type Value = usize;
+
+    fn expecting(&self, formatter: &mut Formatter) -> std::fmt::Result {
+        formatter.write_str("struct DenseMultilinearExtension")
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<usize, A::Error> {
+        // the evaluations come first, so only the overall bound applies
+        seq.next_element_seed(EvaluationsSeed {
+            writer: self.writer,
+            max_size: 1 << MAX_SERIALIZED_VARS,
+            phantom: PhantomData::<F>,
+        })?
+        .ok_or_else(|| de::Error::invalid_length(0, &self))?;
+        let num_vars = seq
+            .next_element()?
+            .ok_or_else(|| de::Error::invalid_length(1, &"struct DenseMultilinearExtension"))?;
+        check_serialized_num_vars(num_vars)
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<usize, A::Error> {
+        let mut evaluations = false;
+        let mut num_vars = None;
+        while let Some(key) = map.next_key()? {
+            match key {
+                FieldName::Evaluations => {
+                    if evaluations {
+                        return Err(de::Error::duplicate_field("evaluations"));
+                    }
+                    // a table is at most as large as `num_vars` says, if it
+                    // came first
+                    map.next_value_seed(EvaluationsSeed {
+                        writer: &mut *self.writer,
+                        max_size: 1 << num_vars.unwrap_or(MAX_SERIALIZED_VARS),
+                        phantom: PhantomData::<F>,
+                    })?;
+                    evaluations = true;
+                }
+                FieldName::NumVars => {
+                    if num_vars.is_some() {
+                        return Err(de::Error::duplicate_field("num_vars"));
+                    }
+                    num_vars = Some(check_serialized_num_vars(map.next_value()?)?);
+                }
+            }
+        }
+        if !evaluations {
+            return Err(de::Error::missing_field("evaluations"));
+        }
+        num_vars.ok_or_else(|| de::Error::missing_field("num_vars"))
+    }
+}
+
+/// Returns an error if a serialized table has more than
+/// `MAX_SERIALIZED_VARS` variables.
+fn check_serialized_num_vars<E: de::Error>(num_vars: usize) -> Result<usize, E> {
+    if num_vars > MAX_SERIALIZED_VARS {
+        return Err(de::Error::custom(MleError::TooManyVariables(num_vars)));
+    }
+    Ok(num_vars)
+}
+
+/// Writes a serialized sequence of at most `max_size` evaluations to
+/// `writer`.
+struct EvaluationsSeed<'a, F> {
+    writer: &'a mut BufWriter<File>,
+    max_size: usize,
+    phantom: PhantomData<F>,
+}
+
+impl<'de, F: PrimeField + Deserialize<'de>> DeserializeSeed<'de> for EvaluationsSeed<'_, F> {
+    type Value = ();
+
+    fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<(), D::Error> {
+        deserializer.deserialize_seq(self)
+    }
+}
+
+impl<'de, F: PrimeField + Deserialize<'de>> Visitor<'de> for EvaluationsSeed<'_, F> {
+    type Value = ();
+
+    fn expecting(&self, formatter: &mut Formatter) -> std::fmt::Result {
+        formatter.write_str("a sequence of evaluations")
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<(), A::Error> {
+        let mut count = 0usize;
+        while let Some(evaluation) = seq.next_element::<F>()? {
+            if count == self.max_size {
+                return Err(de::Error::custom(MleError::InvalidEvaluationsSize {
+                    expected: self.max_size,
+                    actual: count + 1,
+                }));
+            }
+            count += 1;
+            self.writer
+                .write_all(evaluation.to_repr().as_ref())
+                .map_err(de::Error::custom)?;
+        }
+        Ok(())
+    }
+}
@@ -1,17 +1,27 @@
 use std::sync::Arc;
 
-use ark_std::test_rng;
+use ark_std::{rand::RngCore, test_rng};
+use babybear::BabyBear;
 use ff::{PrimeField, Field};
 use goldilocks::Goldilocks as F;
+use mersenne31::Mersenne31;
 
 use crate::{
-    mle::DenseMultilinearExtension,
+    coefficients::{evaluate_coefficients, evaluations_to_coefficients_in_place},
+    error::MleError,
+    expression::Expression,
+    lazy_mle::{EqPolynomial, IdentityPolynomial, SelectorPolynomial},
+    merge::{merge, merge_evaluations, num_selector_vars, part_point, split, split_evaluations},
+    mle::{DenseMultilinearExtension, MultilinearPolynomial, SparseMultilinearExtension},
+    mmap_mle::{
+        FixedMultilinearExtension, MmapMleSeed, MmapMultilinearExtension, MAX_IN_MEMORY_VARS,
+        MAX_SERIALIZED_VARS,
+    },
     util::bit_decompose,
-    virtual_poly::{VirtualPolynomial, build_eq_x_r},
+    virtual_poly::{build_eq_x_r, MleHandle, VirtualPolynomial},
 };
 
-#[test]
-fn test_virtual_polynomial_additions() {
+fn virtual_polynomial_additions<F: PrimeField>() {
     let mut rng = test_rng();
     for nv in 2..5 {
         for num_products in 2..5 {
@@ -30,6 +40,13 @@ fn test_virtual_polynomial_additions() {
 
 }
 
+#[test]
+fn test_virtual_polynomial_additions() {
+    virtual_polynomial_additions::<F>();
+    virtual_polynomial_additions::<BabyBear>();
+    virtual_polynomial_additions::<Mersenne31>();
+}
+
 #[test]
 fn test_virtual_polynomial_mul_by_mle()  {
     let mut rng = test_rng();
@@ -106,3 +123,688 @@ fn build_eq_x_r_for_test<F: PrimeField>(r: &[F]) -> Arc<DenseMultilinearExtensio
 
     Arc::new(mle)
 }
+
+#[test]
+fn test_sparse_mle() {
+    let mut rng = test_rng();
+    for nv in 1..8 {
+        let sparse = SparseMultilinearExtension::<F>::random(nv, nv + 1, &mut rng);
+        let dense = sparse.to_dense();
+        assert_eq!(SparseMultilinearExtension::from_dense(&dense), sparse);
+
+        let point: Vec<F> = (0..nv).map(|_| F::random(&mut rng)).collect();
+        assert_eq!(sparse.evaluate(&point), dense.evaluate(&point));
+        for i in 0..=nv {
+            assert_eq!(
+                sparse.fix_variables(&point[..i]).to_dense(),
+                dense.fix_variables(&point[..i])
+            );
+        }
+    }
+}
+
+#[test]
+fn test_sparse_mle_additions() {
+    let mut rng = test_rng();
+    for nv in 1..8 {
+        let a = SparseMultilinearExtension::<F>::random(nv, nv, &mut rng);
+        let b = SparseMultilinearExtension::<F>::random(nv, nv, &mut rng);
+        let c = &a + &b;
+        let point: Vec<F> = (0..nv).map(|_| F::random(&mut rng)).collect();
+        assert_eq!(a.evaluate(&point) + b.evaluate(&point), c.evaluate(&point));
+
+        let negated = SparseMultilinearExtension::from_evaluations(
+            nv,
+            a.evaluations.iter().map(|(index, value)| (*index, -*value)),
+        );
+        assert!((&a + &negated).evaluations.is_empty());
+    }
+}
+
+/// Check every method of `poly` against its dense form.
+fn test_multilinear_polynomial(poly: &dyn MultilinearPolynomial<F>) {
+    let mut rng = test_rng();
+    let nv = poly.num_vars();
+    let dense = poly.to_dense();
+    assert_eq!(dense.num_vars, nv);
+    assert_eq!(poly.evaluations().collect::<Vec<_>>(), dense.evaluations);
+    for (index, eval) in dense.evaluations.iter().enumerate() {
+        assert_eq!(poly.evaluation(index), *eval);
+    }
+    if let Some(support) = poly.support() {
+        let support = support.collect::<Vec<_>>();
+        assert!(support.windows(2).all(|pair| pair[0] < pair[1]));
+        for (index, eval) in dense.evaluations.iter().enumerate() {
+            assert!(support.contains(&index) || *eval == F::ZERO);
+        }
+    }
+
+    let point: Vec<F> = (0..nv).map(|_| F::random(&mut rng)).collect();
+    assert_eq!(poly.evaluate(&point), dense.evaluate(&point));
+    for i in 0..=nv {
+        assert_eq!(
+            poly.fix_variables(&point[..i]),
+            dense.fix_variables(&point[..i])
+        );
+        assert_eq!(
+            poly.fix_variables_shared(&point[..i]).to_dense(),
+            dense.fix_variables(&point[..i])
+        );
+    }
+}
+
+#[test]
+fn test_multilinear_polynomials() {
+    let mut rng = test_rng();
+    for nv in 1..6 {
+        let r: Vec<F> = (0..nv).map(|_| F::random(&mut rng)).collect();
+        let (dense, _) = DenseMultilinearExtension::<F>::random_mle_list(nv, 1, &mut rng);
+        test_multilinear_polynomial(dense[0].as_ref());
+        test_multilinear_polynomial(&SparseMultilinearExtension::<F>::random(nv, nv, &mut rng));
+        test_multilinear_polynomial(&EqPolynomial::new(&r));
+        test_multilinear_polynomial(&IdentityPolynomial::new(nv));
+        for num_instances in 0..=1 << nv {
+            test_multilinear_polynomial(&SelectorPolynomial::new(nv, num_instances));
+        }
+    }
+}
+
+#[test]
+fn test_virtual_polynomial_with_mixed_multiplicands() {
+    let mut rng = test_rng();
+    let nv = 4;
+    let point: Vec<F> = (0..nv).map(|_| F::random(&mut rng)).collect();
+    let (dense, _) = DenseMultilinearExtension::<F>::random_mle_list(nv, 1, &mut rng);
+    let sparse = SparseMultilinearExtension::<F>::random(nv, 3, &mut rng);
+    let selector = SelectorPolynomial::new(nv, 5);
+    let coeff = F::random(&mut rng);
+
+    let mut poly = VirtualPolynomial::<F, dyn MultilinearPolynomial<F>>::new(nv);
+    poly.add_mle_list(
+        [
+            dense[0].clone() as Arc<dyn MultilinearPolynomial<F>>,
+            Arc::new(sparse.clone()),
+        ],
+        coeff,
+    );
+    poly.add_mle_list([Arc::new(selector.clone()) as _], F::ONE);
+
+    let expected = coeff * dense[0].evaluate(&point) * sparse.evaluate(&point)
+        + MultilinearPolynomial::<F>::evaluate(&selector, &point);
+    assert_eq!(poly.evaluate(&point), expected);
+    assert_eq!(poly.to_dense().evaluate(&point), expected);
+}
+
+#[test]
+fn test_mle_errors() {
+    let mut rng = test_rng();
+    let nv = 3;
+    assert_eq!(
+        DenseMultilinearExtension::<F>::try_from_evaluations_vec(nv, vec![F::ONE; 7]),
+        Err(MleError::InvalidEvaluationsSize {
+            expected: 8,
+            actual: 7
+        })
+    );
+    assert_eq!(
+        SparseMultilinearExtension::<F>::try_from_evaluations(nv, [(8, F::ONE)]),
+        Err(MleError::IndexOutOfRange { index: 8, size: 8 })
+    );
+    assert_eq!(
+        SparseMultilinearExtension::<F>::try_from_evaluations(nv, [(2, F::ONE), (2, F::ONE)]),
+        Err(MleError::DuplicateIndex(2))
+    );
+    assert_eq!(
+        SparseMultilinearExtension::<F>::try_from_evaluations(nv, [(8, F::ZERO)]),
+        Err(MleError::IndexOutOfRange { index: 8, size: 8 })
+    );
+    assert_eq!(
+        SparseMultilinearExtension::<F>::try_from_evaluations(nv, [(2, F::ZERO), (2, F::ONE)]),
+        Err(MleError::DuplicateIndex(2))
+    );
+    assert_eq!(
+        DenseMultilinearExtension::<F>::try_from_evaluations_vec(64, vec![F::ONE; 7]),
+        Err(MleError::TooManyVariables(64))
+    );
+    assert_eq!(
+        SparseMultilinearExtension::<F>::try_from_evaluations(usize::MAX, [(2, F::ONE)]),
+        Err(MleError::TooManyVariables(usize::MAX))
+    );
+
+    let (mles, _) = DenseMultilinearExtension::<F>::random_mle_list(nv, 2, &mut rng);
+    let point: Vec<F> = (0..nv + 1).map(|_| F::random(&mut rng)).collect();
+    assert_eq!(
+        mles[0].try_evaluate(&point),
+        Err(MleError::PointSizeMismatch {
+            expected: 3,
+            actual: 4
+        })
+    );
+    assert_eq!(
+        mles[0].try_fix_variables(&point),
+        Err(MleError::PartialPointTooLong { max: 3, actual: 4 })
+    );
+    assert_eq!(
+        mles[0].try_fix_high_variables(&point),
+        Err(MleError::PartialPointTooLong { max: 3, actual: 4 })
+    );
+    let mut folded = mles[0].as_ref().clone();
+    assert_eq!(
+        folded.try_fix_variables_in_place(&point),
+        Err(MleError::PartialPointTooLong { max: 3, actual: 4 })
+    );
+    assert_eq!(
+        folded.try_fix_high_variables_in_place(&point),
+        Err(MleError::PartialPointTooLong { max: 3, actual: 4 })
+    );
+    assert_eq!(folded, *mles[0].as_ref());
+
+    let mut poly = VirtualPolynomial::new_from_mle(&mles[0], F::ONE);
+    let expected = poly.clone();
+    let (other, _) = DenseMultilinearExtension::<F>::random_mle_list(nv + 1, 1, &mut rng);
+    assert_eq!(
+        poly.try_add_mle_list([], F::ONE),
+        Err(MleError::EmptyProduct)
+    );
+    assert_eq!(
+        poly.try_add_mle_list([mles[1].clone(), other[0].clone()], F::ONE),
+        Err(MleError::NumVarsMismatch {
+            expected: 3,
+            actual: 4
+        })
+    );
+    assert_eq!(
+        poly.try_mul_by_mle(other[0].clone(), F::ONE),
+        Err(MleError::NumVarsMismatch {
+            expected: 3,
+            actual: 4
+        })
+    );
+    assert_eq!(poly, expected);
+    assert!(poly.try_evaluate(&point).is_err());
+    assert!(poly.try_build_f_hat(&point).is_err());
+    assert!(poly.try_build_f_hat(&point[..nv]).is_ok());
+}
+
+#[test]
+fn test_virtual_polynomial_handles() {
+    let mut rng = test_rng();
+    let nv = 4;
+    let point: Vec<F> = (0..nv).map(|_| F::random(&mut rng)).collect();
+    let (mles, _) = DenseMultilinearExtension::<F>::random_mle_list(nv, 2, &mut rng);
+
+    // every table is registered once, however many products use it
+    let mut poly = VirtualPolynomial::new(nv);
+    let a = poly.register_mle(Arc::new(mles[0].as_ref().clone()));
+    let b = poly.register_mle(mles[1].clone());
+    poly.add_product([a, b], F::ONE);
+    poly.add_product([a], F::from(2));
+    poly.mul_by_handle(b, F::from(3));
+    assert_eq!(poly.flattened_ml_extensions.len(), 2);
+    assert_eq!(poly.aux_info.max_degree, 3);
+
+    let (eval_a, eval_b) = (mles[0].evaluate(&point), mles[1].evaluate(&point));
+    assert_eq!(
+        poly.evaluate(&point),
+        F::from(3) * (eval_a * eval_b * eval_b + F::from(2) * eval_a * eval_b)
+    );
+    assert_eq!(poly.mle(a).as_ref(), mles[0].as_ref());
+
+    // an MLE is looked up by its evaluations, also in another `Arc`, in
+    // clones and after its entry has been replaced
+    let mut copy = poly.clone();
+    copy.add_mle_list([mles[1].clone(), mles[1].clone()], F::ONE);
+    assert_eq!(copy.products.last().unwrap().1, vec![1, 1]);
+    copy.add_mle_list([mles[0].clone()], F::ONE);
+    assert_eq!(copy.products.last().unwrap().1, vec![0]);
+    copy.flattened_ml_extensions[1] = mles[0].clone();
+    copy.add_mle_list([mles[1].clone()], F::ONE);
+    assert_eq!(copy.products.last().unwrap().1, vec![2]);
+
+    // a handle of another polynomial is not valid here
+    let mut other = VirtualPolynomial::<F>::new(nv);
+    let handles: Vec<MleHandle> = (0..3)
+        .map(|i| other.register_mle(mles[i % 2].clone()))
+        .collect();
+    assert_eq!(
+        poly.try_add_product([handles[2]], F::ONE),
+        Err(MleError::UnknownHandle(2))
+    );
+    assert_eq!(poly.try_mle(handles[2]), Err(MleError::UnknownHandle(2)));
+}
+
+#[test]
+fn test_virtual_polynomial_serialization() {
+    let mut rng = test_rng();
+    let (poly, _) = VirtualPolynomial::<F>::random(4, (2, 4), 3, &mut rng);
+
+    let bytes = bincode::serialize(&poly).unwrap();
+    let mut deserialized: VirtualPolynomial<F> = bincode::deserialize(&bytes).unwrap();
+    assert_eq!(deserialized, poly);
+
+    // the MLEs of a deserialized polynomial are not registered again
+    let num_mles = deserialized.flattened_ml_extensions.len();
+    let mle = Arc::new(poly.flattened_ml_extensions[0].as_ref().clone());
+    deserialized.mul_by_mle(mle.clone(), F::ONE);
+    assert_eq!(deserialized.flattened_ml_extensions.len(), num_mles);
+    let mut poly = poly;
+    poly.mul_by_mle(mle, F::ONE);
+
+    // the polynomial can be moved to another thread
+    let point: Vec<F> = (0..4).map(|_| F::random(&mut rng)).collect();
+    let expected = poly.evaluate(&point);
+    let evaluation = std::thread::spawn(move || deserialized.evaluate(&point))
+        .join()
+        .unwrap();
+    assert_eq!(evaluation, expected);
+
+    // the products are checked against the MLEs they refer to
+    let deserialize = |poly: &VirtualPolynomial<F>| {
+        bincode::deserialize::<VirtualPolynomial<F>>(&bincode::serialize(poly).unwrap())
+            .map_err(|e| e.to_string())
+    };
+    let mut tampered = poly.clone();
+    tampered.products[0].1[0] = poly.flattened_ml_extensions.len();
+    assert_eq!(
+        deserialize(&tampered),
+        Err(MleError::UnknownHandle(poly.flattened_ml_extensions.len()).to_string())
+    );
+    let mut tampered = poly.clone();
+    tampered.products[0].1.clear();
+    assert_eq!(
+        deserialize(&tampered),
+        Err(MleError::EmptyProduct.to_string())
+    );
+    let mut tampered = poly.clone();
+    tampered.aux_info.max_degree = 1;
+    assert!(deserialize(&tampered).is_err());
+    assert_eq!(
+        VirtualPolynomial::try_from_parts(
+            poly.aux_info.clone(),
+            poly.products.clone(),
+            poly.flattened_ml_extensions.clone()
+        ),
+        Ok(poly)
+    );
+}
+
+#[test]
+fn test_expression() {
+    let mut rng = test_rng();
+    let nv = 4;
+    let point: Vec<F> = (0..nv).map(|_| F::random(&mut rng)).collect();
+    let (mles, _) = DenseMultilinearExtension::<F>::random_mle_list(nv, 8, &mut rng);
+
+    let mut poly = VirtualPolynomial::new(nv);
+    let handles: Vec<MleHandle> = mles
+        .iter()
+        .map(|mle| poly.register_mle(mle.clone()))
+        .collect();
+    let [q_l, q_r, q_m, q_o, q_c, w1, w2, w3]: [Expression<F>; 8] =
+        std::array::from_fn(|i| Expression::mle(handles[i]));
+
+    let gate = &q_l * &w1 + &q_r * &w2 + &q_m * &w1 * &w2 - &q_o * &w3 + &q_c;
+    assert_eq!(gate.degree(), 3);
+    poly.add_expression(&gate);
+    assert_eq!(poly.aux_info.max_degree, 3);
+    assert_eq!(poly.products.len(), 5);
+
+    let evals: Vec<F> = mles.iter().map(|mle| mle.evaluate(&point)).collect();
+    let [q_l_eval, q_r_eval, q_m_eval, q_o_eval, q_c_eval, w1_eval, w2_eval, w3_eval]: [F; 8] =
+        evals.clone().try_into().unwrap();
+    let expected = q_l_eval * w1_eval + q_r_eval * w2_eval + q_m_eval * w1_eval * w2_eval
+        - q_o_eval * w3_eval
+        + q_c_eval;
+    assert_eq!(gate.evaluate(&|handle| evals[handle.index()]), expected);
+    assert_eq!(poly.evaluate(&point), expected);
+
+    // (w1 + w2)^2 - w1^2 - w2^2 = 2 * w1 * w2 once like terms are merged
+    let square = (&w1 + &w2).pow(2) - w1.clone().pow(2) - w2.clone().pow(2);
+    assert_eq!(square.degree(), 2);
+    assert_eq!(
+        square.expand(),
+        vec![(F::from(2), vec![handles[5], handles[6]])]
+    );
+
+    let products = poly.products.clone();
+    assert_eq!(
+        poly.try_add_expression(&(&w1 + Expression::constant(F::ONE))),
+        Err(MleError::ConstantTerm)
+    );
+    assert_eq!(poly.products, products);
+    // a constant that cancels out is fine
+    poly.add_expression(&(&w1 + Expression::constant(F::ONE) - Expression::constant(F::ONE)));
+    assert_eq!(poly.products.len(), products.len() + 1);
+}
+
+fn fix_variables_against_evaluate<F: PrimeField>() {
+    let mut rng = test_rng();
+    for nv in 0..8 {
+        for _ in 0..10 {
+            let (mles, _) = DenseMultilinearExtension::<F>::random_mle_list(nv, 1, &mut rng);
+            let mle = mles[0].as_ref();
+            let point: Vec<F> = (0..nv).map(|_| F::random(&mut rng)).collect();
+            let expected = mle.evaluate(&point);
+            let k = (rng.next_u64() as usize) % (nv + 1);
+            let (low, high) = point.split_at(k);
+
+            assert_eq!(mle.fix_variables(low).evaluate(high), expected);
+            assert_eq!(mle.fix_high_variables(high).evaluate(low), expected);
+
+            let mut folded = mle.clone();
+            folded.fix_variables_in_place(low);
+            assert_eq!(folded, mle.fix_variables(low));
+            assert_eq!(folded.evaluate(high), expected);
+
+            let mut folded = mle.clone();
+            folded.fix_high_variables_in_place(high);
+            assert_eq!(folded, mle.fix_high_variables(high));
+            assert_eq!(folded.evaluate(low), expected);
+        }
+    }
+
+    // a table of several chunks, folded in more than one pass
+    let nv = 15;
+    let (mles, _) = DenseMultilinearExtension::<F>::random_mle_list(nv, 1, &mut rng);
+    let point: Vec<F> = (0..nv).map(|_| F::random(&mut rng)).collect();
+    for k in [1, 12, 14, nv] {
+        let mut folded = mles[0].as_ref().clone();
+        folded.fix_variables_in_place(&point[..k]);
+        assert_eq!(folded, mles[0].fix_variables(&point[..k]));
+    }
+}
+
+#[test]
+fn test_fix_variables_against_evaluate() {
+    fix_variables_against_evaluate::<F>();
+    fix_variables_against_evaluate::<BabyBear>();
+    fix_variables_against_evaluate::<Mersenne31>();
+}
+
+fn merge_and_split<F: PrimeField>() {
+    let mut rng = test_rng();
+    let nv = 3;
+    for num_parts in 1..6 {
+        let (mles, _) = DenseMultilinearExtension::<F>::random_mle_list(nv, num_parts, &mut rng);
+        let parts: Vec<&DenseMultilinearExtension<F>> =
+            mles.iter().map(|mle| mle.as_ref()).collect();
+        let selector_nv = num_selector_vars(num_parts);
+        let merged = merge(&parts);
+        assert_eq!(merged.num_vars, nv + selector_nv);
+        assert_eq!(
+            split(&merged, num_parts),
+            parts.iter().map(|part| (*part).clone()).collect::<Vec<_>>()
+        );
+
+        let point: Vec<F> = (0..nv).map(|_| F::random(&mut rng)).collect();
+        let part_evaluations: Vec<F> = parts.iter().map(|part| part.evaluate(&point)).collect();
+        for (j, evaluation) in part_evaluations.iter().enumerate() {
+            assert_eq!(
+                merged.evaluate(&part_point(&point, j, selector_nv)),
+                *evaluation
+            );
+        }
+        assert_eq!(
+            split_evaluations(&merged, &point, num_parts),
+            part_evaluations
+        );
+
+        let selector_point: Vec<F> = (0..selector_nv).map(|_| F::random(&mut rng)).collect();
+        assert_eq!(
+            merge_evaluations(&part_evaluations, &selector_point),
+            merged.evaluate(&[point.as_slice(), selector_point.as_slice()].concat())
+        );
+    }
+}
+
+#[test]
+fn test_merge_and_split() {
+    merge_and_split::<F>();
+    merge_and_split::<BabyBear>();
+    merge_and_split::<Mersenne31>();
+}
+
+fn coefficients<F: PrimeField>() {
+    let mut rng = test_rng();
+    // the last size also splits the pairs of the top layers between tasks
+    for nv in (0..8).chain([12]) {
+        let (mles, _) = DenseMultilinearExtension::<F>::random_mle_list(nv, 1, &mut rng);
+        let mle = mles[0].as_ref();
+        let coefficients = mle.to_coefficients();
+        assert_eq!(
+            DenseMultilinearExtension::from_coefficients(nv, coefficients.clone()),
+            *mle
+        );
+
+        let point: Vec<F> = (0..nv).map(|_| F::random(&mut rng)).collect();
+        assert_eq!(
+            evaluate_coefficients(&coefficients, &point),
+            mle.evaluate(&point)
+        );
+    }
+
+    // 1 + 2 x_1 + 3 x_2 + 4 x_1 x_2 has evaluations 1, 3, 4, 10
+    let mut values: Vec<F> = [1, 3, 4, 10].into_iter().map(F::from).collect();
+    evaluations_to_coefficients_in_place(&mut values);
+    assert_eq!(
+        values,
+        [1, 2, 3, 4].into_iter().map(F::from).collect::<Vec<_>>()
+    );
+}
+
+#[test]
+fn test_coefficients() {
+    coefficients::<F>();
+    coefficients::<BabyBear>();
+    coefficients::<Mersenne31>();
+}
+
+#[test]
+fn test_evaluate_many_and_at_points() {
+    let mut rng = test_rng();
+    for nv in 0..6 {
+        let (mles, _) = DenseMultilinearExtension::<F>::random_mle_list(nv, 4, &mut rng);
+        let mles: Vec<&DenseMultilinearExtension<F>> =
+            mles.iter().map(|mle| mle.as_ref()).collect();
+        let points: Vec<Vec<F>> = (0..3)
+            .map(|_| (0..nv).map(|_| F::random(&mut rng)).collect())
+            .collect();
+
+        assert_eq!(
+            DenseMultilinearExtension::evaluate_many(&mles, &points[0]),
+            mles.iter()
+                .map(|mle| mle.evaluate(&points[0]))
+                .collect::<Vec<_>>()
+        );
+        assert_eq!(
+            mles[0].evaluate_at_points(&points),
+            points
+                .iter()
+                .map(|point| mles[0].evaluate(point))
+                .collect::<Vec<_>>()
+        );
+    }
+}
+
+#[test]
+fn test_mmap_mle() {
+    let mut rng = test_rng();
+    let dir = tempfile::tempdir().unwrap();
+    for nv in 0..7 {
+        let (mles, _) = DenseMultilinearExtension::<F>::random_mle_list(nv, 1, &mut rng);
+        let dense = mles[0].as_ref();
+        let path = dir.path().join(format!("mle_{}", nv));
+        let mmap = MmapMultilinearExtension::from_dense(&path, dense).unwrap();
+        assert_eq!(mmap.num_vars, nv);
+        assert_eq!(mmap.to_dense(), *dense);
+        assert_eq!(
+            MmapMultilinearExtension::<F>::open(&path)
+                .unwrap()
+                .to_dense(),
+            *dense
+        );
+
+        let point: Vec<F> = (0..nv).map(|_| F::random(&mut rng)).collect();
+        assert_eq!(mmap.evaluate(&point), dense.evaluate(&point));
+        for k in 0..=nv {
+            assert_eq!(
+                mmap.fix_variables_in_memory(&point[..k]).unwrap(),
+                dense.fix_variables(&point[..k])
+            );
+            let fixed = mmap
+                .fix_variables(&point[..k], dir.path().join("unused"))
+                .unwrap();
+            assert!(matches!(fixed, FixedMultilinearExtension::Dense(_)));
+            assert_eq!(fixed.into_dense(), dense.fix_variables(&point[..k]));
+        }
+
+        let fixed_path = dir.path().join(format!("fixed_{}", nv));
+        let fixed = mmap
+            .fix_variables_to_file(&point[..nv / 2], &fixed_path)
+            .unwrap();
+        assert_eq!(fixed.to_dense(), dense.fix_variables(&point[..nv / 2]));
+    }
+
+    let dense = DenseMultilinearExtension::<F>::random_mle_list(3, 1, &mut rng).0[0]
+        .as_ref()
+        .clone();
+    assert!(MmapMultilinearExtension::create(
+        dir.path().join("short"),
+        3,
+        dense.evaluations[..7].to_vec()
+    )
+    .is_err());
+    assert!(!dir.path().join("short").exists());
+    // writing stops at the first evaluation too many
+    let error =
+        MmapMultilinearExtension::create(dir.path().join("long"), 3, std::iter::repeat(F::ONE))
+            .unwrap_err();
+    assert_eq!(error.kind(), std::io::ErrorKind::InvalidInput);
+    assert!(!dir.path().join("long").exists());
+    let error =
+        MmapMultilinearExtension::<F>::create(dir.path().join("huge"), usize::MAX, []).unwrap_err();
+    assert_eq!(error.kind(), std::io::ErrorKind::InvalidInput);
+    assert!(!dir.path().join("huge").exists());
+    let mmap = MmapMultilinearExtension::from_dense(dir.path().join("mle"), &dense).unwrap();
+    assert_eq!(
+        mmap.try_evaluate(&[F::ONE]),
+        Err(MleError::PointSizeMismatch {
+            expected: 3,
+            actual: 1
+        })
+    );
+    let error = mmap
+        .fix_variables_to_file(&[F::ONE; 4], dir.path().join("long"))
+        .unwrap_err();
+    assert_eq!(error.kind(), std::io::ErrorKind::InvalidInput);
+
+    // a non-canonical element is rejected when the file is mapped
+    std::fs::write(dir.path().join("invalid"), [0xff; 8]).unwrap();
+    let error = MmapMultilinearExtension::<F>::open(dir.path().join("invalid")).unwrap_err();
+    assert_eq!(error.kind(), std::io::ErrorKind::InvalidData);
+
+    // a large enough result is written to a file
+    let nv = MAX_IN_MEMORY_VARS + 1;
+    let path = dir.path().join("large");
+    let mmap = MmapMultilinearExtension::create(&path, nv, (0..1u64 << nv).map(F::from)).unwrap();
+    let fixed_path = dir.path().join("large_fixed");
+    match mmap.fix_variables(&[], &fixed_path).unwrap() {
+        FixedMultilinearExtension::Mmap(fixed) => assert_eq!(fixed.get(12345), F::from(12345u64)),
+        FixedMultilinearExtension::Dense(_) => panic!("the result should be in a file"),
+    }
+}
+
+#[test]
+fn test_mmap_mle_serialization() {
+    use bincode::Options;
+    use serde::de::DeserializeSeed;
+
+    let mut rng = test_rng();
+    let dir = tempfile::tempdir().unwrap();
+    let dense = DenseMultilinearExtension::<F>::random_mle_list(5, 1, &mut rng).0[0]
+        .as_ref()
+        .clone();
+    let mmap = MmapMultilinearExtension::from_dense(dir.path().join("mle"), &dense).unwrap();
+
+    // a mapped table serializes exactly like the dense one
+    let bytes = bincode::serialize(&mmap).unwrap();
+    assert_eq!(bytes, bincode::serialize(&dense).unwrap());
+    assert_eq!(
+        bincode::deserialize::<DenseMultilinearExtension<F>>(&bytes).unwrap(),
+        dense
+    );
+
+    // and a checkpoint can be restored into a file
+    let mut deserializer = bincode::Deserializer::from_slice(
+        &bytes,
+        bincode::options()
+            .with_fixint_encoding()
+            .allow_trailing_bytes(),
+    );
+    let restored = MmapMleSeed::<F>::new(dir.path().join("restored"))
+        .deserialize(&mut deserializer)
+        .unwrap();
+    assert_eq!(restored.to_dense(), dense);
+
+    // a bad checkpoint leaves no file behind
+    let restore = |bytes: &[u8], name: &str| {
+        let path = dir.path().join(name);
+        let mut deserializer = bincode::Deserializer::from_slice(
+            bytes,
+            bincode::options()
+                .with_fixint_encoding()
+                .allow_trailing_bytes(),
+        );
+        let restored = MmapMleSeed::<F>::new(&path).deserialize(&mut deserializer);
+        assert!(restored.is_err());
+        assert!(!path.exists());
+    };
+    restore(&bytes[..bytes.len() - 12], "truncated");
+    let num_vars_at = bytes.len() - 8;
+    let mut mismatched = bytes.clone();
+    mismatched[num_vars_at..].copy_from_slice(&4u64.to_le_bytes());
+    restore(&mismatched, "mismatched");
+    let mut too_large = bytes.clone();
+    too_large[num_vars_at..].copy_from_slice(&(MAX_SERIALIZED_VARS as u64 + 1).to_le_bytes());
+    restore(&too_large, "too_large");
+}
+
+#[test]
+fn test_fix_variables_packed() {
+    use goldilocks::GoldilocksExt2;
+
+    // the packed path for Goldilocks agrees with the generic path, which is
+    // taken for the extension field
+    let mut rng = test_rng();
+    for nv in 1..12 {
+        let mle = DenseMultilinearExtension::from_evaluations_vec(
+            nv,
+            (0..1 << nv).map(|_| F::random(&mut rng)).collect(),
+        );
+        let ext = DenseMultilinearExtension::from_evaluations_vec(
+            nv,
+            mle.evaluations
+                .iter()
+                .map(|x| GoldilocksExt2::from(*x))
+                .collect(),
+        );
+        for dim in 0..=nv {
+            let point = (0..dim).map(|_| F::random(&mut rng)).collect::<Vec<_>>();
+            let ext_point = point
+                .iter()
+                .map(|x| GoldilocksExt2::from(*x))
+                .collect::<Vec<_>>();
+            let fixed = mle.fix_variables(&point);
+            let ext_fixed = ext.fix_variables(&ext_point);
+            assert_eq!(fixed.num_vars, nv - dim);
+            assert_eq!(
+                fixed
+                    .evaluations
+                    .iter()
+                    .map(|x| GoldilocksExt2::from(*x))
+                    .collect::<Vec<_>>(),
+                ext_fixed.evaluations
+            );
+        }
+    }
+}
@@ -1,20 +1,31 @@
-use std::cmp::max;
-use std::ops::Add;
-use std::{collections::HashMap, marker::PhantomData, sync::Arc};
+use std::{
+    cmp::max,
+    collections::{hash_map::DefaultHasher, HashMap},
+    hash::{Hash, Hasher},
+    marker::PhantomData,
+    ops::Add,
+    sync::Arc,
+};
 
 use ark_std::rand::Rng;
 use ark_std::{end_timer, start_timer};
 use ff::PrimeField;
 use rayon::prelude::{IndexedParallelIterator, IntoParallelRefMutIterator, ParallelIterator};
 
-use crate::mle::DenseMultilinearExtension;
+use crate::error::MleError;
+use crate::expression::Expression;
+use crate::mle::{DenseMultilinearExtension, MultilinearPolynomial};
 use crate::util::bit_decompose;
-use serde::{Serialize,Deserialize};
+use serde::{Deserialize, Serialize};
 
 #[rustfmt::skip]
 /// A virtual polynomial is a sum of products of multilinear polynomials;
 /// where the multilinear polynomials are stored via their multilinear
-/// extensions:  `(coefficient, DenseMultilinearExtension)`
+/// extensions:  `(coefficient, M)` for any `M: MultilinearPolynomial`,
+/// which defaults to `DenseMultilinearExtension`. Use
+/// `dyn MultilinearPolynomial` to mix dense, sparse and lazy multiplicands.
+/// The sumcheck prover reads and folds every multiplicand through the trait,
+/// so a sparse one stays sparse in every round.
 ///
 /// * Number of products n = `polynomial.products.len()`,
 /// * Number of multiplicands of ith product m_i =
@@ -36,22 +47,93 @@ use serde::{Serialize,Deserialize};
 ///         (c0, \[0, 1, 2\]),
 ///         (c1, \[3, 4\])
 ///     \]
-/// - raw_pointers_lookup_table maps fi to i
+/// - registering fi returns the handle `MleHandle(i)`
 ///
-#[derive(Clone, Debug, Default, PartialEq)]
-pub struct VirtualPolynomial<F: PrimeField> {
+/// MLEs are registered once with `register_mle` and then referred to by
+/// their handles in `add_product`, so the same table is shared by every
+/// product that uses it. `add_mle_list` and `mul_by_mle` register an MLE
+/// unless one with the same evaluations has been registered before, in
+/// whichever `Arc` it arrived.
+///
+/// A deserialized polynomial is checked like one built with
+/// `try_from_parts`.
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(
+    try_from = "VirtualPolynomialParts<F, M>",
+    bound(deserialize = "F: Deserialize<'de>, Arc<M>: Deserialize<'de>, M: MultilinearPolynomial<F>")
+)]
+pub struct VirtualPolynomial<F: PrimeField, M: ?Sized = DenseMultilinearExtension<F>> {
     /// Aux information about the multilinear polynomial
     pub aux_info: VPAuxInfo<F>,
     /// list of reference to products (as usize) of multilinear extension
     pub products: Vec<(F, Vec<usize>)>,
     /// Stores multilinear extensions in which product multiplicand can refer
     /// to.
-    pub flattened_ml_extensions: Vec<Arc<DenseMultilinearExtension<F>>>,
-    /// Pointers to the above poly extensions
-    raw_pointers_lookup_table: HashMap<*const DenseMultilinearExtension<F>, usize>,
+    pub flattened_ml_extensions: Vec<Arc<M>>,
+    /// Positions in `flattened_ml_extensions` by a digest of the evaluations
+    /// of the MLE there. Not serialized, but rebuilt by `try_from_parts`.
+    #[serde(skip)]
+    evaluation_digests: HashMap<u64, Vec<usize>>,
+}
+
+/// The serialized fields of a `VirtualPolynomial`, checked before they are
+/// turned into one.
+#[derive(Deserialize)]
+#[serde(bound(deserialize = "F: Deserialize<'de>, Arc<M>: Deserialize<'de>"))]
+struct VirtualPolynomialParts<F: PrimeField, M: ?Sized> {
+    aux_info: VPAuxInfo<F>,
+    products: Vec<(F, Vec<usize>)>,
+    flattened_ml_extensions: Vec<Arc<M>>,
+}
+
+impl<F: PrimeField, M: MultilinearPolynomial<F> + ?Sized> TryFrom<VirtualPolynomialParts<F, M>>
+    for VirtualPolynomial<F, M>
+{
+    type Error = MleError;
+
+    fn try_from(parts: VirtualPolynomialParts<F, M>) -> Result<Self, MleError> {
+        Self::try_from_parts(
+            parts.aux_info,
+            parts.products,
+            parts.flattened_ml_extensions,
+        )
+    }
+}
+
+/// A stable handle to a multilinear extension registered in a
+/// `VirtualPolynomial`. It stays valid in clones and serialized copies of the
+/// polynomial, and its index is the position of the MLE in
+/// `flattened_ml_extensions`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+pub struct MleHandle(usize);
+
+impl MleHandle {
+    /// The position of the MLE in `flattened_ml_extensions`.
+    pub fn index(&self) -> usize {
+        self.0
+    }
+}
+
+impl<F: PrimeField, M: ?Sized> Clone for VirtualPolynomial<F, M> {
+    fn clone(&self) -> Self {
+        Self {
+            aux_info: self.aux_info.clone(),
+            products: self.products.clone(),
+            flattened_ml_extensions: self.flattened_ml_extensions.clone(),
+            evaluation_digests: self.evaluation_digests.clone(),
+        }
+    }
+}
+
+impl<F: PrimeField, M: PartialEq + ?Sized> PartialEq for VirtualPolynomial<F, M> {
+    fn eq(&self, other: &Self) -> bool {
+        self.aux_info == other.aux_info
+            && self.products == other.products
+            && self.flattened_ml_extensions == other.flattened_ml_extensions
+    }
 }
 
-#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize,Deserialize)]
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
 /// Auxiliary information about the multilinear polynomial
 pub struct VPAuxInfo<F: PrimeField> {
     /// max number of multiplicands in each product
@@ -63,18 +145,13 @@ pub struct VPAuxInfo<F: PrimeField> {
     pub phantom: PhantomData<F>,
 }
 
-impl<F: PrimeField> AsRef<[u8]> for VPAuxInfo<F> {
-    fn as_ref(&self) -> &[u8] { todo!() }
-}
-
-
-impl<F: PrimeField> Add for &VirtualPolynomial<F> {
-    type Output = VirtualPolynomial<F>;
-    fn add(self, other: &VirtualPolynomial<F>) -> Self::Output {
+impl<F: PrimeField, M: MultilinearPolynomial<F> + ?Sized> Add for &VirtualPolynomial<F, M> {
+    type Output = VirtualPolynomial<F, M>;
+    fn add(self, other: &VirtualPolynomial<F, M>) -> Self::Output {
         let start = start_timer!(|| "virtual poly add");
         let mut res = self.clone();
         for products in other.products.iter() {
-            let cur: Vec<Arc<DenseMultilinearExtension<F>>> = products
+            let cur: Vec<Arc<M>> = products
                 .1
                 .iter()
                 .map(|&x| other.flattened_ml_extensions[x].clone())
@@ -87,130 +164,349 @@ impl<F: PrimeField> Add for &VirtualPolynomial<F> {
     }
 }
 
-// TODO: convert this into a trait
-impl<F: PrimeField> VirtualPolynomial<F> {
+impl<F: PrimeField, M: MultilinearPolynomial<F> + ?Sized> VirtualPolynomial<F, M> {
     /// Creates an empty virtual polynomial with `num_variables`.
     pub fn new(num_variables: usize) -> Self {
         VirtualPolynomial {
             aux_info: VPAuxInfo {
                 max_degree: 0,
                 num_variables,
-                phantom: PhantomData::default(),
+                phantom: PhantomData,
             },
             products: Vec::new(),
             flattened_ml_extensions: Vec::new(),
-            raw_pointers_lookup_table: HashMap::new(),
+            evaluation_digests: HashMap::new(),
         }
     }
 
     /// Creates an new virtual polynomial from a MLE and its coefficient.
-    pub fn new_from_mle(mle: &Arc<DenseMultilinearExtension<F>>, coefficient: F) -> Self {
-        let mle_ptr: *const DenseMultilinearExtension<F> = Arc::as_ptr(mle);
-        let mut hm = HashMap::new();
-        hm.insert(mle_ptr, 0);
-
+    pub fn new_from_mle(mle: &Arc<M>, coefficient: F) -> Self {
         VirtualPolynomial {
             aux_info: VPAuxInfo {
                 // The max degree is the max degree of any individual variable
                 max_degree: 1,
-                num_variables: mle.num_vars,
-                phantom: PhantomData::default(),
+                num_variables: mle.num_vars(),
+                phantom: PhantomData,
             },
             // here `0` points to the first polynomial of `flattened_ml_extensions`
             products: vec![(coefficient, vec![0])],
             flattened_ml_extensions: vec![mle.clone()],
-            raw_pointers_lookup_table: hm,
+            evaluation_digests: HashMap::from([(Self::digest(mle.as_ref()), vec![0])]),
         }
     }
 
+    /// Creates a virtual polynomial from its fields.
+    /// Returns an error if a multiplicand has a different `num_vars` from
+    /// `aux_info`, or a product is empty, refers to an unknown MLE or has more
+    /// multiplicands than `aux_info.max_degree`.
+    pub fn try_from_parts(
+        aux_info: VPAuxInfo<F>,
+        products: Vec<(F, Vec<usize>)>,
+        flattened_ml_extensions: Vec<Arc<M>>,
+    ) -> Result<Self, MleError> {
+        for mle in flattened_ml_extensions.iter() {
+            if mle.num_vars() != aux_info.num_variables {
+                return Err(MleError::NumVarsMismatch {
+                    expected: aux_info.num_variables,
+                    actual: mle.num_vars(),
+                });
+            }
+        }
+        for (_, product) in products.iter() {
+            if product.is_empty() {
+                return Err(MleError::EmptyProduct);
+            }
+            if product.len() > aux_info.max_degree {
+                return Err(MleError::DegreeTooHigh {
+                    max_degree: aux_info.max_degree,
+                    degree: product.len(),
+                });
+            }
+            if let Some(&index) = product
+                .iter()
+                .find(|&&index| index >= flattened_ml_extensions.len())
+            {
+                return Err(MleError::UnknownHandle(index));
+            }
+        }
+
+        let mut evaluation_digests: HashMap<u64, Vec<usize>> = HashMap::new();
+        for (index, mle) in flattened_ml_extensions.iter().enumerate() {
+            evaluation_digests
+                .entry(Self::digest(mle.as_ref()))
+                .or_default()
+                .push(index);
+        }
+        Ok(VirtualPolynomial {
+            aux_info,
+            products,
+            flattened_ml_extensions,
+            evaluation_digests,
+        })
+    }
+
+    /// Add a product of list of multilinear extensions to self
+    /// Panics if the list is empty, or the MLE has a different `num_vars`
+    /// from self; see `try_add_mle_list`.
+    ///
+    /// The MLEs will be multiplied together, and then multiplied by the scalar
+    /// `coefficient`.
+    pub fn add_mle_list(&mut self, mle_list: impl IntoIterator<Item = Arc<M>>, coefficient: F) {
+        self.try_add_mle_list(mle_list, coefficient)
+            .unwrap_or_else(|e| panic!("{}", e))
+    }
+
     /// Add a product of list of multilinear extensions to self
     /// Returns an error if the list is empty, or the MLE has a different
-    /// `num_vars` from self.
+    /// `num_vars` from self, in which case self is left unchanged.
     ///
     /// The MLEs will be multiplied together, and then multiplied by the scalar
     /// `coefficient`.
-    pub fn add_mle_list(
+    pub fn try_add_mle_list(
         &mut self,
-        mle_list: impl IntoIterator<Item = Arc<DenseMultilinearExtension<F>>>,
+        mle_list: impl IntoIterator<Item = Arc<M>>,
         coefficient: F,
-    ) {
-        let mle_list: Vec<Arc<DenseMultilinearExtension<F>>> = mle_list.into_iter().collect();
-        let mut indexed_product = Vec::with_capacity(mle_list.len());
+    ) -> Result<(), MleError> {
+        let mle_list: Vec<Arc<M>> = mle_list.into_iter().collect();
 
-        assert!(!mle_list.is_empty(), "input mle_list is empty");
+        if mle_list.is_empty() {
+            return Err(MleError::EmptyProduct);
+        }
+        for mle in mle_list.iter() {
+            self.check_num_vars(mle.as_ref())?;
+        }
 
-        self.aux_info.max_degree = max(self.aux_info.max_degree, mle_list.len());
+        let handles: Vec<MleHandle> = mle_list
+            .into_iter()
+            .map(|mle| self.find_or_register(mle))
+            .collect();
+        self.try_add_product(handles, coefficient)
+    }
 
-        for mle in mle_list {
-            assert_eq!(
-                mle.num_vars, self.aux_info.num_variables,
-                "product has a multiplicand with wrong number of variables {} vs {}",
-                mle.num_vars, self.aux_info.num_variables
-            );
+    /// Register an MLE with self and return its handle. The MLE is not part
+    /// of the polynomial until it is used in a product.
+    /// Panics if the MLE has a different `num_vars` from self; see
+    /// `try_register_mle`.
+    pub fn register_mle(&mut self, mle: Arc<M>) -> MleHandle {
+        self.try_register_mle(mle)
+            .unwrap_or_else(|e| panic!("{}", e))
+    }
 
-            let mle_ptr: *const DenseMultilinearExtension<F> = Arc::as_ptr(&mle);
-            if let Some(index) = self.raw_pointers_lookup_table.get(&mle_ptr) {
-                indexed_product.push(*index)
-            } else {
-                let curr_index = self.flattened_ml_extensions.len();
-                self.flattened_ml_extensions.push(mle.clone());
-                self.raw_pointers_lookup_table.insert(mle_ptr, curr_index);
-                indexed_product.push(curr_index);
-            }
+    /// Register an MLE with self and return its handle.
+    /// Returns an error if the MLE has a different `num_vars` from self.
+    pub fn try_register_mle(&mut self, mle: Arc<M>) -> Result<MleHandle, MleError> {
+        self.check_num_vars(mle.as_ref())?;
+        let digest = Self::digest(mle.as_ref());
+        Ok(self.push_mle(mle, digest))
+    }
+
+    /// The MLE registered under `handle`.
+    /// Panics if the handle is unknown; see `try_mle`.
+    pub fn mle(&self, handle: MleHandle) -> &Arc<M> {
+        self.try_mle(handle).unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    /// The MLE registered under `handle`.
+    /// Returns an error if the handle is unknown.
+    pub fn try_mle(&self, handle: MleHandle) -> Result<&Arc<M>, MleError> {
+        self.flattened_ml_extensions
+            .get(handle.0)
+            .ok_or(MleError::UnknownHandle(handle.0))
+    }
+
+    /// Add a product of registered MLEs to self, multiplied by the scalar
+    /// `coefficient`.
+    /// Panics if the list is empty or a handle is unknown; see
+    /// `try_add_product`.
+    pub fn add_product(&mut self, handles: impl IntoIterator<Item = MleHandle>, coefficient: F) {
+        self.try_add_product(handles, coefficient)
+            .unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    /// Add a product of registered MLEs to self, multiplied by the scalar
+    /// `coefficient`.
+    /// Returns an error if the list is empty or a handle is unknown, in which
+    /// case self is left unchanged.
+    pub fn try_add_product(
+        &mut self,
+        handles: impl IntoIterator<Item = MleHandle>,
+        coefficient: F,
+    ) -> Result<(), MleError> {
+        let indexed_product: Vec<usize> = handles.into_iter().map(|handle| handle.0).collect();
+
+        if indexed_product.is_empty() {
+            return Err(MleError::EmptyProduct);
+        }
+        if let Some(index) = indexed_product
+            .iter()
+            .find(|&&index| index >= self.flattened_ml_extensions.len())
+        {
+            return Err(MleError::UnknownHandle(*index));
         }
+
+        self.aux_info.max_degree = max(self.aux_info.max_degree, indexed_product.len());
         self.products.push((coefficient, indexed_product));
+        Ok(())
+    }
+
+    /// Expand `expression` into a sum of products and add them to self.
+    /// Panics if the expression has a constant term or an unknown handle; see
+    /// `try_add_expression`.
+    pub fn add_expression(&mut self, expression: &Expression<F>) {
+        self.try_add_expression(expression)
+            .unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    /// Expand `expression` into a sum of products and add them to self.
+    /// Returns an error if the expression has a non-zero constant term, which
+    /// a product of MLEs cannot express, or refers to an unknown handle. In
+    /// that case self is left unchanged.
+    pub fn try_add_expression(&mut self, expression: &Expression<F>) -> Result<(), MleError> {
+        let terms = expression.expand();
+        for (_, handles) in terms.iter() {
+            if handles.is_empty() {
+                return Err(MleError::ConstantTerm);
+            }
+            if let Some(handle) = handles
+                .iter()
+                .find(|handle| handle.0 >= self.flattened_ml_extensions.len())
+            {
+                return Err(MleError::UnknownHandle(handle.0));
+            }
+        }
+        for (coefficient, handles) in terms {
+            self.try_add_product(handles, coefficient)?;
+        }
+        Ok(())
+    }
+
+    /// The handle of an MLE with the same evaluations as `mle` if one is
+    /// registered already, or of the new registration of `mle` otherwise.
+    fn find_or_register(&mut self, mle: Arc<M>) -> MleHandle {
+        // a shared `Arc` is found without reading its evaluations
+        if let Some(index) = self
+            .flattened_ml_extensions
+            .iter()
+            .position(|registered| Arc::ptr_eq(registered, &mle))
+        {
+            return MleHandle(index);
+        }
+        let digest = Self::digest(mle.as_ref());
+        // `flattened_ml_extensions` is public, so a candidate is compared
+        // with `mle` before it is trusted
+        let found = self.evaluation_digests.get(&digest).and_then(|indices| {
+            indices.iter().copied().find(|&index| {
+                self.flattened_ml_extensions
+                    .get(index)
+                    .is_some_and(|registered| {
+                        registered.num_vars() == mle.num_vars()
+                            && registered.evaluations().eq(mle.evaluations())
+                    })
+            })
+        });
+        match found {
+            Some(index) => MleHandle(index),
+            None => self.push_mle(mle, digest),
+        }
+    }
+
+    /// Append `mle`, whose digest is `digest`, to `flattened_ml_extensions`
+    /// and return its handle.
+    fn push_mle(&mut self, mle: Arc<M>, digest: u64) -> MleHandle {
+        let index = self.flattened_ml_extensions.len();
+        self.evaluation_digests
+            .entry(digest)
+            .or_default()
+            .push(index);
+        self.flattened_ml_extensions.push(mle);
+        MleHandle(index)
+    }
+
+    /// A digest of the evaluations of `mle`, the key of
+    /// `evaluation_digests`.
+    fn digest(mle: &M) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        mle.num_vars().hash(&mut hasher);
+        mle.evaluations()
+            .for_each(|eval| eval.to_repr().as_ref().hash(&mut hasher));
+        hasher.finish()
     }
 
     /// Multiple the current VirtualPolynomial by an MLE:
     /// - add the MLE to the MLE list;
     /// - multiple each product by MLE and its coefficient.
+    ///
+    /// Panics if the MLE has a different `num_vars` from self; see
+    /// `try_mul_by_mle`.
+    pub fn mul_by_mle(&mut self, mle: Arc<M>, coefficient: F) {
+        self.try_mul_by_mle(mle, coefficient)
+            .unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    /// Multiple the current VirtualPolynomial by an MLE:
+    /// - add the MLE to the MLE list;
+    /// - multiple each product by MLE and its coefficient.
+    ///
     /// Returns an error if the MLE has a different `num_vars` from self.
-    pub fn mul_by_mle(&mut self, mle: Arc<DenseMultilinearExtension<F>>, coefficient: F) {
+    pub fn try_mul_by_mle(&mut self, mle: Arc<M>, coefficient: F) -> Result<(), MleError> {
         let start = start_timer!(|| "mul by mle");
 
-        assert_eq!(
-            mle.num_vars, self.aux_info.num_variables,
-            "product has a multiplicand with wrong number of variables {} vs {}",
-            mle.num_vars, self.aux_info.num_variables
-        );
-
-        let mle_ptr: *const DenseMultilinearExtension<F> = Arc::as_ptr(&mle);
+        self.check_num_vars(mle.as_ref())?;
 
         // check if this mle already exists in the virtual polynomial
-        let mle_index = match self.raw_pointers_lookup_table.get(&mle_ptr) {
-            Some(&p) => p,
-            None => {
-                self.raw_pointers_lookup_table
-                    .insert(mle_ptr, self.flattened_ml_extensions.len());
-                self.flattened_ml_extensions.push(mle);
-                self.flattened_ml_extensions.len() - 1
-            }
-        };
+        let handle = self.find_or_register(mle);
+        self.try_mul_by_handle(handle, coefficient)?;
+
+        end_timer!(start);
+        Ok(())
+    }
+
+    /// Multiple the current VirtualPolynomial by a registered MLE and its
+    /// coefficient.
+    /// Panics if the handle is unknown; see `try_mul_by_handle`.
+    pub fn mul_by_handle(&mut self, handle: MleHandle, coefficient: F) {
+        self.try_mul_by_handle(handle, coefficient)
+            .unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    /// Multiple the current VirtualPolynomial by a registered MLE and its
+    /// coefficient.
+    /// Returns an error if the handle is unknown.
+    pub fn try_mul_by_handle(&mut self, handle: MleHandle, coefficient: F) -> Result<(), MleError> {
+        if handle.0 >= self.flattened_ml_extensions.len() {
+            return Err(MleError::UnknownHandle(handle.0));
+        }
 
         for (prod_coef, indices) in self.products.iter_mut() {
             // - add the MLE to the MLE list;
             // - multiple each product by MLE and its coefficient.
-            indices.push(mle_index);
+            indices.push(handle.0);
             *prod_coef *= coefficient;
         }
 
         // increase the max degree by one as the MLE has degree 1.
         self.aux_info.max_degree += 1;
-        end_timer!(start);
+        Ok(())
     }
 
     /// Evaluate the virtual polynomial at point `point`.
-    /// Returns an error is point.len() does not match `num_variables`.
+    /// Panics if point.len() does not match `num_variables`; see
+    /// `try_evaluate`.
     pub fn evaluate(&self, point: &[F]) -> F {
+        self.try_evaluate(point).unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    /// Evaluate the virtual polynomial at point `point`.
+    /// Returns an error is point.len() does not match `num_variables`.
+    pub fn try_evaluate(&self, point: &[F]) -> Result<F, MleError> {
         let start = start_timer!(|| "evaluation");
 
-        assert_eq!(
-            self.aux_info.num_variables,
-            point.len(),
-            "wrong number of variables {} vs {}",
-            self.aux_info.num_variables,
-            point.len()
-        );
+        if self.aux_info.num_variables != point.len() {
+            return Err(MleError::PointSizeMismatch {
+                expected: self.aux_info.num_variables,
+                actual: point.len(),
+            });
+        }
 
         let evals: Vec<F> = self
             .flattened_ml_extensions
@@ -225,9 +521,66 @@ impl<F: PrimeField> VirtualPolynomial<F> {
             .sum();
 
         end_timer!(start);
-        res
+        Ok(res)
+    }
+
+    fn check_num_vars(&self, mle: &M) -> Result<(), MleError> {
+        if mle.num_vars() != self.aux_info.num_variables {
+            return Err(MleError::NumVarsMismatch {
+                expected: self.aux_info.num_variables,
+                actual: mle.num_vars(),
+            });
+        }
+        Ok(())
+    }
+
+    /// Print out the evaluation map for testing. Panic if the num_vars > 5.
+    pub fn print_evals(&self) {
+        if self.aux_info.num_variables > 5 {
+            panic!("this function is used for testing only. cannot print more than 5 num_vars")
+        }
+        for i in 0..1 << self.aux_info.num_variables {
+            let point = bit_decompose(i, self.aux_info.num_variables);
+            let point_fr: Vec<F> = point.iter().map(|&x| F::from(x as u64)).collect();
+            println!("{} {:?}", i, self.evaluate(point_fr.as_ref()))
+        }
+        println!()
+    }
+
+    /// Share every multiplicand as a `dyn MultilinearPolynomial`, without
+    /// copying the ones whose type allows it.
+    pub fn to_dyn(&self) -> VirtualPolynomial<F, dyn MultilinearPolynomial<F>> {
+        VirtualPolynomial {
+            aux_info: self.aux_info.clone(),
+            products: self.products.clone(),
+            flattened_ml_extensions: self
+                .flattened_ml_extensions
+                .iter()
+                .map(|mle| mle.clone().into_shared())
+                .collect(),
+            evaluation_digests: self.evaluation_digests.clone(),
+        }
+    }
+
+    /// Expand every multiplicand into its dense form, sharing the ones that
+    /// already are dense.
+    pub fn to_dense(&self) -> VirtualPolynomial<F> {
+        let flattened_ml_extensions: Vec<_> = self
+            .flattened_ml_extensions
+            .iter()
+            .map(|mle| mle.clone().into_dense())
+            .collect();
+        VirtualPolynomial {
+            aux_info: self.aux_info.clone(),
+            products: self.products.clone(),
+            flattened_ml_extensions,
+            // expanding an MLE keeps its evaluations, and so its digest
+            evaluation_digests: self.evaluation_digests.clone(),
+        }
     }
+}
 
+impl<F: PrimeField> VirtualPolynomial<F> {
     /// Sample a random virtual polynomial, return the polynomial and its sum.
     pub fn random(
         nv: usize,
@@ -281,35 +634,27 @@ impl<F: PrimeField> VirtualPolynomial<F> {
     //
     // This function is used in ZeroCheck.
     pub fn build_f_hat(&self, r: &[F]) -> Self {
+        self.try_build_f_hat(r).unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    /// Same as `build_f_hat`, but returns an error if `r.len()` does not
+    /// match `num_variables`.
+    pub fn try_build_f_hat(&self, r: &[F]) -> Result<Self, MleError> {
         let start = start_timer!(|| "zero check build hat f");
 
-        assert_eq!(
-            self.aux_info.num_variables,
-            r.len(),
-            "r.len() is different from number of variables: {} vs {}",
-            r.len(),
-            self.aux_info.num_variables
-        );
+        if self.aux_info.num_variables != r.len() {
+            return Err(MleError::PointSizeMismatch {
+                expected: self.aux_info.num_variables,
+                actual: r.len(),
+            });
+        }
 
         let eq_x_r = build_eq_x_r(r);
         let mut res = self.clone();
-        res.mul_by_mle(eq_x_r, F::ONE);
+        res.try_mul_by_mle(eq_x_r, F::ONE)?;
 
         end_timer!(start);
-        res
-    }
-
-    /// Print out the evaluation map for testing. Panic if the num_vars > 5.
-    pub fn print_evals(&self) {
-        if self.aux_info.num_variables > 5 {
-            panic!("this function is used for testing only. cannot print more than 5 num_vars")
-        }
-        for i in 0..1 << self.aux_info.num_variables {
-            let point = bit_decompose(i, self.aux_info.num_variables);
-            let point_fr: Vec<F> = point.iter().map(|&x| F::from(x as u64)).collect();
-            println!("{} {:?}", i, self.evaluate(point_fr.as_ref()))
-        }
-        println!()
+        Ok(res)
     }
 }
 
@@ -1,2 +1,4 @@
 #[allow(clippy::module_inception)]
 pub mod poseidon2;
+pub mod poseidon2_instance_goldilocks;
+pub mod poseidon2_params;
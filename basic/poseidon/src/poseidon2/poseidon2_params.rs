@@ -0,0 +1,43 @@
+use ff::PrimeField;
+
+#[derive(Clone, Debug)]
+pub struct Poseidon2Params<F: PrimeField> {
+    pub(crate) t: usize, // statesize
+    pub(crate) d: usize, // sbox degree
+    pub(crate) rounds_f_beginning: usize,
+    pub(crate) rounds_p: usize,
+    #[allow(dead_code)]
+    pub(crate) rounds_f_end: usize,
+    pub(crate) rounds: usize,
+    pub(crate) mat_internal_diag_m_1: Vec<F>,
+    pub(crate) round_constants: Vec<Vec<F>>,
+}
+
+impl<F: PrimeField> Poseidon2Params<F> {
+    pub fn new(
+        t: usize,
+        d: usize,
+        rounds_f: usize,
+        rounds_p: usize,
+        mat_internal_diag_m_1: &[F],
+        round_constants: &[Vec<F>],
+    ) -> Self {
+        assert!(d == 3 || d == 5 || d == 7);
+        assert_eq!(rounds_f % 2, 0);
+        assert_eq!(mat_internal_diag_m_1.len(), t);
+        let r = rounds_f / 2;
+        let rounds = rounds_f + rounds_p;
+        assert_eq!(round_constants.len(), rounds);
+
+        Poseidon2Params {
+            t,
+            d,
+            rounds_f_beginning: r,
+            rounds_p,
+            rounds_f_end: r,
+            rounds,
+            mat_internal_diag_m_1: mat_internal_diag_m_1.to_owned(),
+            round_constants: round_constants.to_owned(),
+        }
+    }
+}
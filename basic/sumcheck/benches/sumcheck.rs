@@ -0,0 +1,46 @@
+use ark_std::test_rng;
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+use ff::Field;
+use goldilocks::Goldilocks;
+use multilinear_extensions::virtual_poly::VirtualPolynomial;
+use sumcheck::structs::IOPProverState;
+use transcript::{PoseidonTranscript, Transcript};
+
+const NUM_SAMPLES: usize = 10;
+
+/// Compare proving `f(x) * eq(x, r)` with `eq` as one more multiplicand
+/// against the prover that factors it out of every round.
+fn bench_eq_factor(c: &mut Criterion) {
+    let mut group = c.benchmark_group("zerocheck");
+    group.sample_size(NUM_SAMPLES);
+
+    let mut rng = test_rng();
+    for nv in [12, 16] {
+        for degree in [2, 3] {
+            let (poly, _) =
+                VirtualPolynomial::<Goldilocks>::random(nv, (degree, degree + 1), 2, &mut rng);
+            let eq_point = (0..nv)
+                .map(|_| Goldilocks::random(&mut rng))
+                .collect::<Vec<_>>();
+            let f_hat = poly.build_f_hat(&eq_point);
+            let parameter = format!("nv_{}_degree_{}", nv, degree);
+
+            group.bench_function(BenchmarkId::new("generic", &parameter), |b| {
+                b.iter(|| {
+                    let mut transcript = PoseidonTranscript::new(b"bench");
+                    IOPProverState::prove(&f_hat, &mut transcript)
+                })
+            });
+            group.bench_function(BenchmarkId::new("eq_factor", &parameter), |b| {
+                b.iter(|| {
+                    let mut transcript = PoseidonTranscript::new(b"bench");
+                    IOPProverState::prove_with_eq_factor(&poly, &eq_point, &mut transcript)
+                })
+            });
+        }
+    }
+    group.finish();
+}
+
+criterion_group!(benches, bench_eq_factor);
+criterion_main!(benches);
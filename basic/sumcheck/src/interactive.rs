@@ -0,0 +1,361 @@
+//! The sumcheck protocol run interactively: the prover and the verifier are
+//! separate state machines that exchange typed messages over a `Channel`,
+//! and the verifier samples its challenges from its own randomness instead of
+//! a Fiat-Shamir transcript.
+
+use std::{
+    fmt::{Display, Formatter},
+    io::{self, Read, Write},
+    marker::PhantomData,
+    sync::mpsc::{channel, Receiver, Sender},
+};
+
+use ark_std::rand::RngCore;
+use goldilocks::SmallField;
+use multilinear_extensions::{
+    mle::MultilinearPolynomial,
+    virtual_poly::{VPAuxInfo, VirtualPolynomial},
+};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use transcript::Challenge;
+
+use crate::{
+    structs::{IOPProverMessage, IOPProverState, IOPVerifierState, SumCheckSubClaim},
+    util::interpolate_uni_poly,
+};
+
+/// A message exchanged between the prover and the verifier.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SumCheckMessage<F: SmallField> {
+    /// The prover opens the protocol with the shape of its polynomial and the
+    /// sum it claims over the boolean hypercube.
+    Claim {
+        aux_info: VPAuxInfo<F>,
+        claimed_sum: F,
+    },
+    /// The prover's univariate polynomial of the current round.
+    RoundPolynomial(IOPProverMessage<F>),
+    /// The verifier's random challenge for the current round.
+    Challenge(F),
+}
+
+/// An error raised while moving messages over a channel.
+#[derive(Debug)]
+pub enum ChannelError {
+    /// The other end of the channel is gone.
+    Disconnected,
+    /// Reading from or writing to the underlying transport failed.
+    Io(io::Error),
+    /// A message could not be encoded or decoded.
+    Codec(String),
+    /// A frame is longer than the channel accepts.
+    FrameTooLarge(u64),
+}
+
+impl Display for ChannelError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ChannelError::Disconnected => write!(f, "channel is disconnected"),
+            ChannelError::Io(e) => write!(f, "channel i/o error: {}", e),
+            ChannelError::Codec(e) => write!(f, "channel codec error: {}", e),
+            ChannelError::FrameTooLarge(len) => {
+                write!(f, "channel frame of {} bytes is too large", len)
+            }
+        }
+    }
+}
+
+impl std::error::Error for ChannelError {}
+
+impl From<io::Error> for ChannelError {
+    fn from(e: io::Error) -> Self {
+        ChannelError::Io(e)
+    }
+}
+
+/// An error raised by either party of an interactive sumcheck.
+#[derive(Debug)]
+pub enum InteractiveError {
+    Channel(ChannelError),
+    /// The other party sent a message the protocol does not expect now.
+    UnexpectedMessage(&'static str),
+    /// The verifier rejected the proof.
+    Rejected(&'static str),
+}
+
+impl Display for InteractiveError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            InteractiveError::Channel(e) => write!(f, "{}", e),
+            InteractiveError::UnexpectedMessage(e) => write!(f, "unexpected message: {}", e),
+            InteractiveError::Rejected(e) => write!(f, "verifier rejected: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for InteractiveError {}
+
+impl From<ChannelError> for InteractiveError {
+    fn from(e: ChannelError) -> Self {
+        InteractiveError::Channel(e)
+    }
+}
+
+/// One end of a bidirectional, ordered message channel.
+pub trait Channel<M> {
+    /// Send a message to the other end.
+    fn send(&mut self, msg: &M) -> Result<(), ChannelError>;
+
+    /// Block until a message from the other end arrives.
+    fn recv(&mut self) -> Result<M, ChannelError>;
+}
+
+/// A channel between two threads of the same process.
+pub struct InProcessChannel<M> {
+    sender: Sender<M>,
+    receiver: Receiver<M>,
+}
+
+impl<M> InProcessChannel<M> {
+    /// Create both ends of a channel.
+    pub fn pair() -> (Self, Self) {
+        let (sender_a, receiver_b) = channel();
+        let (sender_b, receiver_a) = channel();
+        (
+            Self {
+                sender: sender_a,
+                receiver: receiver_a,
+            },
+            Self {
+                sender: sender_b,
+                receiver: receiver_b,
+            },
+        )
+    }
+}
+
+impl<M: Clone> Channel<M> for InProcessChannel<M> {
+    fn send(&mut self, msg: &M) -> Result<(), ChannelError> {
+        self.sender
+            .send(msg.clone())
+            .map_err(|_| ChannelError::Disconnected)
+    }
+
+    fn recv(&mut self) -> Result<M, ChannelError> {
+        self.receiver.recv().map_err(|_| ChannelError::Disconnected)
+    }
+}
+
+/// A channel over a Unix domain socket. Each message is sent as its bincode
+/// encoding, prefixed by the length of the encoding as a little endian `u64`.
+/// Frames longer than `MAX_FRAME_SIZE` are refused on both ends, so that the
+/// peer cannot choose the size of an allocation.
+#[cfg(unix)]
+pub struct UnixSocketChannel<M> {
+    stream: std::os::unix::net::UnixStream,
+    phantom: PhantomData<M>,
+}
+
+/// The longest frame a `UnixSocketChannel` sends or receives, in bytes.
+#[cfg(unix)]
+pub const MAX_FRAME_SIZE: u64 = 1 << 26;
+
+#[cfg(unix)]
+impl<M> UnixSocketChannel<M> {
+    /// Wrap one end of a connected socket.
+    pub fn new(stream: std::os::unix::net::UnixStream) -> Self {
+        Self {
+            stream,
+            phantom: PhantomData,
+        }
+    }
+
+    /// Create both ends of a channel over an unnamed socket pair.
+    pub fn pair() -> Result<(Self, Self), ChannelError> {
+        let (a, b) = std::os::unix::net::UnixStream::pair()?;
+        Ok((Self::new(a), Self::new(b)))
+    }
+}
+
+#[cfg(unix)]
+impl<M: Serialize + DeserializeOwned> Channel<M> for UnixSocketChannel<M> {
+    fn send(&mut self, msg: &M) -> Result<(), ChannelError> {
+        let bytes = bincode::serialize(msg).map_err(|e| ChannelError::Codec(e.to_string()))?;
+        if bytes.len() as u64 > MAX_FRAME_SIZE {
+            return Err(ChannelError::FrameTooLarge(bytes.len() as u64));
+        }
+        self.stream.write_all(&(bytes.len() as u64).to_le_bytes())?;
+        self.stream.write_all(&bytes)?;
+        Ok(())
+    }
+
+    fn recv(&mut self) -> Result<M, ChannelError> {
+        let mut len = [0u8; 8];
+        self.stream
+            .read_exact(&mut len)
+            .map_err(|e| match e.kind() {
+                io::ErrorKind::UnexpectedEof => ChannelError::Disconnected,
+                _ => ChannelError::Io(e),
+            })?;
+        let len = u64::from_le_bytes(len);
+        if len > MAX_FRAME_SIZE {
+            return Err(ChannelError::FrameTooLarge(len));
+        }
+        let mut bytes = vec![0u8; len as usize];
+        self.stream.read_exact(&mut bytes)?;
+        bincode::deserialize(&bytes).map_err(|e| ChannelError::Codec(e.to_string()))
+    }
+}
+
+/// The prover side of an interactive sumcheck.
+pub struct InteractiveProver<F: SmallField> {
+    state: IOPProverState<F>,
+    claimed_sum: F,
+}
+
+impl<F: SmallField> InteractiveProver<F> {
+    /// Prepare to prove that `poly` sums to `claimed_sum`.
+    pub fn new<M: MultilinearPolynomial<F> + ?Sized>(
+        poly: &VirtualPolynomial<F, M>,
+        claimed_sum: F,
+    ) -> Self {
+        Self {
+            state: IOPProverState::prover_init(poly),
+            claimed_sum,
+        }
+    }
+
+    /// Run the protocol to the end, returning the point chosen by the
+    /// verifier.
+    pub fn run<C: Channel<SumCheckMessage<F>>>(
+        mut self,
+        channel: &mut C,
+    ) -> Result<Vec<F>, InteractiveError> {
+        let aux_info = self.state.poly.aux_info.clone();
+        channel.send(&SumCheckMessage::Claim {
+            aux_info: aux_info.clone(),
+            claimed_sum: self.claimed_sum,
+        })?;
+
+        let mut challenge = None;
+        for _ in 0..aux_info.num_variables {
+            let prover_msg = self.state.prove_round_and_update_state(&challenge);
+            channel.send(&SumCheckMessage::RoundPolynomial(prover_msg))?;
+            match channel.recv()? {
+                SumCheckMessage::Challenge(r) => challenge = Some(challenge_from_element(r)),
+                _ => return Err(InteractiveError::UnexpectedMessage("expected a challenge")),
+            }
+        }
+        // pushing the last challenge point to the state
+        if let Some(p) = challenge {
+            self.state.challenges.push(p)
+        };
+
+        Ok(self
+            .state
+            .challenges
+            .iter()
+            .map(|challenge| challenge.elements[0])
+            .collect())
+    }
+}
+
+/// The verifier side of an interactive sumcheck.
+pub struct InteractiveVerifier<F: SmallField, R: RngCore> {
+    rng: R,
+    phantom: PhantomData<F>,
+}
+
+impl<F: SmallField, R: RngCore> InteractiveVerifier<F, R> {
+    /// Prepare to verify with challenges sampled from `rng`.
+    pub fn new(rng: R) -> Self {
+        Self {
+            rng,
+            phantom: PhantomData,
+        }
+    }
+
+    /// Run the protocol to the end, checking every round as it arrives.
+    ///
+    /// The shape of the polynomial and the claimed sum come from the caller;
+    /// the claim opening the protocol is only compared with them, so that the
+    /// prover cannot choose the amount of work of the verifier.
+    ///
+    /// Returns the subclaim that the polynomial evaluates to
+    /// `expected_evaluation` at `point`, which the caller still has to check.
+    pub fn run<C: Channel<SumCheckMessage<F>>>(
+        mut self,
+        channel: &mut C,
+        aux_info: &VPAuxInfo<F>,
+        claimed_sum: F,
+    ) -> Result<SumCheckSubClaim<F>, InteractiveError> {
+        match channel.recv()? {
+            SumCheckMessage::Claim {
+                aux_info: claimed_aux_info,
+                claimed_sum: prover_sum,
+            } => {
+                if claimed_aux_info != *aux_info || prover_sum != claimed_sum {
+                    return Err(InteractiveError::Rejected(
+                        "claim does not match the expected one",
+                    ));
+                }
+            }
+            _ => return Err(InteractiveError::UnexpectedMessage("expected a claim")),
+        };
+        if aux_info.num_variables == 0 {
+            return Err(InteractiveError::Rejected("claim is over a constant"));
+        }
+        // a round polynomial needs P(0) and P(1)
+        if aux_info.max_degree == 0 {
+            return Err(InteractiveError::Rejected("claim is of degree 0"));
+        }
+        let num_evaluations = aux_info
+            .max_degree
+            .checked_add(1)
+            .ok_or(InteractiveError::Rejected("claim is of too large a degree"))?;
+
+        let mut state = IOPVerifierState::verifier_init(aux_info);
+        let mut expected = claimed_sum;
+        for _ in 0..aux_info.num_variables {
+            let prover_msg = match channel.recv()? {
+                SumCheckMessage::RoundPolynomial(prover_msg) => prover_msg,
+                _ => {
+                    return Err(InteractiveError::UnexpectedMessage(
+                        "expected a round polynomial",
+                    ))
+                }
+            };
+            if prover_msg.evaluations.len() != num_evaluations {
+                return Err(InteractiveError::Rejected(
+                    "incorrect number of evaluations",
+                ));
+            }
+            // 1. check if the received 'P(0) + P(1) = expected`.
+            if prover_msg.evaluations[0] + prover_msg.evaluations[1] != expected {
+                return Err(InteractiveError::Rejected(
+                    "Prover message is not consistent with the claim.",
+                ));
+            }
+            // 2. set `expected` to P(r)`
+            let r = F::random(&mut self.rng);
+            expected = interpolate_uni_poly(&prover_msg.evaluations, r);
+            state.update_state(&prover_msg, challenge_from_element(r));
+            channel.send(&SumCheckMessage::Challenge(r))?;
+        }
+
+        Ok(SumCheckSubClaim {
+            point: state
+                .challenges
+                .iter()
+                .map(|challenge| challenge.elements[0])
+                .collect(),
+            expected_evaluation: expected,
+        })
+    }
+}
+
+fn challenge_from_element<F: SmallField>(r: F) -> Challenge<F> {
+    let mut challenge = Challenge::default();
+    challenge.elements[0] = r;
+    challenge
+}
@@ -1,4 +1,9 @@
+pub mod interactive;
 mod prover;
 mod verifier;
 pub mod structs;
-mod util;
\ No newline at end of file
+pub mod streaming;
+mod util;
+
+#[cfg(test)]
+mod test;
@@ -1,27 +1,75 @@
-use std::sync::Arc;
+use std::marker::PhantomData;
 
 use ark_std::{end_timer, start_timer};
-use ff::PrimeField;
-use goldilocks::SmallField;
-use multilinear_extensions::{mle::DenseMultilinearExtension, virtual_poly::VirtualPolynomial};
+use goldilocks::{
+    packed::{
+        as_goldilocks_slice, as_goldilocks_slice_mut, Backend, PackedArith, PackedGoldilocks,
+        PackedKernel, WIDTH,
+    },
+    Goldilocks, SmallField,
+};
+use multilinear_extensions::{
+    mle::{DenseMultilinearExtension, MultilinearPolynomial},
+    virtual_poly::{build_eq_x_r_vec, VPAuxInfo, VirtualPolynomial},
+};
 use rayon::prelude::{IntoParallelIterator, IntoParallelRefIterator, ParallelIterator};
-use transcript::{Transcript, Challenge};
+use transcript::{Challenge, Transcript};
 
 use crate::{
-    structs::{IOPProof, IOPProverMessage, IOPProverState},
+    structs::{
+        BatchedIOPProof, CompressedIOPProof, CompressedIOPProverMessage, IOPProof,
+        IOPProverMessage, IOPProverState,
+    },
     util::{barycentric_weights, extrapolate},
 };
 
 impl<F: SmallField> IOPProverState<F> {
-    fn prove(poly: &VirtualPolynomial<F>, transcript: &mut Transcript<F>) -> IOPProof<F> {
+    /// Given a virtual polynomial, generate an IOP proof for the sum of its
+    /// evaluations over the boolean hypercube.
+    pub fn prove<M: MultilinearPolynomial<F> + ?Sized>(
+        poly: &VirtualPolynomial<F, M>,
+        transcript: &mut impl Transcript<F>,
+    ) -> IOPProof<F> {
         let start = start_timer!(|| "sum check prove");
 
         transcript.append_serializable_element(b"aux info", &poly.aux_info);
+        let proof = Self::prove_rounds(Self::prover_init(poly), transcript);
+
+        end_timer!(start);
+        proof
+    }
+
+    /// Given a virtual polynomial `f` and a point `r`, generate an IOP proof
+    /// for the sum of `f(x) * eq(x, r)` over the boolean hypercube.
+    ///
+    /// Instead of multiplying `f` by the MLE of `eq(x, r)` as
+    /// `VirtualPolynomial::build_f_hat` does, the round polynomial is split as
+    /// `s_j(X) = l_j(X) * q_j(X)` following [Gruen24](https://eprint.iacr.org/2024/108),
+    /// where `l_j(X) = eq((ρ_1, ..., ρ_{j-1}), (r_1, ..., r_{j-1})) * eq(X, r_j)`
+    /// is known to the verifier. Only `q_j` is sent, which has the degree of
+    /// `f` rather than one more, so every round message is one evaluation
+    /// shorter. Verify with `IOPVerifierState::verify_with_eq_factor`.
+    pub fn prove_with_eq_factor<M: MultilinearPolynomial<F> + ?Sized>(
+        poly: &VirtualPolynomial<F, M>,
+        eq_point: &[F],
+        transcript: &mut impl Transcript<F>,
+    ) -> IOPProof<F> {
+        let start = start_timer!(|| "sum check prove with eq factor");
+
+        transcript.append_serializable_element(b"aux info", &poly.aux_info);
+        let proof =
+            Self::prove_rounds(Self::prover_init_with_eq_factor(poly, eq_point), transcript);
+
+        end_timer!(start);
+        proof
+    }
 
-        let mut prover_state = Self::prover_init(poly);
+    /// Run all rounds of an initialized prover against the transcript.
+    fn prove_rounds(mut prover_state: Self, transcript: &mut impl Transcript<F>) -> IOPProof<F> {
+        let num_variables = prover_state.poly.aux_info.num_variables;
         let mut challenge = None;
-        let mut prover_msgs = Vec::with_capacity(poly.aux_info.num_variables);
-        for _ in 0..poly.aux_info.num_variables {
+        let mut prover_msgs = Vec::with_capacity(num_variables);
+        for _ in 0..num_variables {
             let prover_msg =
                 IOPProverState::prove_round_and_update_state(&mut prover_state, &challenge);
             transcript.append_serializable_element(b"prover msg", &prover_msg);
@@ -33,17 +81,156 @@ impl<F: SmallField> IOPProverState<F> {
             prover_state.challenges.push(p)
         };
 
-        end_timer!(start);
         IOPProof {
             // the point consists of the first elements in the challenge
-            point: prover_state.challenges.iter().map(|challenge|challenge.elements[0]).collect(),
+            point: prover_state
+                .challenges
+                .iter()
+                .map(|challenge| challenge.elements[0])
+                .collect(),
             proofs: prover_msgs,
         }
     }
 
+    /// Given a list of virtual polynomials and their claimed sums, possibly over
+    /// different numbers of variables, generate a single IOP proof for a random
+    /// linear combination of them.
+    ///
+    /// An instance with `nv_i` variables out of `nv` is front-loaded with
+    /// `nv - nv_i` dummy rounds in which it does not depend on the bound
+    /// variable. Its sum over the larger hypercube is therefore
+    /// `2^(nv - nv_i)` times its claimed sum, and it is evaluated at the last
+    /// `nv_i` coordinates of the final point.
+    pub fn prove_batch<M: MultilinearPolynomial<F> + ?Sized>(
+        polys: &[(VirtualPolynomial<F, M>, F)],
+        transcript: &mut impl Transcript<F>,
+    ) -> BatchedIOPProof<F> {
+        let start = start_timer!(|| "batched sum check prove");
+        assert!(!polys.is_empty(), "Attempt to prove an empty batch.");
+
+        for (poly, claimed_sum) in polys.iter() {
+            transcript.append_serializable_element(b"aux info", &poly.aux_info);
+            transcript.append_field_element(*claimed_sum);
+        }
+        let alpha = transcript
+            .get_and_append_challenge(b"batching coefficient")
+            .elements[0];
+        let coefficients = std::iter::successors(Some(F::ONE), |power| Some(*power * alpha))
+            .take(polys.len())
+            .collect::<Vec<_>>();
+
+        let aux_info = VPAuxInfo::<F> {
+            max_degree: polys
+                .iter()
+                .map(|(poly, _)| poly.aux_info.max_degree)
+                .max()
+                .unwrap(),
+            num_variables: polys
+                .iter()
+                .map(|(poly, _)| poly.aux_info.num_variables)
+                .max()
+                .unwrap(),
+            phantom: PhantomData,
+        };
+        transcript.append_serializable_element(b"aux info", &aux_info);
+
+        let mut prover_states = polys
+            .iter()
+            .map(|(poly, _)| Self::prover_init(poly))
+            .collect::<Vec<_>>();
+        let extrapolation_aux = (1..aux_info.max_degree)
+            .map(|degree| {
+                let points = (0..1 + degree as u64).map(F::from).collect::<Vec<_>>();
+                let weights = barycentric_weights(&points);
+                (points, weights)
+            })
+            .collect::<Vec<_>>();
+
+        let mut challenges: Vec<Challenge<F>> = Vec::with_capacity(aux_info.num_variables);
+        let mut prover_msgs = Vec::with_capacity(aux_info.num_variables);
+        for round in 0..aux_info.num_variables {
+            let mut evaluations = vec![F::ZERO; aux_info.max_degree + 1];
+            for (((poly, claimed_sum), prover_state), coefficient) in polys
+                .iter()
+                .zip(prover_states.iter_mut())
+                .zip(coefficients.iter())
+            {
+                let offset = aux_info.num_variables - poly.aux_info.num_variables;
+                let instance_evaluations = if round < offset {
+                    // dummy round: the round polynomial is the constant half of
+                    // the instance's sum over the remaining variables.
+                    let sum = *claimed_sum * F::from(2).pow_vartime([(offset - round - 1) as u64]);
+                    vec![sum; aux_info.max_degree + 1]
+                } else {
+                    let challenge = if round == offset {
+                        None
+                    } else {
+                        challenges.last().copied()
+                    };
+                    let mut instance_evaluations = prover_state
+                        .prove_round_and_update_state(&challenge)
+                        .evaluations;
+                    // extend a lower degree round polynomial to
+                    // `max_degree + 1` evaluations
+                    let degree = poly.aux_info.max_degree;
+                    if degree < aux_info.max_degree {
+                        let (points, weights) = &extrapolation_aux[degree - 1];
+                        let extrapolation = (degree + 1..=aux_info.max_degree)
+                            .map(|at| {
+                                extrapolate(
+                                    points,
+                                    weights,
+                                    &instance_evaluations,
+                                    &F::from(at as u64),
+                                )
+                            })
+                            .collect::<Vec<_>>();
+                        instance_evaluations.extend(extrapolation);
+                    }
+                    instance_evaluations
+                };
+                evaluations
+                    .iter_mut()
+                    .zip(instance_evaluations.iter())
+                    .for_each(|(evaluation, instance)| *evaluation += *coefficient * instance);
+            }
+
+            let prover_msg = IOPProverMessage { evaluations };
+            transcript.append_serializable_element(b"prover msg", &prover_msg);
+            prover_msgs.push(prover_msg);
+            challenges.push(transcript.get_and_append_challenge(b"Internal round"));
+        }
+
+        let point = challenges
+            .iter()
+            .map(|challenge| challenge.elements[0])
+            .collect::<Vec<_>>();
+        let evaluations = polys
+            .iter()
+            .map(|(poly, _)| {
+                poly.evaluate(&point[aux_info.num_variables - poly.aux_info.num_variables..])
+            })
+            .collect();
+
+        end_timer!(start);
+        BatchedIOPProof {
+            proof: IOPProof {
+                point,
+                proofs: prover_msgs,
+            },
+            evaluations,
+        }
+    }
+
     /// Initialize the prover state to argue for the sum of the input polynomial
     /// over {0,1}^`num_vars`.
-    pub fn prover_init(polynomial: &VirtualPolynomial<F>) -> Self {
+    ///
+    /// The multiplicands are shared rather than copied. A product with a
+    /// sparse multiplicand is only summed over the support of that
+    /// multiplicand, and a sparse multiplicand stays sparse when it is folded.
+    pub fn prover_init<M: MultilinearPolynomial<F> + ?Sized>(
+        polynomial: &VirtualPolynomial<F, M>,
+    ) -> Self {
         let start = start_timer!(|| "sum check prover init");
         assert_ne!(
             polynomial.aux_info.num_variables, 0,
@@ -54,7 +241,7 @@ impl<F: SmallField> IOPProverState<F> {
         Self {
             challenges: Vec::with_capacity(polynomial.aux_info.num_variables),
             round: 0,
-            poly: polynomial.clone(),
+            poly: polynomial.to_dyn(),
             extrapolation_aux: (1..polynomial.aux_info.max_degree)
                 .map(|degree| {
                     let points = (0..1 + degree as u64).map(F::from).collect::<Vec<_>>();
@@ -62,6 +249,32 @@ impl<F: SmallField> IOPProverState<F> {
                     (points, weights)
                 })
                 .collect(),
+            eq_suffix: None,
+        }
+    }
+
+    /// Initialize the prover state to argue for the sum of the input polynomial
+    /// multiplied by `eq(x, eq_point)` over {0,1}^`num_vars`.
+    pub fn prover_init_with_eq_factor<M: MultilinearPolynomial<F> + ?Sized>(
+        polynomial: &VirtualPolynomial<F, M>,
+        eq_point: &[F],
+    ) -> Self {
+        assert_eq!(
+            eq_point.len(),
+            polynomial.aux_info.num_variables,
+            "eq point has a wrong number of variables {} vs {}",
+            eq_point.len(),
+            polynomial.aux_info.num_variables
+        );
+
+        let eq_suffix = if eq_point.len() > 1 {
+            build_eq_x_r_vec(&eq_point[1..])
+        } else {
+            vec![F::ONE]
+        };
+        Self {
+            eq_suffix: Some(eq_suffix),
+            ..Self::prover_init(polynomial)
         }
     }
 
@@ -69,12 +282,15 @@ impl<F: SmallField> IOPProverState<F> {
     /// next round.
     ///
     /// Main algorithm used is from section 3.2 of [XZZPS19](https://eprint.iacr.org/2019/317.pdf#subsection.3.2).
-    fn prove_round_and_update_state(&mut self, challenge: &Option<Challenge<F>>) -> IOPProverMessage<F> {
+    pub(crate) fn prove_round_and_update_state(
+        &mut self,
+        challenge: &Option<Challenge<F>>,
+    ) -> IOPProverMessage<F> {
         let start =
             start_timer!(|| format!("sum check prove {}-th round and update state", self.round));
 
         assert!(
-            self.round >= self.poly.aux_info.num_variables,
+            self.round < self.poly.aux_info.num_variables,
             "Prover is not active"
         );
 
@@ -91,27 +307,26 @@ impl<F: SmallField> IOPProverState<F> {
         //    g(r_1, ..., r_{m-1}, x_m ... x_n)
         //
         // eval g over r_m, and mutate g to g(r_1, ... r_m,, x_{m+1}... x_n)
-        let mut flattened_ml_extensions: Vec<DenseMultilinearExtension<F>> = self
-            .poly
-            .flattened_ml_extensions
-            .par_iter()
-            .map(|x| x.as_ref().clone())
-            .collect();
-
         if let Some(chal) = challenge {
             assert!(self.round != 0, "first round should be prover first.");
 
             self.challenges.push(*chal);
 
-            let r = self.challenges[self.round - 1];
-            #[cfg(feature = "parallel")]
-            flattened_ml_extensions
-                .par_iter_mut()
-                .for_each(|mle| *mle = fix_variables(mle, &[r]));
-            #[cfg(not(feature = "parallel"))]
-            flattened_ml_extensions
-                .iter_mut()
-                .for_each(|mle| *mle = mle.fix_variables(&[r.elements[0]]));
+            let r = self.challenges[self.round - 1].elements[0];
+            // drop the lowest variable of the eq table: summing over it leaves
+            // the eq of the other variables, as eq(0, r_i) + eq(1, r_i) = 1
+            if let Some(eq_suffix) = self.eq_suffix.as_mut() {
+                for i in 0..eq_suffix.len() / 2 {
+                    eq_suffix[i] = eq_suffix[i << 1] + eq_suffix[(i << 1) + 1];
+                }
+                eq_suffix.truncate(eq_suffix.len() / 2);
+            }
+            self.poly.flattened_ml_extensions = self
+                .poly
+                .flattened_ml_extensions
+                .par_iter()
+                .map(|mle| mle.fix_variables_shared(&[r]))
+                .collect();
         } else if self.round > 0 {
             panic!("verifier message is empty");
         }
@@ -122,45 +337,28 @@ impl<F: SmallField> IOPProverState<F> {
         let products_list = self.poly.products.clone();
         let mut products_sum = vec![F::ZERO; self.poly.aux_info.max_degree + 1];
 
+        // eq(x_{m+1}... x_n, r_{m+1}... r_n) for the variables not bound yet; the
+        // factor eq(x_m, r_m) is left for the verifier to multiply in.
+        let eq_suffix = self.eq_suffix.as_deref();
+
         // Step 2: generate sum for the partial evaluated polynomial:
         // f(r_1, ... r_m,, x_{m+1}... x_n)
 
         products_list.iter().for_each(|(coefficient, products)| {
-            let mut sum = (0..1 << (self.poly.aux_info.num_variables - self.round))
-                .into_par_iter()
-                .fold(
-                    || {
-                        (
-                            vec![(F::ZERO, F::ZERO); products.len()],
-                            vec![F::ZERO; products.len() + 1],
-                        )
-                    },
-                    |(mut buf, mut acc), b| {
-                        buf.iter_mut()
-                            .zip(products.iter())
-                            .for_each(|((eval, step), f)| {
-                                let table = &flattened_ml_extensions[*f].evaluations;
-                                *eval = table[b << 1];
-                                *step = table[(b << 1) + 1] - table[b << 1];
-                            });
-                        acc[0] += buf.iter().map(|(eval, _)| eval).product::<F>();
-                        acc[1..].iter_mut().for_each(|acc| {
-                            buf.iter_mut().for_each(|(eval, step)| *eval += step as &_);
-                            *acc += buf.iter().map(|(eval, _)| eval).product::<F>();
-                        });
-                        (buf, acc)
-                    },
-                )
-                .map(|(_, partial)| partial)
-                .reduce(
-                    || vec![F::ZERO; products.len() + 1],
-                    |mut sum, partial| {
-                        sum.iter_mut()
-                            .zip(partial.iter())
-                            .for_each(|(sum, partial)| *sum += partial);
-                        sum
-                    },
-                );
+            let num_points = 1 << (self.poly.aux_info.num_variables - self.round);
+            let mles = products
+                .iter()
+                .map(|f| self.poly.flattened_ml_extensions[*f].as_ref())
+                .collect::<Vec<_>>();
+            let mut sum = match mles
+                .iter()
+                .map(|mle| mle.as_dense())
+                .collect::<Option<Vec<_>>>()
+            {
+                Some(tables) => packed_round_sums(&tables, eq_suffix)
+                    .unwrap_or_else(|| round_sums(&tables, eq_suffix, num_points)),
+                None => generic_round_sums(&mles, eq_suffix, num_points),
+            };
             sum.iter_mut().for_each(|sum| *sum *= coefficient);
             let extraploation = (0..self.poly.aux_info.max_degree - products.len())
                 .into_par_iter()
@@ -175,12 +373,6 @@ impl<F: SmallField> IOPProverState<F> {
                 .zip(sum.iter().chain(extraploation.iter()))
                 .for_each(|(products_sum, sum)| *products_sum += sum);
         });
-
-        // update prover's state to the partial evaluated polynomial
-        self.poly.flattened_ml_extensions = flattened_ml_extensions
-            .par_iter()
-            .map(|x| Arc::new(x.clone()))
-            .collect();
         end_timer!(start);
 
         IOPProverMessage {
@@ -188,3 +380,221 @@ impl<F: SmallField> IOPProverState<F> {
         }
     }
 }
+
+/// Number of `WIDTH` points a rayon task of `packed_round_sums` sums over in
+/// one `Backend::run`.
+const PACKED_MIN_LEN: usize = 64;
+
+/// The packed partial sums of `packed_round_sums` over the points `b` of
+/// `chunks`, in units of `WIDTH` points.
+struct RoundSumsKernel<'a> {
+    tables: &'a [&'a [Goldilocks]],
+    eq_suffix: Option<&'a [Goldilocks]>,
+    chunks: std::ops::Range<usize>,
+}
+
+impl PackedKernel for RoundSumsKernel<'_> {
+    type Output = Vec<PackedGoldilocks>;
+
+    #[inline(always)]
+    fn run<A: PackedArith>(self, arith: A) -> Vec<PackedGoldilocks> {
+        let mut buf = vec![(PackedGoldilocks::ZERO, PackedGoldilocks::ZERO); self.tables.len()];
+        let mut acc = vec![PackedGoldilocks::ZERO; self.tables.len() + 1];
+        let product = |buf: &[(PackedGoldilocks, PackedGoldilocks)]| {
+            buf.iter()
+                .fold(PackedGoldilocks::ONE, |product, (eval, _)| {
+                    arith.mul(product, *eval)
+                })
+        };
+        for chunk in self.chunks {
+            let points = chunk * WIDTH..(chunk + 1) * WIDTH;
+            buf.iter_mut()
+                .zip(self.tables.iter())
+                .for_each(|((eval, step), table)| {
+                    let (low, high) =
+                        PackedGoldilocks::deinterleave(&table[2 * points.start..2 * points.end]);
+                    *eval = low;
+                    *step = arith.sub(high, low);
+                });
+            if let Some(eq_suffix) = self.eq_suffix {
+                let weight = PackedGoldilocks::from_slice(&eq_suffix[points]);
+                let (eval, step) = &mut buf[0];
+                *eval = arith.mul(*eval, weight);
+                *step = arith.mul(*step, weight);
+            }
+            acc[0] = arith.add(acc[0], product(&buf));
+            acc[1..].iter_mut().for_each(|acc| {
+                buf.iter_mut()
+                    .for_each(|(eval, step)| *eval = arith.add(*eval, *step));
+                *acc = arith.add(*acc, product(&buf));
+            });
+        }
+        acc
+    }
+}
+
+/// The sums over `b` of the product of the multiplicands `tables` at
+/// `(t, b)`, for `t = 0..=tables.len()`, with the first multiplicand weighted
+/// by `eq_suffix`. This is the fast path of a round for Goldilocks, with
+/// `WIDTH` points `b` at a time; `None` if `F` is not Goldilocks or there
+/// are fewer than `WIDTH` points.
+pub(crate) fn packed_round_sums<F: SmallField>(
+    tables: &[&DenseMultilinearExtension<F>],
+    eq_suffix: Option<&[F]>,
+) -> Option<Vec<F>> {
+    let degree = tables.len();
+    let tables = tables
+        .iter()
+        .map(|table| as_goldilocks_slice(&table.evaluations))
+        .collect::<Option<Vec<_>>>()?;
+    let eq_suffix = match eq_suffix {
+        Some(eq_suffix) => Some(as_goldilocks_slice(eq_suffix)?),
+        None => None,
+    };
+    let num_points = tables.first()?.len() / 2;
+    if num_points < WIDTH {
+        return None;
+    }
+
+    let backend = Backend::detect();
+    let num_chunks = num_points / WIDTH;
+    let packed_sum = (0..num_chunks.div_ceil(PACKED_MIN_LEN))
+        .into_par_iter()
+        .map(|task| {
+            backend.run(RoundSumsKernel {
+                tables: &tables,
+                eq_suffix,
+                chunks: task * PACKED_MIN_LEN..num_chunks.min((task + 1) * PACKED_MIN_LEN),
+            })
+        })
+        .reduce(
+            || vec![PackedGoldilocks::ZERO; degree + 1],
+            |mut sum, partial| {
+                sum.iter_mut()
+                    .zip(partial.iter())
+                    .for_each(|(sum, partial)| *sum += *partial);
+                sum
+            },
+        );
+
+    let mut sum = vec![F::ZERO; degree + 1];
+    as_goldilocks_slice_mut(&mut sum)?
+        .iter_mut()
+        .zip(packed_sum.iter())
+        .for_each(|(sum, packed_sum)| *sum = packed_sum.sum());
+    Some(sum)
+}
+
+/// The sums over the `num_points` points `b` of the product of the
+/// multiplicands `tables` at `(t, b)`, for `t = 0..=tables.len()`, with the
+/// first multiplicand weighted by `eq_suffix`.
+pub(crate) fn round_sums<F: SmallField>(
+    tables: &[&DenseMultilinearExtension<F>],
+    eq_suffix: Option<&[F]>,
+    num_points: usize,
+) -> Vec<F> {
+    sum_products(
+        (0..num_points).into_par_iter(),
+        tables.len(),
+        eq_suffix,
+        |j, b| {
+            let table = &tables[j].evaluations;
+            (table[b << 1], table[(b << 1) + 1])
+        },
+    )
+}
+
+/// Same as `round_sums`, but for multiplicands of any form, which are read
+/// through `MultilinearPolynomial::evaluation`. The product vanishes at every
+/// `b` at which a sparse multiplicand is zero at both `(0, b)` and `(1, b)`,
+/// so if there is one, only the points `b` of the smallest support are
+/// visited.
+pub(crate) fn generic_round_sums<F: SmallField>(
+    mles: &[&dyn MultilinearPolynomial<F>],
+    eq_suffix: Option<&[F]>,
+    num_points: usize,
+) -> Vec<F> {
+    let pair = |j: usize, b: usize| (mles[j].evaluation(b << 1), mles[j].evaluation((b << 1) + 1));
+    let support = mles
+        .iter()
+        .filter_map(|mle| mle.support())
+        .map(|support| {
+            let mut points = support.map(|index| index >> 1).collect::<Vec<_>>();
+            points.dedup();
+            points
+        })
+        .min_by_key(|points| points.len());
+    match support {
+        Some(points) => sum_products(points.into_par_iter(), mles.len(), eq_suffix, pair),
+        None => sum_products((0..num_points).into_par_iter(), mles.len(), eq_suffix, pair),
+    }
+}
+
+/// The sums over the points `b` of the product of `degree` multiplicands at
+/// `(t, b)`, for `t = 0..=degree`, with the first multiplicand weighted by
+/// `eq_suffix`, where `pair(j, b)` are the evaluations of the j-th
+/// multiplicand at `(0, b)` and `(1, b)`.
+fn sum_products<F: SmallField>(
+    points: impl ParallelIterator<Item = usize>,
+    degree: usize,
+    eq_suffix: Option<&[F]>,
+    pair: impl Fn(usize, usize) -> (F, F) + Sync,
+) -> Vec<F> {
+    points
+        .fold(
+            || (vec![(F::ZERO, F::ZERO); degree], vec![F::ZERO; degree + 1]),
+            |(mut buf, mut acc), b| {
+                buf.iter_mut().enumerate().for_each(|(j, (eval, step))| {
+                    let (at_zero, at_one) = pair(j, b);
+                    *eval = at_zero;
+                    *step = at_one - at_zero;
+                });
+                // the product is linear in each multiplicand, so
+                // weighting the first one weights every evaluation
+                if let Some(eq_suffix) = &eq_suffix {
+                    let (eval, step) = &mut buf[0];
+                    *eval *= eq_suffix[b];
+                    *step *= eq_suffix[b];
+                }
+                acc[0] += buf.iter().map(|(eval, _)| eval).product::<F>();
+                acc[1..].iter_mut().for_each(|acc| {
+                    buf.iter_mut().for_each(|(eval, step)| *eval += step as &_);
+                    *acc += buf.iter().map(|(eval, _)| eval).product::<F>();
+                });
+                (buf, acc)
+            },
+        )
+        .map(|(_, partial)| partial)
+        .reduce(
+            || vec![F::ZERO; degree + 1],
+            |mut sum, partial| {
+                sum.iter_mut()
+                    .zip(partial.iter())
+                    .for_each(|(sum, partial)| *sum += partial);
+                sum
+            },
+        )
+}
+
+impl<F: SmallField> IOPProof<F> {
+    /// Drop the evaluation at 1 from every round message; see
+    /// `CompressedIOPProof`.
+    pub fn compress(&self) -> CompressedIOPProof<F> {
+        CompressedIOPProof {
+            point: self.point.clone(),
+            proofs: self
+                .proofs
+                .iter()
+                .map(|msg| CompressedIOPProverMessage {
+                    evaluations: msg
+                        .evaluations
+                        .iter()
+                        .enumerate()
+                        .filter(|(i, _)| *i != 1)
+                        .map(|(_, evaluation)| *evaluation)
+                        .collect(),
+                })
+                .collect(),
+        }
+    }
+}
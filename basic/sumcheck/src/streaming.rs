@@ -0,0 +1,320 @@
+//! A small-space sumcheck prover for polynomials whose evaluation tables do
+//! not fit in memory.
+//!
+//! Instead of folding every table after each round, the prover reads the
+//! original evaluations again in every round and folds the variables bound so
+//! far on the fly, weighting them by `eq(x, r)` tables over blocks of
+//! `ceil(nv / k)` challenges. With `k` blocks this needs
+//! `O(k * 2^(nv / k))` field elements of memory per thread, at the cost of
+//! `O(nv * 2^nv)` instead of `O(2^nv)` work.
+
+use std::{cmp::max, marker::PhantomData, sync::Arc};
+
+use ark_std::{end_timer, start_timer};
+use ff::{Field, PrimeField};
+use goldilocks::SmallField;
+use multilinear_extensions::{
+    mle::DenseMultilinearExtension,
+    mmap_mle::MmapMultilinearExtension,
+    virtual_poly::{build_eq_x_r_vec, VPAuxInfo},
+};
+use rayon::prelude::{IntoParallelIterator, ParallelIterator};
+use transcript::{Challenge, Transcript};
+
+use crate::structs::{IOPProof, IOPProverMessage, StreamingProverState};
+
+/// A source of the evaluations of a multilinear polynomial over
+/// {0,1}^`num_vars` that can be read any number of times.
+pub trait EvaluationStream<F>: Send + Sync {
+    /// Number of variables of the streamed polynomial.
+    fn num_vars(&self) -> usize;
+
+    /// Write the evaluations at indices `start..start + buf.len()` into `buf`,
+    /// where an index represents a point in {0,1}^`num_vars` in little endian
+    /// form.
+    fn read(&self, start: usize, buf: &mut [F]);
+}
+
+impl<F: Field> EvaluationStream<F> for DenseMultilinearExtension<F> {
+    fn num_vars(&self) -> usize {
+        self.num_vars
+    }
+
+    fn read(&self, start: usize, buf: &mut [F]) {
+        buf.copy_from_slice(&self.evaluations[start..start + buf.len()]);
+    }
+}
+
+impl<F: PrimeField> EvaluationStream<F> for MmapMultilinearExtension<F> {
+    fn num_vars(&self) -> usize {
+        self.num_vars
+    }
+
+    fn read(&self, start: usize, buf: &mut [F]) {
+        self.read(start, buf)
+    }
+}
+
+/// Streams evaluations that are computed on demand by a generator mapping an
+/// index of the boolean hypercube to the evaluation at that point.
+pub struct EvaluationGenerator<G> {
+    num_vars: usize,
+    generator: G,
+}
+
+impl<G> EvaluationGenerator<G> {
+    pub fn new(num_vars: usize, generator: G) -> Self {
+        Self {
+            num_vars,
+            generator,
+        }
+    }
+}
+
+impl<F, G: Fn(usize) -> F + Send + Sync> EvaluationStream<F> for EvaluationGenerator<G> {
+    fn num_vars(&self) -> usize {
+        self.num_vars
+    }
+
+    fn read(&self, start: usize, buf: &mut [F]) {
+        buf.iter_mut()
+            .enumerate()
+            .for_each(|(i, eval)| *eval = (self.generator)(start + i));
+    }
+}
+
+/// A sum of products of streamed multilinear polynomials; the streaming
+/// counterpart of `VirtualPolynomial`, with `products` indexing into `streams`.
+pub struct StreamingPolynomial<F: SmallField> {
+    /// Aux information about the multilinear polynomial
+    pub aux_info: VPAuxInfo<F>,
+    /// list of reference to products (as usize) of streamed polynomials
+    pub products: Vec<(F, Vec<usize>)>,
+    /// Stores the streams in which product multiplicand can refer to.
+    pub streams: Vec<Arc<dyn EvaluationStream<F>>>,
+}
+
+impl<F: SmallField> StreamingPolynomial<F> {
+    /// Creates an empty streaming polynomial with `num_variables`.
+    pub fn new(num_variables: usize) -> Self {
+        Self {
+            aux_info: VPAuxInfo {
+                max_degree: 0,
+                num_variables,
+                phantom: PhantomData,
+            },
+            products: Vec::new(),
+            streams: Vec::new(),
+        }
+    }
+
+    /// Add a product of list of streamed polynomials to self, multiplied by
+    /// the scalar `coefficient`.
+    pub fn add_stream_list(
+        &mut self,
+        stream_list: impl IntoIterator<Item = Arc<dyn EvaluationStream<F>>>,
+        coefficient: F,
+    ) {
+        let stream_list: Vec<Arc<dyn EvaluationStream<F>>> = stream_list.into_iter().collect();
+        assert!(!stream_list.is_empty(), "input stream_list is empty");
+
+        self.aux_info.max_degree = max(self.aux_info.max_degree, stream_list.len());
+
+        let mut indexed_product = Vec::with_capacity(stream_list.len());
+        for stream in stream_list {
+            assert_eq!(
+                stream.num_vars(),
+                self.aux_info.num_variables,
+                "product has a multiplicand with wrong number of variables {} vs {}",
+                stream.num_vars(),
+                self.aux_info.num_variables
+            );
+
+            match self.streams.iter().position(|s| Arc::ptr_eq(s, &stream)) {
+                Some(index) => indexed_product.push(index),
+                None => {
+                    indexed_product.push(self.streams.len());
+                    self.streams.push(stream);
+                }
+            }
+        }
+        self.products.push((coefficient, indexed_product));
+    }
+}
+
+impl<'a, F: SmallField> StreamingProverState<'a, F> {
+    /// Given a streamed polynomial, generate the same IOP proof as
+    /// `IOPProverState::prove` on its in-memory counterpart, folding the bound
+    /// variables with `num_blocks` eq tables.
+    pub fn prove(
+        poly: &'a StreamingPolynomial<F>,
+        num_blocks: usize,
+        transcript: &mut impl Transcript<F>,
+    ) -> IOPProof<F> {
+        let start = start_timer!(|| "streaming sum check prove");
+
+        transcript.append_serializable_element(b"aux info", &poly.aux_info);
+
+        let mut prover_state = Self::prover_init(poly, num_blocks);
+        let mut challenge = None;
+        let mut prover_msgs = Vec::with_capacity(poly.aux_info.num_variables);
+        for _ in 0..poly.aux_info.num_variables {
+            let prover_msg = prover_state.prove_round_and_update_state(&challenge);
+            transcript.append_serializable_element(b"prover msg", &prover_msg);
+            prover_msgs.push(prover_msg);
+            challenge = Some(transcript.get_and_append_challenge(b"Internal round"));
+        }
+        // pushing the last challenge point to the state
+        if let Some(p) = challenge {
+            prover_state.challenges.push(p)
+        };
+
+        end_timer!(start);
+        IOPProof {
+            // the point consists of the first elements in the challenge
+            point: prover_state
+                .challenges
+                .iter()
+                .map(|challenge| challenge.elements[0])
+                .collect(),
+            proofs: prover_msgs,
+        }
+    }
+
+    /// Initialize the prover state to argue for the sum of the streamed
+    /// polynomial over {0,1}^`num_vars`.
+    pub fn prover_init(polynomial: &'a StreamingPolynomial<F>, num_blocks: usize) -> Self {
+        assert_ne!(
+            polynomial.aux_info.num_variables, 0,
+            "Attempt to prove a constant."
+        );
+        assert_ne!(num_blocks, 0, "number of blocks should be positive");
+
+        Self {
+            challenges: Vec::with_capacity(polynomial.aux_info.num_variables),
+            round: 0,
+            poly: polynomial,
+            block_size: polynomial.aux_info.num_variables.div_ceil(num_blocks),
+        }
+    }
+
+    /// Receive message from verifier, generate prover message, and proceed to
+    /// next round.
+    pub(crate) fn prove_round_and_update_state(
+        &mut self,
+        challenge: &Option<Challenge<F>>,
+    ) -> IOPProverMessage<F> {
+        let start = start_timer!(|| format!(
+            "streaming sum check prove {}-th round and update state",
+            self.round
+        ));
+
+        assert!(
+            self.round < self.poly.aux_info.num_variables,
+            "Prover is not active"
+        );
+
+        if let Some(chal) = challenge {
+            assert!(self.round != 0, "first round should be prover first.");
+            self.challenges.push(*chal);
+        } else if self.round > 0 {
+            panic!("verifier message is empty");
+        }
+
+        // at round m, every stream g is read as
+        //
+        //    g(r_1, ..., r_{m-1}, t, x) = \sum_b eq(r_1, ..., r_{m-1}, b) g(b, t, x)
+        //
+        // where the eq weights are the product of one lookup per block table,
+        // and the lowest block is folded in a single pass over a chunk.
+        let bound_point = self
+            .challenges
+            .iter()
+            .map(|challenge| challenge.elements[0])
+            .collect::<Vec<_>>();
+        let eq_tables = if bound_point.is_empty() {
+            vec![vec![F::ONE]]
+        } else {
+            bound_point
+                .chunks(self.block_size)
+                .map(build_eq_x_r_vec)
+                .collect::<Vec<_>>()
+        };
+        let chunk_size = eq_tables[0].len();
+        let num_chunks = (1 << self.round) / chunk_size;
+        let prefix_len = self.round;
+
+        self.round += 1;
+
+        let max_degree = self.poly.aux_info.max_degree;
+        let streams = &self.poly.streams;
+        let products_list = &self.poly.products;
+        let products_sum = (0..1usize << (self.poly.aux_info.num_variables - self.round))
+            .into_par_iter()
+            .fold(
+                || {
+                    (
+                        vec![F::ZERO; chunk_size],
+                        vec![(F::ZERO, F::ZERO); streams.len()],
+                        vec![F::ZERO; max_degree + 1],
+                    )
+                },
+                |(mut buf, mut evals, mut acc), x| {
+                    for (stream, (eval, step)) in streams.iter().zip(evals.iter_mut()) {
+                        let mut folded = [F::ZERO; 2];
+                        for (t, folded) in folded.iter_mut().enumerate() {
+                            let base = ((x << 1) + t) << prefix_len;
+                            for c in 0..num_chunks {
+                                stream.read(base + c * chunk_size, &mut buf);
+                                let chunk_sum = buf
+                                    .iter()
+                                    .zip(eq_tables[0].iter())
+                                    .map(|(eval, weight)| *eval * weight)
+                                    .sum::<F>();
+                                *folded += chunk_sum * chunk_weight(&eq_tables[1..], c);
+                            }
+                        }
+                        *eval = folded[0];
+                        *step = folded[1] - folded[0];
+                    }
+                    for (coefficient, products) in products_list.iter() {
+                        for (t, acc) in acc.iter_mut().enumerate() {
+                            let t = F::from(t as u64);
+                            let product = products
+                                .iter()
+                                .map(|&i| evals[i].0 + t * evals[i].1)
+                                .product::<F>();
+                            *acc += *coefficient * product;
+                        }
+                    }
+                    (buf, evals, acc)
+                },
+            )
+            .map(|(_, _, partial)| partial)
+            .reduce(
+                || vec![F::ZERO; max_degree + 1],
+                |mut sum, partial| {
+                    sum.iter_mut()
+                        .zip(partial.iter())
+                        .for_each(|(sum, partial)| *sum += partial);
+                    sum
+                },
+            );
+
+        end_timer!(start);
+        IOPProverMessage {
+            evaluations: products_sum,
+        }
+    }
+}
+
+/// The eq weight of the `chunk`-th chunk of the bound prefix, given the eq
+/// tables of every block above the lowest one.
+fn chunk_weight<F: Field>(eq_tables: &[Vec<F>], mut chunk: usize) -> F {
+    let mut weight = F::ONE;
+    for table in eq_tables {
+        weight *= table[chunk & (table.len() - 1)];
+        chunk >>= table.len().trailing_zeros();
+    }
+    weight
+}
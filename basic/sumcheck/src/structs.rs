@@ -1,8 +1,9 @@
-use ff::PrimeField;
 use goldilocks::SmallField;
-use multilinear_extensions::virtual_poly::VirtualPolynomial;
+use multilinear_extensions::{mle::MultilinearPolynomial, virtual_poly::VirtualPolynomial};
+use serde::{Deserialize, Serialize};
 use transcript::Challenge;
-use serde::{Serialize, Deserialize};
+
+use crate::streaming::StreamingPolynomial;
 
 /// An IOP proof is a collections of
 /// - messages from prover to verifier at each round through the interactive
@@ -21,17 +22,75 @@ pub struct IOPProverMessage<F: SmallField> {
     pub(crate) evaluations: Vec<F>,
 }
 
+/// An IOP proof whose round messages omit the evaluation at 1, which the
+/// verifier recovers from the running claim as `g(1) = claim - g(0)`.
+///
+/// A round message of a degree `d` proof shrinks from `d + 1` to `d` field
+/// elements, i.e. by 50%, 33%, 25% and 20% for degrees 1 to 4. The
+/// uncompressed `IOPProof` it was derived from is easier to inspect when
+/// debugging.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CompressedIOPProof<F: SmallField> {
+    pub point: Vec<F>,
+    pub proofs: Vec<CompressedIOPProverMessage<F>>,
+}
+
+/// A message from the prover to the verifier at a given round
+/// is a list of evaluations at `0, 2, 3, ..., max_degree`.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CompressedIOPProverMessage<F: SmallField> {
+    pub(crate) evaluations: Vec<F>,
+}
+
+/// A batched IOP proof is a single sumcheck proof over a random linear
+/// combination of several instances, together with the evaluation each
+/// instance claims at its own suffix of the final point.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BatchedIOPProof<F: SmallField> {
+    pub proof: IOPProof<F>,
+    pub evaluations: Vec<F>,
+}
+
+/// A SumCheckSubClaim is a claim generated by the verifier at the end of
+/// verification when it is convinced.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct SumCheckSubClaim<F: SmallField> {
+    /// the multi-dimensional point that this multilinear extension is evaluated
+    /// to
+    pub point: Vec<F>,
+    /// the expected evaluation
+    pub expected_evaluation: F,
+}
+
 /// Prover State of a PolyIOP.
 pub struct IOPProverState<F: SmallField> {
     /// sampled randomness given by the verifier
     pub challenges: Vec<Challenge<F>>,
     /// the current round number
     pub(crate) round: usize,
-    /// pointer to the virtual polynomial
-    pub(crate) poly: VirtualPolynomial<F>,
+    /// the virtual polynomial, with its multiplicands shared in the form they
+    /// are stored in
+    pub(crate) poly: VirtualPolynomial<F, dyn MultilinearPolynomial<F>>,
     /// points with precomputed barycentric weights for extrapolating smaller
     /// degree uni-polys to `max_degree + 1` evaluations.
     pub(crate) extrapolation_aux: Vec<(Vec<F>, Vec<F>)>,
+    /// if the polynomial is implicitly multiplied by an `eq(x, r)` factor,
+    /// the evaluations of `eq` over the variables after the current round's,
+    /// i.e. `eq(x_{m+1}, ..., x_n, r_{m+1}, ..., r_n)` in round m
+    pub(crate) eq_suffix: Option<Vec<F>>,
+}
+
+/// Prover State of a PolyIOP whose multilinear polynomials are streamed in
+/// several passes rather than held in memory.
+pub struct StreamingProverState<'a, F: SmallField> {
+    /// sampled randomness given by the verifier
+    pub challenges: Vec<Challenge<F>>,
+    /// the current round number
+    pub(crate) round: usize,
+    /// pointer to the streamed polynomial
+    pub(crate) poly: &'a StreamingPolynomial<F>,
+    /// number of challenges folded by one eq table; bounds the memory used
+    pub(crate) block_size: usize,
 }
 
 /// Verifier State of a PolyIOP
@@ -45,4 +104,4 @@ pub struct IOPVerifierState<F: SmallField> {
     pub(crate) polynomials_received: Vec<Vec<F>>,
     /// a list storing the randomness sampled by the verifier at each round
     pub(crate) challenges: Vec<Challenge<F>>,
-}
\ No newline at end of file
+}
@@ -0,0 +1,698 @@
+use ark_std::test_rng;
+use babybear::BabyBear;
+use ff::Field;
+use goldilocks::{Goldilocks as F, SmallField};
+use mersenne31::Mersenne31;
+use std::{marker::PhantomData, sync::Arc};
+
+use multilinear_extensions::{
+    lazy_mle::EqPolynomial,
+    mle::{DenseMultilinearExtension, MultilinearPolynomial, SparseMultilinearExtension},
+    mmap_mle::MmapMultilinearExtension,
+    util::bit_decompose,
+    virtual_poly::{VPAuxInfo, VirtualPolynomial},
+};
+use transcript::{
+    diff, Blake3Transcript, KeccakTranscript, PoseidonTranscript, RecordingTranscript,
+    Sha256Transcript, Transcript, TranscriptOp,
+};
+
+use crate::{
+    interactive::{
+        Channel, ChannelError, InProcessChannel, InteractiveError, InteractiveProver,
+        InteractiveVerifier, SumCheckMessage,
+    },
+    prover::{packed_round_sums, round_sums},
+    streaming::{EvaluationGenerator, EvaluationStream, StreamingPolynomial},
+    structs::{IOPProverMessage, IOPProverState, IOPVerifierState, StreamingProverState},
+};
+
+fn test_sumcheck(nv: usize, num_multiplicands_range: (usize, usize), num_products: usize) {
+    test_sumcheck_with_transcript::<F, PoseidonTranscript<F>>(
+        nv,
+        num_multiplicands_range,
+        num_products,
+    );
+}
+
+fn test_sumcheck_with_transcript<F: SmallField, T: Transcript<F>>(
+    nv: usize,
+    num_multiplicands_range: (usize, usize),
+    num_products: usize,
+) {
+    let mut rng = test_rng();
+
+    let (poly, asserted_sum) =
+        VirtualPolynomial::<F>::random(nv, num_multiplicands_range, num_products, &mut rng);
+
+    let mut transcript = T::new(b"test");
+    let proof = IOPProverState::prove(&poly, &mut transcript);
+
+    let mut transcript = T::new(b"test");
+    let subclaim = IOPVerifierState::verify(asserted_sum, &proof, &poly.aux_info, &mut transcript);
+    assert_eq!(subclaim.point, proof.point);
+    assert_eq!(
+        poly.evaluate(&subclaim.point),
+        subclaim.expected_evaluation,
+        "wrong subclaim"
+    );
+}
+
+#[test]
+fn test_trivial_polynomial() {
+    test_sumcheck(1, (4, 13), 5);
+}
+
+#[test]
+fn test_normal_polynomial() {
+    for nv in 2..6 {
+        test_sumcheck(nv, (1, 4), 3);
+    }
+}
+
+#[test]
+fn test_hash_transcripts() {
+    test_sumcheck_with_transcript::<F, KeccakTranscript<F>>(4, (1, 4), 3);
+    test_sumcheck_with_transcript::<F, Sha256Transcript<F>>(4, (1, 4), 3);
+    test_sumcheck_with_transcript::<F, Blake3Transcript<F>>(4, (1, 4), 3);
+}
+
+fn sumcheck_over_field<F: SmallField>() {
+    for nv in 1..6 {
+        test_sumcheck_with_transcript::<F, PoseidonTranscript<F>>(nv, (1, 4), 3);
+    }
+    test_sumcheck_with_transcript::<F, KeccakTranscript<F>>(4, (1, 4), 3);
+}
+
+#[test]
+fn test_sumcheck_over_fields() {
+    sumcheck_over_field::<BabyBear>();
+    sumcheck_over_field::<Mersenne31>();
+}
+
+#[test]
+#[should_panic(expected = "Prover message is not consistent with the claim.")]
+fn test_wrong_claimed_sum() {
+    let mut rng = test_rng();
+    let (poly, asserted_sum) = VirtualPolynomial::<F>::random(4, (2, 3), 2, &mut rng);
+
+    let mut transcript = PoseidonTranscript::new(b"test");
+    let proof = IOPProverState::prove(&poly, &mut transcript);
+
+    let mut transcript = PoseidonTranscript::new(b"test");
+    IOPVerifierState::verify(
+        asserted_sum + F::ONE,
+        &proof,
+        &poly.aux_info,
+        &mut transcript,
+    );
+}
+
+#[test]
+#[should_panic(expected = "Prover message is not consistent with the claim.")]
+fn test_wrong_claimed_sum_with_forged_first_round() {
+    let mut rng = test_rng();
+    let (poly, asserted_sum) = VirtualPolynomial::<F>::random(4, (2, 3), 2, &mut rng);
+
+    let mut transcript = PoseidonTranscript::new(b"test");
+    let mut proof = IOPProverState::prove(&poly, &mut transcript);
+    // make the first round consistent with the wrong sum; the next challenge
+    // is derived from the forged message, so a later round gives it away
+    proof.proofs[0].evaluations[0] += F::ONE;
+
+    let mut transcript = PoseidonTranscript::new(b"test");
+    IOPVerifierState::verify(
+        asserted_sum + F::ONE,
+        &proof,
+        &poly.aux_info,
+        &mut transcript,
+    );
+}
+
+fn batched_sumcheck<F: SmallField>() {
+    let mut rng = test_rng();
+
+    // instances over different numbers of variables and of different degrees
+    let instances = [
+        (5, (2, 4), 2),
+        (3, (1, 2), 3),
+        (1, (3, 5), 1),
+        (5, (1, 3), 2),
+    ]
+    .into_iter()
+    .map(|(nv, num_multiplicands_range, num_products)| {
+        VirtualPolynomial::<F>::random(nv, num_multiplicands_range, num_products, &mut rng)
+    })
+    .collect::<Vec<_>>();
+    let claimed_sums = instances.iter().map(|(_, sum)| *sum).collect::<Vec<_>>();
+    let aux_infos = instances
+        .iter()
+        .map(|(poly, _)| poly.aux_info.clone())
+        .collect::<Vec<_>>();
+
+    let mut transcript = PoseidonTranscript::new(b"test");
+    let proof = IOPProverState::prove_batch(&instances, &mut transcript);
+
+    let mut transcript = PoseidonTranscript::new(b"test");
+    let subclaims =
+        IOPVerifierState::verify_batch(&claimed_sums, &proof, &aux_infos, &mut transcript);
+
+    assert_eq!(subclaims.len(), instances.len());
+    for ((poly, _), subclaim) in instances.iter().zip(subclaims.iter()) {
+        assert_eq!(subclaim.point.len(), poly.aux_info.num_variables);
+        assert_eq!(
+            subclaim.point[..],
+            proof.proof.point[proof.proof.point.len() - subclaim.point.len()..]
+        );
+        assert_eq!(
+            poly.evaluate(&subclaim.point),
+            subclaim.expected_evaluation,
+            "wrong subclaim"
+        );
+    }
+}
+
+#[test]
+fn test_batched_sumcheck() {
+    batched_sumcheck::<F>();
+    batched_sumcheck::<BabyBear>();
+    batched_sumcheck::<Mersenne31>();
+}
+
+#[test]
+#[should_panic(expected = "Prover message is not consistent with the claim.")]
+fn test_batched_sumcheck_wrong_claimed_sum() {
+    let mut rng = test_rng();
+
+    let instances = [3, 2]
+        .into_iter()
+        .map(|nv| VirtualPolynomial::<F>::random(nv, (2, 3), 2, &mut rng))
+        .collect::<Vec<_>>();
+    let mut claimed_sums = instances.iter().map(|(_, sum)| *sum).collect::<Vec<_>>();
+    let aux_infos = instances
+        .iter()
+        .map(|(poly, _)| poly.aux_info.clone())
+        .collect::<Vec<_>>();
+
+    let mut transcript = PoseidonTranscript::new(b"test");
+    let proof = IOPProverState::prove_batch(&instances, &mut transcript);
+
+    claimed_sums[0] += F::ONE;
+    let mut transcript = PoseidonTranscript::new(b"test");
+    IOPVerifierState::verify_batch(&claimed_sums, &proof, &aux_infos, &mut transcript);
+}
+
+fn sumcheck_with_eq_factor<F: SmallField>() {
+    let mut rng = test_rng();
+    for nv in 1..6 {
+        let (poly, _) = VirtualPolynomial::<F>::random(nv, (1, 4), 3, &mut rng);
+        let eq_point = (0..nv).map(|_| F::random(&mut rng)).collect::<Vec<_>>();
+        let f_hat = poly.build_f_hat(&eq_point);
+        let asserted_sum = (0..1 << nv)
+            .map(|i| {
+                let point = bit_decompose(i, nv)
+                    .into_iter()
+                    .map(|bit| F::from(bit as u64))
+                    .collect::<Vec<_>>();
+                f_hat.evaluate(&point)
+            })
+            .sum::<F>();
+
+        let mut transcript = PoseidonTranscript::new(b"test");
+        let proof = IOPProverState::prove_with_eq_factor(&poly, &eq_point, &mut transcript);
+        // one evaluation fewer per round than the generic proof over f_hat
+        let mut transcript = PoseidonTranscript::new(b"test");
+        let generic_proof = IOPProverState::prove(&f_hat, &mut transcript);
+        for (msg, generic_msg) in proof.proofs.iter().zip(generic_proof.proofs.iter()) {
+            assert_eq!(msg.evaluations.len() + 1, generic_msg.evaluations.len());
+        }
+
+        let mut transcript = PoseidonTranscript::new(b"test");
+        let subclaim = IOPVerifierState::verify_with_eq_factor(
+            asserted_sum,
+            &proof,
+            &poly.aux_info,
+            &eq_point,
+            &mut transcript,
+        );
+        assert_eq!(subclaim.point, proof.point);
+        assert_eq!(
+            f_hat.evaluate(&subclaim.point),
+            subclaim.expected_evaluation,
+            "wrong subclaim"
+        );
+    }
+}
+
+#[test]
+fn test_sumcheck_with_eq_factor() {
+    sumcheck_with_eq_factor::<F>();
+    sumcheck_with_eq_factor::<BabyBear>();
+    sumcheck_with_eq_factor::<Mersenne31>();
+}
+
+#[test]
+#[should_panic(expected = "Prover message is not consistent with the claim.")]
+fn test_sumcheck_with_eq_factor_wrong_claimed_sum() {
+    let mut rng = test_rng();
+    let (poly, _) = VirtualPolynomial::<F>::random(3, (2, 3), 2, &mut rng);
+    let eq_point = (0..3).map(|_| F::random(&mut rng)).collect::<Vec<_>>();
+
+    let mut transcript = PoseidonTranscript::new(b"test");
+    let proof = IOPProverState::prove_with_eq_factor(&poly, &eq_point, &mut transcript);
+
+    let mut transcript = PoseidonTranscript::new(b"test");
+    IOPVerifierState::verify_with_eq_factor(
+        F::random(&mut rng),
+        &proof,
+        &poly.aux_info,
+        &eq_point,
+        &mut transcript,
+    );
+}
+
+#[test]
+fn test_compressed_sumcheck() {
+    let mut rng = test_rng();
+    for nv in 1..6 {
+        let (poly, asserted_sum) = VirtualPolynomial::<F>::random(nv, (1, 4), 3, &mut rng);
+
+        let mut transcript = PoseidonTranscript::new(b"test");
+        let proof = IOPProverState::prove(&poly, &mut transcript);
+        let compressed_proof = proof.compress();
+        for msg in compressed_proof.proofs.iter() {
+            assert_eq!(msg.evaluations.len(), poly.aux_info.max_degree);
+        }
+
+        let mut transcript = PoseidonTranscript::new(b"test");
+        let subclaim =
+            IOPVerifierState::verify(asserted_sum, &proof, &poly.aux_info, &mut transcript);
+        let mut transcript = PoseidonTranscript::new(b"test");
+        let compressed_subclaim = IOPVerifierState::verify_compressed(
+            asserted_sum,
+            &compressed_proof,
+            &poly.aux_info,
+            &mut transcript,
+        );
+        assert_eq!(subclaim, compressed_subclaim);
+        assert_eq!(
+            poly.evaluate(&compressed_subclaim.point),
+            compressed_subclaim.expected_evaluation,
+            "wrong subclaim"
+        );
+    }
+}
+
+#[test]
+fn test_compressed_sumcheck_tampered_proof() {
+    let mut rng = test_rng();
+    let (poly, asserted_sum) = VirtualPolynomial::<F>::random(4, (2, 3), 2, &mut rng);
+
+    let mut transcript = PoseidonTranscript::new(b"test");
+    let mut proof = IOPProverState::prove(&poly, &mut transcript).compress();
+    proof.proofs[3].evaluations[0] += F::ONE;
+
+    // every round is made consistent with the running claim, so the tampered
+    // message is only caught against the oracle
+    let mut transcript = PoseidonTranscript::new(b"test");
+    let subclaim =
+        IOPVerifierState::verify_compressed(asserted_sum, &proof, &poly.aux_info, &mut transcript);
+    assert_ne!(poly.evaluate(&subclaim.point), subclaim.expected_evaluation);
+}
+
+#[test]
+fn test_recorded_transcripts_diverge_at_tampered_message() {
+    type T = RecordingTranscript<F, PoseidonTranscript<F>>;
+    let mut rng = test_rng();
+    let (poly, asserted_sum) = VirtualPolynomial::<F>::random(4, (2, 3), 2, &mut rng);
+
+    let mut prover_transcript = T::new(b"test");
+    let mut proof = IOPProverState::prove(&poly, &mut prover_transcript).compress();
+    proof.proofs[3].evaluations[0] += F::ONE;
+
+    let mut verifier_transcript = T::new(b"test");
+    IOPVerifierState::verify_compressed(
+        asserted_sum,
+        &proof,
+        &poly.aux_info,
+        &mut verifier_transcript,
+    );
+
+    // the label, the aux info, then a message and a challenge per round
+    let mismatch = diff(prover_transcript.log(), verifier_transcript.log()).unwrap();
+    assert_eq!(mismatch.index, 2 + 2 * 3);
+    assert!(matches!(
+        mismatch.left,
+        Some(TranscriptOp::AppendSerializableElement { label, .. }) if label == "prover msg"
+    ));
+}
+
+#[test]
+fn test_streaming_sumcheck() {
+    let mut rng = test_rng();
+    for nv in 1..7 {
+        let (poly, _) = VirtualPolynomial::<F>::random(nv, (1, 4), 3, &mut rng);
+        let streaming_poly = StreamingPolynomial {
+            aux_info: poly.aux_info.clone(),
+            products: poly.products.clone(),
+            streams: poly
+                .flattened_ml_extensions
+                .iter()
+                .map(|mle| mle.clone() as Arc<dyn EvaluationStream<F>>)
+                .collect(),
+        };
+
+        let mut transcript = PoseidonTranscript::new(b"test");
+        let proof = IOPProverState::prove(&poly, &mut transcript);
+        for num_blocks in 1..=nv + 1 {
+            let mut transcript = PoseidonTranscript::new(b"test");
+            let streaming_proof =
+                StreamingProverState::prove(&streaming_poly, num_blocks, &mut transcript);
+            assert_eq!(proof, streaming_proof);
+        }
+    }
+}
+
+#[test]
+fn test_streaming_sumcheck_from_mmap() {
+    let mut rng = test_rng();
+    let dir = tempfile::tempdir().unwrap();
+    let nv = 6;
+    let (poly, _) = VirtualPolynomial::<F>::random(nv, (2, 3), 2, &mut rng);
+    let streaming_poly = StreamingPolynomial {
+        aux_info: poly.aux_info.clone(),
+        products: poly.products.clone(),
+        streams: poly
+            .flattened_ml_extensions
+            .iter()
+            .enumerate()
+            .map(|(i, mle)| {
+                let path = dir.path().join(format!("mle_{}", i));
+                Arc::new(MmapMultilinearExtension::from_dense(path, mle).unwrap())
+                    as Arc<dyn EvaluationStream<F>>
+            })
+            .collect(),
+    };
+
+    let mut transcript = PoseidonTranscript::new(b"test");
+    let proof = IOPProverState::prove(&poly, &mut transcript);
+    let mut transcript = PoseidonTranscript::new(b"test");
+    let streaming_proof = StreamingProverState::prove(&streaming_poly, 2, &mut transcript);
+    assert_eq!(proof, streaming_proof);
+}
+
+#[test]
+fn test_streaming_sumcheck_with_generator() {
+    let nv = 6;
+    let f = |i: usize| F::from((i * i + 7) as u64);
+    let g = |i: usize| F::from((3 * i + 1) as u64);
+
+    let mut streaming_poly = StreamingPolynomial::new(nv);
+    let f_stream: Arc<dyn EvaluationStream<F>> = Arc::new(EvaluationGenerator::new(nv, f));
+    let g_stream: Arc<dyn EvaluationStream<F>> = Arc::new(EvaluationGenerator::new(nv, g));
+    streaming_poly.add_stream_list([f_stream.clone(), g_stream], F::from(2));
+    streaming_poly.add_stream_list([f_stream], F::ONE);
+
+    let f_mle = Arc::new(DenseMultilinearExtension::from_evaluations_vec(
+        nv,
+        (0..1 << nv).map(f).collect(),
+    ));
+    let g_mle = Arc::new(DenseMultilinearExtension::from_evaluations_vec(
+        nv,
+        (0..1 << nv).map(g).collect(),
+    ));
+    let mut poly = VirtualPolynomial::new(nv);
+    poly.add_mle_list([f_mle.clone(), g_mle], F::from(2));
+    poly.add_mle_list([f_mle], F::ONE);
+    assert_eq!(poly.aux_info, streaming_poly.aux_info);
+
+    let mut transcript = PoseidonTranscript::new(b"test");
+    let proof = IOPProverState::prove(&poly, &mut transcript);
+    let mut transcript = PoseidonTranscript::new(b"test");
+    let streaming_proof = StreamingProverState::prove(&streaming_poly, 2, &mut transcript);
+    assert_eq!(proof, streaming_proof);
+}
+
+#[test]
+fn test_sumcheck_with_mixed_multiplicands() {
+    let mut rng = test_rng();
+    let nv = 6;
+    let sparse = Arc::new(SparseMultilinearExtension::<F>::random(nv, 5, &mut rng));
+    let (dense, _) = DenseMultilinearExtension::<F>::random_mle_list(nv, 1, &mut rng);
+    let r: Vec<F> = (0..nv).map(|_| F::random(&mut rng)).collect();
+    let eq = Arc::new(EqPolynomial::new(&r));
+    let eq_table = eq.to_dense();
+    let asserted_sum = sparse
+        .evaluations
+        .iter()
+        .map(|(index, value)| *value * dense[0].evaluations[*index] * eq_table.evaluations[*index])
+        .sum::<F>();
+
+    let mut poly = VirtualPolynomial::<F, dyn MultilinearPolynomial<F>>::new(nv);
+    poly.add_mle_list([sparse as _, dense[0].clone() as _, eq as _], F::ONE);
+
+    let mut transcript = PoseidonTranscript::new(b"test");
+    let proof = IOPProverState::prove(&poly, &mut transcript);
+
+    let mut transcript = PoseidonTranscript::new(b"test");
+    let subclaim = IOPVerifierState::verify(asserted_sum, &proof, &poly.aux_info, &mut transcript);
+    assert_eq!(
+        poly.evaluate(&subclaim.point),
+        subclaim.expected_evaluation,
+        "wrong subclaim"
+    );
+}
+
+/// A sparse polynomial that panics if it is ever expanded into a dense
+/// table.
+#[derive(Debug)]
+struct NeverDense(SparseMultilinearExtension<F>);
+
+impl MultilinearPolynomial<F> for NeverDense {
+    fn num_vars(&self) -> usize {
+        self.0.num_vars
+    }
+
+    fn evaluate(&self, point: &[F]) -> F {
+        self.0.evaluate(point)
+    }
+
+    fn fix_variables(&self, _: &[F]) -> DenseMultilinearExtension<F> {
+        panic!("sparse multiplicand folded into a dense table")
+    }
+
+    fn fix_variables_shared(&self, partial_point: &[F]) -> Arc<dyn MultilinearPolynomial<F>> {
+        Arc::new(NeverDense(self.0.fix_variables(partial_point)))
+    }
+
+    fn evaluations(&self) -> Box<dyn Iterator<Item = F> + '_> {
+        MultilinearPolynomial::evaluations(&self.0)
+    }
+
+    fn evaluation(&self, index: usize) -> F {
+        self.0.evaluation(index)
+    }
+
+    fn support(&self) -> Option<Box<dyn Iterator<Item = usize> + '_>> {
+        self.0.support()
+    }
+
+    fn to_dense(&self) -> DenseMultilinearExtension<F> {
+        panic!("sparse multiplicand expanded into a dense table")
+    }
+
+    fn into_shared(self: Arc<Self>) -> Arc<dyn MultilinearPolynomial<F>> {
+        self
+    }
+}
+
+#[test]
+fn test_sumcheck_never_densifies_sparse_multiplicands() {
+    let mut rng = test_rng();
+    let nv = 10;
+    let sparse = SparseMultilinearExtension::<F>::random(nv, 6, &mut rng);
+    let (dense, _) = DenseMultilinearExtension::<F>::random_mle_list(nv, 2, &mut rng);
+    let r: Vec<F> = (0..nv).map(|_| F::random(&mut rng)).collect();
+
+    let mut poly = VirtualPolynomial::<F, dyn MultilinearPolynomial<F>>::new(nv);
+    let never_dense = poly.register_mle(Arc::new(NeverDense(sparse.clone())));
+    let a = poly.register_mle(dense[0].clone());
+    let b = poly.register_mle(dense[1].clone());
+    let eq = poly.register_mle(Arc::new(EqPolynomial::new(&r)));
+    poly.add_product([never_dense, a, eq], F::from(3));
+    poly.add_product([b, never_dense], F::ONE);
+    poly.add_product([a, b], F::from(2));
+
+    // the same polynomial with every multiplicand expanded
+    let mut expanded = VirtualPolynomial::<F>::new(nv);
+    let sparse = Arc::new(sparse.to_dense());
+    let eq = Arc::new(EqPolynomial::new(&r).to_dense());
+    expanded.add_mle_list([sparse.clone(), dense[0].clone(), eq], F::from(3));
+    expanded.add_mle_list([dense[1].clone(), sparse], F::ONE);
+    expanded.add_mle_list([dense[0].clone(), dense[1].clone()], F::from(2));
+    let asserted_sum = (0..1 << nv)
+        .map(|index| {
+            let point = bit_decompose(index, nv)
+                .into_iter()
+                .map(|bit| F::from(bit as u64))
+                .collect::<Vec<_>>();
+            expanded.evaluate(&point)
+        })
+        .sum::<F>();
+
+    let mut transcript = PoseidonTranscript::new(b"test");
+    let proof = IOPProverState::prove(&poly, &mut transcript);
+    let mut transcript = PoseidonTranscript::new(b"test");
+    assert_eq!(proof, IOPProverState::prove(&expanded, &mut transcript));
+
+    let mut transcript = PoseidonTranscript::new(b"test");
+    let subclaim = IOPVerifierState::verify(asserted_sum, &proof, &poly.aux_info, &mut transcript);
+    assert_eq!(
+        poly.evaluate(&subclaim.point),
+        subclaim.expected_evaluation,
+        "wrong subclaim"
+    );
+}
+
+fn test_interactive_sumcheck<C>(mut prover_channel: C, mut verifier_channel: C)
+where
+    C: Channel<SumCheckMessage<F>> + Send + 'static,
+{
+    let mut rng = test_rng();
+    let (poly, asserted_sum) = VirtualPolynomial::<F>::random(5, (2, 4), 3, &mut rng);
+
+    let aux_info = poly.aux_info.clone();
+    let verifier = std::thread::spawn(move || {
+        InteractiveVerifier::new(test_rng()).run(&mut verifier_channel, &aux_info, asserted_sum)
+    });
+    let point = InteractiveProver::new(&poly, asserted_sum)
+        .run(&mut prover_channel)
+        .unwrap();
+    let subclaim = verifier.join().unwrap().unwrap();
+
+    assert_eq!(subclaim.point, point);
+    assert_eq!(
+        poly.evaluate(&subclaim.point),
+        subclaim.expected_evaluation,
+        "wrong subclaim"
+    );
+}
+
+#[test]
+fn test_interactive_sumcheck_in_process() {
+    let (prover_channel, verifier_channel) = InProcessChannel::pair();
+    test_interactive_sumcheck(prover_channel, verifier_channel);
+}
+
+#[cfg(unix)]
+#[test]
+fn test_interactive_sumcheck_unix_socket() {
+    let (prover_channel, verifier_channel) = crate::interactive::UnixSocketChannel::pair().unwrap();
+    test_interactive_sumcheck(prover_channel, verifier_channel);
+}
+
+#[test]
+fn test_interactive_sumcheck_wrong_claimed_sum() {
+    let mut rng = test_rng();
+    let (poly, asserted_sum) = VirtualPolynomial::<F>::random(4, (2, 3), 2, &mut rng);
+
+    for verifier_sum in [asserted_sum, asserted_sum + F::ONE] {
+        let (mut prover_channel, mut verifier_channel) = InProcessChannel::pair();
+        let aux_info = poly.aux_info.clone();
+        let verifier = std::thread::spawn(move || {
+            InteractiveVerifier::<F, _>::new(test_rng()).run(
+                &mut verifier_channel,
+                &aux_info,
+                verifier_sum,
+            )
+        });
+        // the verifier hangs up after rejecting, so the prover sees a disconnect
+        assert!(InteractiveProver::new(&poly, asserted_sum + F::ONE)
+            .run(&mut prover_channel)
+            .is_err());
+        assert!(matches!(
+            verifier.join().unwrap(),
+            Err(InteractiveError::Rejected(_))
+        ));
+    }
+}
+
+#[test]
+fn test_interactive_sumcheck_degree_zero_claim() {
+    let aux_info = VPAuxInfo {
+        max_degree: 0,
+        num_variables: 2,
+        phantom: PhantomData,
+    };
+    let claimed_sum = F::ONE;
+    let claim = SumCheckMessage::Claim {
+        aux_info: aux_info.clone(),
+        claimed_sum,
+    };
+    let round = SumCheckMessage::RoundPolynomial(IOPProverMessage {
+        evaluations: vec![claimed_sum],
+    });
+
+    // a verifier expecting another degree rejects the claim, and one
+    // expecting degree 0 rejects it before reading a round polynomial
+    let expected_aux_info = VPAuxInfo {
+        max_degree: 2,
+        ..aux_info.clone()
+    };
+    for expected_aux_info in [expected_aux_info, aux_info] {
+        let (mut prover_channel, mut verifier_channel) = InProcessChannel::pair();
+        prover_channel.send(&claim).unwrap();
+        prover_channel.send(&round).unwrap();
+        assert!(matches!(
+            InteractiveVerifier::<F, _>::new(test_rng()).run(
+                &mut verifier_channel,
+                &expected_aux_info,
+                claimed_sum
+            ),
+            Err(InteractiveError::Rejected(_))
+        ));
+    }
+}
+
+#[cfg(unix)]
+#[test]
+fn test_unix_socket_channel_frame_too_large() {
+    use std::io::Write;
+
+    let (mut a, b) = std::os::unix::net::UnixStream::pair().unwrap();
+    a.write_all(&u64::MAX.to_le_bytes()).unwrap();
+    let mut channel = crate::interactive::UnixSocketChannel::<SumCheckMessage<F>>::new(b);
+    assert!(matches!(
+        channel.recv(),
+        Err(ChannelError::FrameTooLarge(u64::MAX))
+    ));
+}
+
+#[test]
+fn test_packed_round_sums() {
+    let mut rng = test_rng();
+    for nv in 1..8 {
+        let tables = (0..3)
+            .map(|_| {
+                DenseMultilinearExtension::from_evaluations_vec(
+                    nv,
+                    (0..1 << nv).map(|_| F::random(&mut rng)).collect(),
+                )
+            })
+            .collect::<Vec<_>>();
+        let eq_suffix = (0..1 << (nv - 1))
+            .map(|_| F::random(&mut rng))
+            .collect::<Vec<_>>();
+        for products in [vec![0], vec![1, 2], vec![2, 0, 1, 1]] {
+            let tables = products.iter().map(|f| &tables[*f]).collect::<Vec<_>>();
+            for eq_suffix in [None, Some(eq_suffix.as_slice())] {
+                let expected = round_sums(&tables, eq_suffix, 1 << (nv - 1));
+                match packed_round_sums(&tables, eq_suffix) {
+                    Some(sums) => assert_eq!(sums, expected),
+                    // the packed path needs a full `PackedGoldilocks` of points
+                    None => assert!(nv < 4),
+                }
+            }
+        }
+    }
+}
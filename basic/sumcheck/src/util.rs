@@ -11,7 +11,8 @@ pub(crate) fn barycentric_weights<F: PrimeField>(points: &[F]) -> Vec<F> {
             points
                 .iter()
                 .enumerate()
-                .filter_map(|(i, point_i)| (i != j).then(|| *point_j - point_i))
+                .filter(|(i, _)| *i != j)
+                .map(|(_, point_i)| *point_j - point_i)
                 .reduce(|acc, value| acc * value)
                 .unwrap_or(F::ONE)
         })
@@ -34,8 +35,8 @@ pub fn batch_inversion_and_mul<F: PrimeField>(v: &mut [F], coeff: &F) {
     let num_elem_per_thread = max(num_elems / num_cpus_available, min_elements_per_thread);
 
     // Batch invert in parallel, without copying the vector
-    v.par_chunks_mut(num_elem_per_thread).for_each(|mut chunk| {
-        serial_batch_inversion_and_mul(&mut chunk, coeff);
+    v.par_chunks_mut(num_elem_per_thread).for_each(|chunk| {
+        serial_batch_inversion_and_mul(chunk, coeff);
     });
 }
 
@@ -51,7 +52,7 @@ fn serial_batch_inversion_and_mul<F: PrimeField>(v: &mut [F], coeff: &F) {
     // First pass: compute [a, ab, abc, ...]
     let mut prod = Vec::with_capacity(v.len());
     let mut tmp = F::ONE;
-    for f in v.iter().filter(|f| f.is_zero().unwrap_u8() == 1) {
+    for f in v.iter().filter(|f| f.is_zero().unwrap_u8() == 0) {
         tmp.mul_assign(f);
         prod.push(tmp);
     }
@@ -68,13 +69,13 @@ fn serial_batch_inversion_and_mul<F: PrimeField>(v: &mut [F], coeff: &F) {
         // Backwards
         .rev()
         // Ignore normalized elements
-        .filter(|f| f.is_zero().unwrap_u8() == 1)
+        .filter(|f| f.is_zero().unwrap_u8() == 0)
         // Backwards, skip last element, fill in one for last term.
         .zip(prod.into_iter().rev().skip(1).chain(Some(F::ONE)))
     {
         // tmp := tmp * f; f := tmp * s = 1/f
         let new_tmp = tmp * *f;
-        *f = tmp * &s;
+        *f = tmp * s;
         tmp = new_tmp;
     }
 }
@@ -96,3 +97,16 @@ pub(crate) fn extrapolate<F: PrimeField>(points: &[F], weights: &[F], evals: &[F
         .sum::<F>()
         * sum_inv
 }
+
+/// Evaluate the univariate polynomial given by its evaluations at
+/// `0, 1, ..., p_i.len() - 1` at the point `eval_at`.
+pub(crate) fn interpolate_uni_poly<F: PrimeField>(p_i: &[F], eval_at: F) -> F {
+    let points = (0..p_i.len() as u64).map(F::from).collect::<Vec<_>>();
+    // `extrapolate` divides by `eval_at - point`, so the interpolation points
+    // themselves are handled separately.
+    if let Some(i) = points.iter().position(|point| *point == eval_at) {
+        return p_i[i];
+    }
+    let weights = barycentric_weights(&points);
+    extrapolate(&points, &weights, p_i, &eval_at)
+}
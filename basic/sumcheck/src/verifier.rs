@@ -1,14 +1,241 @@
 use ark_std::{end_timer, start_timer};
-use ff::PrimeField;
 use goldilocks::SmallField;
 use multilinear_extensions::virtual_poly::VPAuxInfo;
+use std::marker::PhantomData;
 use transcript::{Challenge, Transcript};
 
-use crate::structs::{IOPProverMessage, IOPVerifierState};
+use crate::{
+    structs::{
+        BatchedIOPProof, CompressedIOPProof, IOPProof, IOPProverMessage, IOPVerifierState,
+        SumCheckSubClaim,
+    },
+    util::{barycentric_weights, extrapolate, interpolate_uni_poly},
+};
 
 impl<F: SmallField> IOPVerifierState<F> {
+    /// Verify an IOP proof for the claim that the polynomial described by
+    /// `aux_info` sums to `claimed_sum` over the boolean hypercube.
+    ///
+    /// Returns the subclaim that the polynomial evaluates to
+    /// `expected_evaluation` at `point`, which the caller still has to check.
+    pub fn verify(
+        claimed_sum: F,
+        proof: &IOPProof<F>,
+        aux_info: &VPAuxInfo<F>,
+        transcript: &mut impl Transcript<F>,
+    ) -> SumCheckSubClaim<F> {
+        let start = start_timer!(|| "sum check verify");
+
+        transcript.append_serializable_element(b"aux info", aux_info);
+
+        let mut verifier_state = Self::verifier_init(aux_info);
+        for i in 0..aux_info.num_variables {
+            let prover_msg = proof.proofs.get(i).expect("proof is incomplete");
+            transcript.append_serializable_element(b"prover msg", prover_msg);
+            verifier_state.verify_round_and_update_state(prover_msg, transcript);
+        }
+        let res = verifier_state.check_and_generate_subclaim(&claimed_sum);
+
+        end_timer!(start);
+        res
+    }
+
+    /// Verify a compressed IOP proof for the claim that the polynomial
+    /// described by `aux_info` sums to `claimed_sum` over the boolean
+    /// hypercube.
+    ///
+    /// The evaluation at 1 of each round is recovered from the running claim
+    /// before the message is absorbed, so the transcript is the same as for
+    /// the uncompressed proof.
+    pub fn verify_compressed(
+        claimed_sum: F,
+        proof: &CompressedIOPProof<F>,
+        aux_info: &VPAuxInfo<F>,
+        transcript: &mut impl Transcript<F>,
+    ) -> SumCheckSubClaim<F> {
+        let start = start_timer!(|| "sum check verify compressed");
+
+        transcript.append_serializable_element(b"aux info", aux_info);
+
+        let mut verifier_state = Self::verifier_init(aux_info);
+        let mut expected = claimed_sum;
+        for i in 0..aux_info.num_variables {
+            let compressed_msg = proof.proofs.get(i).expect("proof is incomplete");
+            assert_eq!(
+                compressed_msg.evaluations.len(),
+                aux_info.max_degree,
+                "incorrect number of evaluations: {} vs {}",
+                compressed_msg.evaluations.len(),
+                aux_info.max_degree
+            );
+
+            let mut evaluations = compressed_msg.evaluations.clone();
+            evaluations.insert(1, expected - evaluations[0]);
+            let prover_msg = IOPProverMessage { evaluations };
+
+            transcript.append_serializable_element(b"prover msg", &prover_msg);
+            let challenge = verifier_state.verify_round_and_update_state(&prover_msg, transcript);
+            expected = interpolate_uni_poly(&prover_msg.evaluations, challenge.elements[0]);
+        }
+        let res = verifier_state.check_and_generate_subclaim(&claimed_sum);
+
+        end_timer!(start);
+        res
+    }
+
+    /// Verify an IOP proof produced by `IOPProverState::prove_with_eq_factor`
+    /// for the claim that `f(x) * eq(x, eq_point)` sums to `claimed_sum`,
+    /// where `aux_info` describes `f`.
+    ///
+    /// Each round message only carries `q_j`; the verifier multiplies in the
+    /// known linear factor `l_j` and then runs the usual checks, so the
+    /// subclaim is the same as for a proof over `f.build_f_hat(eq_point)`.
+    pub fn verify_with_eq_factor(
+        claimed_sum: F,
+        proof: &IOPProof<F>,
+        aux_info: &VPAuxInfo<F>,
+        eq_point: &[F],
+        transcript: &mut impl Transcript<F>,
+    ) -> SumCheckSubClaim<F> {
+        let start = start_timer!(|| "sum check verify with eq factor");
+        assert_eq!(
+            eq_point.len(),
+            aux_info.num_variables,
+            "eq point has a wrong number of variables {} vs {}",
+            eq_point.len(),
+            aux_info.num_variables
+        );
+
+        transcript.append_serializable_element(b"aux info", aux_info);
+
+        let mut verifier_state = Self::verifier_init(&VPAuxInfo {
+            max_degree: aux_info.max_degree + 1,
+            num_variables: aux_info.num_variables,
+            phantom: PhantomData,
+        });
+        let points = (0..=aux_info.max_degree as u64)
+            .map(F::from)
+            .collect::<Vec<_>>();
+        let weights = barycentric_weights(&points);
+        let eq = |x: F, r: F| x * r + (F::ONE - x) * (F::ONE - r);
+        // eq(ρ_1, ..., ρ_{j-1}, r_1, ..., r_{j-1})
+        let mut eq_prefix = F::ONE;
+        for (i, r_i) in eq_point.iter().enumerate() {
+            let prover_msg = proof.proofs.get(i).expect("proof is incomplete");
+            transcript.append_serializable_element(b"prover msg", prover_msg);
+            assert_eq!(
+                prover_msg.evaluations.len(),
+                aux_info.max_degree + 1,
+                "incorrect number of evaluations: {} vs {}",
+                prover_msg.evaluations.len(),
+                aux_info.max_degree + 1
+            );
+
+            // recover s_j(X) = l_j(X) * q_j(X) at 0, ..., max_degree + 1
+            let at = F::from(aux_info.max_degree as u64 + 1);
+            let q_next = extrapolate(&points, &weights, &prover_msg.evaluations, &at);
+            let evaluations = prover_msg
+                .evaluations
+                .iter()
+                .chain(std::iter::once(&q_next))
+                .enumerate()
+                .map(|(x, q)| eq_prefix * eq(F::from(x as u64), *r_i) * q)
+                .collect();
+
+            let challenge = verifier_state
+                .verify_round_and_update_state(&IOPProverMessage { evaluations }, transcript);
+            eq_prefix *= eq(challenge.elements[0], *r_i);
+        }
+        let res = verifier_state.check_and_generate_subclaim(&claimed_sum);
+
+        end_timer!(start);
+        res
+    }
+
+    /// Verify a batched IOP proof produced by `IOPProverState::prove_batch`
+    /// for instances described by `aux_infos` with sums `claimed_sums`.
+    ///
+    /// Returns one subclaim per instance, on the suffix of the final point
+    /// that the instance was evaluated at.
+    pub fn verify_batch(
+        claimed_sums: &[F],
+        proof: &BatchedIOPProof<F>,
+        aux_infos: &[VPAuxInfo<F>],
+        transcript: &mut impl Transcript<F>,
+    ) -> Vec<SumCheckSubClaim<F>> {
+        let start = start_timer!(|| "batched sum check verify");
+        assert!(!aux_infos.is_empty(), "Attempt to verify an empty batch.");
+        assert_eq!(
+            claimed_sums.len(),
+            aux_infos.len(),
+            "number of claimed sums does not match number of instances"
+        );
+        assert_eq!(
+            proof.evaluations.len(),
+            aux_infos.len(),
+            "number of evaluations does not match number of instances"
+        );
+
+        for (aux_info, claimed_sum) in aux_infos.iter().zip(claimed_sums.iter()) {
+            transcript.append_serializable_element(b"aux info", aux_info);
+            transcript.append_field_element(*claimed_sum);
+        }
+        let alpha = transcript
+            .get_and_append_challenge(b"batching coefficient")
+            .elements[0];
+        let coefficients = std::iter::successors(Some(F::ONE), |power| Some(*power * alpha))
+            .take(aux_infos.len())
+            .collect::<Vec<_>>();
+
+        let aux_info = VPAuxInfo::<F> {
+            max_degree: aux_infos.iter().map(|info| info.max_degree).max().unwrap(),
+            num_variables: aux_infos
+                .iter()
+                .map(|info| info.num_variables)
+                .max()
+                .unwrap(),
+            phantom: PhantomData,
+        };
+        // an instance with fewer variables is summed over a larger hypercube on
+        // which it does not depend, which scales its sum by a power of two
+        let claimed_sum = aux_infos
+            .iter()
+            .zip(claimed_sums.iter())
+            .zip(coefficients.iter())
+            .map(|((info, claimed_sum), coefficient)| {
+                let scalar =
+                    F::from(2).pow_vartime([(aux_info.num_variables - info.num_variables) as u64]);
+                *coefficient * claimed_sum * scalar
+            })
+            .sum::<F>();
+
+        let subclaim = Self::verify(claimed_sum, &proof.proof, &aux_info, transcript);
+
+        let expected_evaluation = coefficients
+            .iter()
+            .zip(proof.evaluations.iter())
+            .map(|(coefficient, evaluation)| *coefficient * evaluation)
+            .sum::<F>();
+        assert_eq!(
+            expected_evaluation, subclaim.expected_evaluation,
+            "Batched evaluations are not consistent with the claim."
+        );
+
+        let res = aux_infos
+            .iter()
+            .zip(proof.evaluations.iter())
+            .map(|(info, evaluation)| SumCheckSubClaim {
+                point: subclaim.point[aux_info.num_variables - info.num_variables..].to_vec(),
+                expected_evaluation: *evaluation,
+            })
+            .collect();
+
+        end_timer!(start);
+        res
+    }
+
     /// Initialize the verifier's state.
-    fn verifier_init(index_info: &VPAuxInfo<F>) -> Self {
+    pub(crate) fn verifier_init(index_info: &VPAuxInfo<F>) -> Self {
         let start = start_timer!(|| "sum check verifier init");
         let res = Self {
             round: 1,
@@ -31,7 +258,7 @@ impl<F: SmallField> IOPVerifierState<F> {
     fn verify_round_and_update_state(
         &mut self,
         prover_msg: &IOPProverMessage<F>,
-        transcript: &mut Transcript<F>,
+        transcript: &mut impl Transcript<F>,
     ) -> Challenge<F> {
         let start =
             start_timer!(|| format!("sum check verify {}-th round and update state", self.round));
@@ -50,6 +277,24 @@ impl<F: SmallField> IOPVerifierState<F> {
         // such checks to `check_and_generate_subclaim` after the last round.
 
         let challenge = transcript.get_and_append_challenge(b"Internal round");
+        self.update_state(prover_msg, challenge);
+
+        end_timer!(start);
+        challenge
+    }
+
+    /// Store a prover message together with the challenge answering it, and
+    /// proceed to the next round.
+    pub(crate) fn update_state(
+        &mut self,
+        prover_msg: &IOPProverMessage<F>,
+        challenge: Challenge<F>,
+    ) {
+        assert!(
+            !self.finished,
+            "Incorrect verifier state: Verifier is already finished."
+        );
+
         self.challenges.push(challenge);
         self.polynomials_received
             .push(prover_msg.evaluations.to_vec());
@@ -61,8 +306,52 @@ impl<F: SmallField> IOPVerifierState<F> {
             // proceed to the next round
             self.round += 1;
         }
+    }
+
+    /// Check the deferred round messages against `asserted_sum` and generate
+    /// the subclaim on the final point.
+    fn check_and_generate_subclaim(&self, asserted_sum: &F) -> SumCheckSubClaim<F> {
+        let start = start_timer!(|| "sum check check and generate subclaim");
+
+        assert!(
+            self.finished,
+            "Incorrect verifier state: Verifier has not finished."
+        );
+        assert_eq!(
+            self.polynomials_received.len(),
+            self.num_vars,
+            "insufficient rounds"
+        );
+
+        let mut expected = *asserted_sum;
+        for (evaluations, challenge) in self.polynomials_received.iter().zip(self.challenges.iter())
+        {
+            assert_eq!(
+                evaluations.len(),
+                self.max_degree + 1,
+                "incorrect number of evaluations: {} vs {}",
+                evaluations.len(),
+                self.max_degree + 1
+            );
+            // the deferred check during the interactive phase:
+            // 1. check if the received 'P(0) + P(1) = expected`.
+            assert_eq!(
+                evaluations[0] + evaluations[1],
+                expected,
+                "Prover message is not consistent with the claim."
+            );
+            // 2. set `expected` to P(r)`
+            expected = interpolate_uni_poly(evaluations, challenge.elements[0]);
+        }
 
         end_timer!(start);
-        challenge
+        SumCheckSubClaim {
+            point: self
+                .challenges
+                .iter()
+                .map(|challenge| challenge.elements[0])
+                .collect(),
+            expected_evaluation: expected,
+        }
     }
 }
@@ -0,0 +1,132 @@
+//! Byte-oriented transcripts over a 256 bits hash function.
+//!
+//! Appended data is buffered, and a challenge hashes the previous state with
+//! the buffer into the new state. The challenge elements are then sampled by
+//! rejection from the words of `H(state || counter)` for increasing counters,
+//! so they are exactly uniform in the field.
+
+use std::marker::PhantomData;
+
+use goldilocks::SmallField;
+use sha3::Digest;
+
+use crate::{Challenge, Transcript, OUTPUT_WIDTH};
+
+/// A hash function with 32 bytes digests.
+pub trait TranscriptHash {
+    fn hash(data: &[u8]) -> [u8; 32];
+}
+
+/// Keccak-256, as used by the EVM.
+#[derive(Copy, Clone, Debug)]
+pub struct Keccak256;
+
+impl TranscriptHash for Keccak256 {
+    fn hash(data: &[u8]) -> [u8; 32] {
+        sha3::Keccak256::digest(data).into()
+    }
+}
+
+#[derive(Copy, Clone, Debug)]
+pub struct Sha256;
+
+impl TranscriptHash for Sha256 {
+    fn hash(data: &[u8]) -> [u8; 32] {
+        sha2::Sha256::digest(data).into()
+    }
+}
+
+#[derive(Copy, Clone, Debug)]
+pub struct Blake3;
+
+impl TranscriptHash for Blake3 {
+    fn hash(data: &[u8]) -> [u8; 32] {
+        *blake3::hash(data).as_bytes()
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct HashTranscript<F, H> {
+    state: [u8; 32],
+    /// Data appended since the last challenge.
+    pending: Vec<u8>,
+    phantom: PhantomData<(F, H)>,
+}
+
+pub type KeccakTranscript<F> = HashTranscript<F, Keccak256>;
+pub type Sha256Transcript<F> = HashTranscript<F, Sha256>;
+pub type Blake3Transcript<F> = HashTranscript<F, Blake3>;
+
+impl<F: SmallField, H: TranscriptHash> Transcript<F> for HashTranscript<F, H> {
+    fn new(label: &'static [u8]) -> Self {
+        let mut transcript = Self {
+            state: [0; 32],
+            pending: vec![],
+            phantom: PhantomData,
+        };
+        transcript.append_message(label);
+        transcript
+    }
+
+    // The message is prefixed with its length so that consecutive messages
+    // cannot be confused.
+    fn append_message(&mut self, msg: &[u8]) {
+        self.pending
+            .extend_from_slice(&(msg.len() as u64).to_le_bytes());
+        self.pending.extend_from_slice(msg);
+    }
+
+    fn append_field_element(&mut self, element: F) {
+        element
+            .to_canonical_u64_vec()
+            .into_iter()
+            .for_each(|limb| self.pending.extend_from_slice(&limb.to_le_bytes()));
+    }
+
+    fn get_and_append_challenge(&mut self, label: &'static [u8]) -> Challenge<F> {
+        self.append_message(label);
+        self.state = H::hash(&[self.state.as_slice(), &self.pending].concat());
+        self.pending.clear();
+
+        let state = self.state;
+        let mut words = (0u64..).flat_map(|counter| {
+            H::hash(&[state.as_slice(), &counter.to_le_bytes()].concat())
+                .chunks(8)
+                .map(|word| u64::from_le_bytes(word.try_into().unwrap()))
+                .collect::<Vec<_>>()
+        });
+        let mut challenge = Challenge {
+            elements: [F::ZERO; OUTPUT_WIDTH],
+        };
+        challenge
+            .elements
+            .iter_mut()
+            .for_each(|element| *element = sample_field_element(|| words.next().unwrap()));
+        self.append_challenge(challenge);
+        challenge
+    }
+
+    fn pow_hash(&self, nonce: u64) -> u64 {
+        let digest =
+            H::hash(&[self.state.as_slice(), &self.pending, &nonce.to_le_bytes()].concat());
+        u64::from_be_bytes(digest[..8].try_into().unwrap())
+    }
+}
+
+/// Sample a field element of at most 64 bits from uniform 64 bits words, by
+/// rejecting the words that are not canonical once cut to the size of the
+/// field.
+fn sample_field_element<F: SmallField>(mut next_word: impl FnMut() -> u64) -> F {
+    assert!(
+        F::NUM_BITS <= 64,
+        "cannot sample a field element of more than 64 bits"
+    );
+    let mask = u64::MAX >> (64 - F::NUM_BITS);
+    loop {
+        let word = next_word() & mask;
+        let element = F::from(word);
+        if element.to_canonical_u64_vec() == [word] {
+            return element;
+        }
+    }
+}
@@ -1,72 +1,70 @@
-//! This repo is not properly implemented
-//! Transcript APIs are placeholders; the actual logic is to be implemented later.
+//! Fiat-Shamir transcripts, behind the `Transcript` trait so that the hash
+//! can be chosen per verifier: `PoseidonTranscript` is field-native, for
+//! verifiers that are themselves circuits, while `KeccakTranscript`,
+//! `Sha256Transcript` and `Blake3Transcript` are byte-oriented, for on-chain
+//! and native verifiers.
 
-use std::marker::PhantomData;
 use goldilocks::SmallField;
 use serde::Serialize;
 
-// temporarily using 12-4 hashes
-pub const INPUT_WIDTH: usize = 12;
-pub const OUTPUT_WIDTH: usize = 12;
+mod hash;
+mod poseidon2;
+mod pow;
+mod recording;
 
-// TODO
-#[derive(Default, Copy, Clone)]
-pub struct Transcript<F> {
-    is_empty: bool,
-    state: [F; INPUT_WIDTH],
-}
+pub use hash::{
+    Blake3, Blake3Transcript, HashTranscript, Keccak256, KeccakTranscript, Sha256,
+    Sha256Transcript, TranscriptHash,
+};
+pub use poseidon2::{PoseidonTranscript, INPUT_WIDTH, RATE};
+pub use pow::{PowError, ProofOfWork};
+pub use recording::{diff, RecordingTranscript, TranscriptLog, TranscriptMismatch, TranscriptOp};
+
+/// Number of field elements in a challenge.
+pub const OUTPUT_WIDTH: usize = 4;
 
-// TODO
-#[derive(Default, Copy, Clone)]
+#[derive(Default, Copy, Clone, Debug, PartialEq, Eq)]
 pub struct Challenge<F> {
     pub elements: [F; OUTPUT_WIDTH],
 }
 
-impl<F: SmallField> Transcript<F> {
+/// A transcript of the messages of an IOP, from which the verifier's
+/// challenges are derived. The prover and the verifier must append the same
+/// messages in the same order to derive the same challenges.
+pub trait Transcript<F: SmallField>: Sized {
     /// Create a new IOP transcript.
-    pub fn new(label: &'static [u8]) -> Self {
-        // TODO!
-        println!("mock function. remember to fix me");
-        Self::default()
-    }
+    fn new(label: &'static [u8]) -> Self;
 
     // Append the message to the transcript.
-    pub fn append_message(&mut self, msg: &[u8]) {
-        // TODO!
-        println!("mock function. remember to fix me");
-    }
+    fn append_message(&mut self, msg: &[u8]);
 
     // Append the field elemetn to the transcript.
-    pub fn append_field_element(&mut self, element: F) {
-        // TODO!
-        println!("mock function. remember to fix me");
-    }
+    fn append_field_element(&mut self, element: F);
 
     // Append the challenge to the transcript.
-    pub fn append_challenge(&mut self, challenge: Challenge<F>) {
-        // TODO!
-        println!("mock function. remember to fix me");
+    fn append_challenge(&mut self, challenge: Challenge<F>) {
+        challenge
+            .elements
+            .into_iter()
+            .for_each(|element| self.append_field_element(element));
     }
 
     // Append the message to the transcript.
-    pub fn append_serializable_element<S: Serialize>(
-        &mut self,
-        label: &'static [u8],
-        element: &S,
-    ) {
-        // TODO!
-        println!("mock function. remember to fix me");
+    fn append_serializable_element<S: Serialize>(&mut self, label: &'static [u8], element: &S) {
+        self.append_message(label);
+        let bytes = bincode::serialize(element).expect("failed to serialize element");
+        self.append_message(&bytes);
     }
 
     // Generate the challenge from the current transcript
     // and append it to the transcript.
-    //
-    // The output field element is statistical uniform as long
-    // as the field has a size less than 2^384.
-    pub fn get_and_append_challenge(&mut self, label: &'static [u8]) -> Challenge<F> {
-        //  we need to reject when transcript is empty
-        println!("mock function. remember to fix me");
-        assert!(!self.is_empty);
-        Challenge::<F>::default()
-    }
-}
\ No newline at end of file
+    fn get_and_append_challenge(&mut self, label: &'static [u8]) -> Challenge<F>;
+
+    /// Hash the current transcript with a proof-of-work `nonce`, without
+    /// changing the transcript. The work of the nonce is the number of
+    /// leading zero bits of the hash; see `ProofOfWork`.
+    fn pow_hash(&self, nonce: u64) -> u64;
+}
+
+#[cfg(test)]
+mod test;
@@ -0,0 +1,114 @@
+//! A field-native transcript: a duplex sponge over the Poseidon2 permutation
+//! of width `INPUT_WIDTH` over Goldilocks.
+//!
+//! Everything appended to the transcript is encoded as Goldilocks elements and
+//! added into the first `RATE` elements of the state, which is permuted
+//! whenever they are used up. A challenge is read off the rate after padding
+//! the pending input and permuting, and is then appended to the transcript.
+
+use std::{marker::PhantomData, sync::LazyLock};
+
+use ff::Field;
+use goldilocks::{Goldilocks, SmallField};
+use poseidon::poseidon2::{
+    poseidon2::Poseidon2, poseidon2_instance_goldilocks::POSEIDON2_GOLDILOCKS_12_PARAMS,
+};
+
+use crate::{Challenge, Transcript, OUTPUT_WIDTH};
+
+/// Width of the permutation.
+pub const INPUT_WIDTH: usize = 12;
+/// Number of elements of the state that input is absorbed into and challenges
+/// are squeezed from; the other `INPUT_WIDTH - RATE` are the capacity.
+pub const RATE: usize = 8;
+
+static POSEIDON2: LazyLock<Poseidon2<Goldilocks>> =
+    LazyLock::new(|| Poseidon2::new(&POSEIDON2_GOLDILOCKS_12_PARAMS));
+
+#[derive(Copy, Clone, Debug)]
+pub struct PoseidonTranscript<F> {
+    state: [Goldilocks; INPUT_WIDTH],
+    /// Number of elements absorbed into the rate since the last permutation.
+    absorbed: usize,
+    phantom: PhantomData<F>,
+}
+
+impl<F: SmallField> Transcript<F> for PoseidonTranscript<F> {
+    fn new(label: &'static [u8]) -> Self {
+        let mut transcript = Self {
+            state: [Goldilocks::ZERO; INPUT_WIDTH],
+            absorbed: 0,
+            phantom: PhantomData,
+        };
+        transcript.absorb_bytes(label);
+        transcript
+    }
+
+    fn append_message(&mut self, msg: &[u8]) {
+        self.absorb_bytes(msg);
+    }
+
+    fn append_field_element(&mut self, element: F) {
+        element
+            .to_canonical_u64_vec()
+            .into_iter()
+            .for_each(|limb| self.absorb(Goldilocks::from(limb)));
+    }
+
+    // The output field element is uniform for Goldilocks; for a smaller
+    // field its statistical distance from uniform is at most |F| / p.
+    fn get_and_append_challenge(&mut self, label: &'static [u8]) -> Challenge<F> {
+        self.absorb_bytes(label);
+
+        // pad the pending input with a one followed by zeros
+        self.state[self.absorbed] += Goldilocks::ONE;
+        self.permute();
+
+        let mut challenge = Challenge {
+            elements: [F::ZERO; OUTPUT_WIDTH],
+        };
+        challenge
+            .elements
+            .iter_mut()
+            .zip(self.state.iter())
+            .for_each(|(element, state)| *element = F::from(state.to_canonical_u64()));
+        self.append_challenge(challenge);
+        challenge
+    }
+
+    fn pow_hash(&self, nonce: u64) -> u64 {
+        let mut transcript = *self;
+        transcript.absorb_bytes(&nonce.to_le_bytes());
+        transcript.state[transcript.absorbed] += Goldilocks::ONE;
+        transcript.permute();
+        transcript.state[0].to_canonical_u64()
+    }
+}
+
+impl<F: SmallField> PoseidonTranscript<F> {
+    /// Absorb a byte string, prefixed with its length so that consecutive
+    /// strings cannot be confused, in limbs of 7 bytes that fit in a field
+    /// element.
+    fn absorb_bytes(&mut self, bytes: &[u8]) {
+        self.absorb(Goldilocks::from(bytes.len() as u64));
+        bytes.chunks(7).for_each(|chunk| {
+            let mut limb = [0u8; 8];
+            limb[..chunk.len()].copy_from_slice(chunk);
+            self.absorb(Goldilocks::from(u64::from_le_bytes(limb)));
+        });
+    }
+
+    fn absorb(&mut self, element: Goldilocks) {
+        self.state[self.absorbed] += element;
+        self.absorbed += 1;
+        if self.absorbed == RATE {
+            self.permute();
+        }
+    }
+
+    fn permute(&mut self) {
+        let state = POSEIDON2.permutation(&self.state);
+        self.state.copy_from_slice(&state);
+        self.absorbed = 0;
+    }
+}
@@ -0,0 +1,96 @@
+//! Proof-of-work grinding: before a challenge is drawn, the prover finds a
+//! nonce such that the hash of the transcript and the nonce has `bits`
+//! leading zero bits, and appends the nonce. Every attempt of a cheating
+//! prover at a favourable challenge then costs `2^bits` hashes, which adds
+//! `bits` bits of security to the challenge.
+
+use std::fmt::{Display, Formatter};
+
+use goldilocks::SmallField;
+use rayon::prelude::{IntoParallelIterator, ParallelIterator};
+
+use crate::Transcript;
+
+/// Number of nonces each thread tries per round of `ProofOfWork::grind`.
+const GRIND_BLOCK_PER_THREAD: u64 = 1 << 10;
+
+/// The proof-of-work parameter of a protocol.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct ProofOfWork {
+    bits: u32,
+}
+
+/// An error raised when a proof-of-work nonce does not have the required
+/// work.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum PowError {
+    InsufficientWork { bits: u32, nonce: u64 },
+}
+
+impl Display for PowError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PowError::InsufficientWork { bits, nonce } => write!(
+                f,
+                "proof-of-work nonce {} does not have {} leading zero bits",
+                nonce, bits
+            ),
+        }
+    }
+}
+
+impl std::error::Error for PowError {}
+
+impl ProofOfWork {
+    pub fn new(bits: u32) -> Self {
+        assert!(bits <= 64, "cannot require more than 64 bits of work");
+        Self { bits }
+    }
+
+    /// Number of leading zero bits required of the hash, i.e. the bits of
+    /// security that grinding adds.
+    pub fn bits(&self) -> u32 {
+        self.bits
+    }
+
+    /// Search for a nonce with the required work, in parallel, and append it
+    /// to the transcript. The smallest such nonce is returned, so that
+    /// proofs are reproducible.
+    pub fn grind<F: SmallField, T: Transcript<F> + Sync>(&self, transcript: &mut T) -> u64 {
+        // `find_first` over all of u64 splits the range in halves, and the
+        // threads working on the upper half never find the first nonce.
+        // Search consecutive blocks that keep every thread busy instead.
+        let block = rayon::current_num_threads() as u64 * GRIND_BLOCK_PER_THREAD;
+        let nonce = (0..=u64::MAX / block)
+            .find_map(|k| {
+                let start = k * block;
+                (start..start.saturating_add(block))
+                    .into_par_iter()
+                    .find_first(|nonce| self.has_work(&*transcript, *nonce))
+            })
+            .expect("no proof-of-work nonce found");
+        transcript.append_serializable_element(b"pow nonce", &nonce);
+        nonce
+    }
+
+    /// Check that the prover's nonce has the required work, and append it to
+    /// the transcript.
+    pub fn verify<F: SmallField, T: Transcript<F>>(
+        &self,
+        transcript: &mut T,
+        nonce: u64,
+    ) -> Result<(), PowError> {
+        if !self.has_work(&*transcript, nonce) {
+            return Err(PowError::InsufficientWork {
+                bits: self.bits,
+                nonce,
+            });
+        }
+        transcript.append_serializable_element(b"pow nonce", &nonce);
+        Ok(())
+    }
+
+    fn has_work<F: SmallField, T: Transcript<F>>(&self, transcript: &T, nonce: u64) -> bool {
+        transcript.pow_hash(nonce).leading_zeros() >= self.bits
+    }
+}
@@ -0,0 +1,191 @@
+//! A transcript wrapper that records every operation, to find where the
+//! transcripts of a prover and a verifier diverge.
+//!
+//! Field elements are recorded as their canonical `u64` limbs and byte
+//! strings in hex, so that logs dumped as JSON are readable and can be
+//! compared across runs.
+
+use std::{
+    fmt::{Display, Formatter, Write},
+    marker::PhantomData,
+};
+
+use goldilocks::SmallField;
+use serde::{Deserialize, Serialize};
+
+use crate::{Challenge, Transcript};
+
+/// An operation on a transcript, with its label and data.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "op", rename_all = "snake_case")]
+pub enum TranscriptOp {
+    New {
+        label: String,
+    },
+    AppendMessage {
+        data: String,
+    },
+    AppendFieldElement {
+        element: Vec<u64>,
+    },
+    AppendChallenge {
+        elements: Vec<Vec<u64>>,
+    },
+    AppendSerializableElement {
+        label: String,
+        data: String,
+    },
+    GetAndAppendChallenge {
+        label: String,
+        challenge: Vec<Vec<u64>>,
+    },
+}
+
+/// The operations on a transcript, in order.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TranscriptLog {
+    pub operations: Vec<TranscriptOp>,
+}
+
+impl TranscriptLog {
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).expect("failed to serialize transcript log")
+    }
+
+    pub fn from_json(json: &str) -> Result<Self, serde_json::Error> {
+        serde_json::from_str(json)
+    }
+}
+
+/// The first operation at which two logs differ. An operation is `None` when
+/// its log has ended.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TranscriptMismatch {
+    pub index: usize,
+    pub left: Option<TranscriptOp>,
+    pub right: Option<TranscriptOp>,
+}
+
+impl Display for TranscriptMismatch {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "transcripts diverge at operation {}: {:?} vs {:?}",
+            self.index, self.left, self.right
+        )
+    }
+}
+
+/// Find the first operation at which two logs differ, if any.
+pub fn diff(left: &TranscriptLog, right: &TranscriptLog) -> Option<TranscriptMismatch> {
+    let len = left.operations.len().max(right.operations.len());
+    (0..len)
+        .map(|index| TranscriptMismatch {
+            index,
+            left: left.operations.get(index).cloned(),
+            right: right.operations.get(index).cloned(),
+        })
+        .find(|mismatch| mismatch.left != mismatch.right)
+}
+
+/// Wraps a transcript and records every operation on it.
+#[derive(Clone, Debug)]
+pub struct RecordingTranscript<F, T> {
+    inner: T,
+    log: TranscriptLog,
+    phantom: PhantomData<F>,
+}
+
+impl<F: SmallField, T: Transcript<F>> RecordingTranscript<F, T> {
+    pub fn log(&self) -> &TranscriptLog {
+        &self.log
+    }
+
+    pub fn inner(&self) -> &T {
+        &self.inner
+    }
+
+    pub fn into_parts(self) -> (T, TranscriptLog) {
+        (self.inner, self.log)
+    }
+}
+
+impl<F: SmallField, T: Transcript<F>> Transcript<F> for RecordingTranscript<F, T> {
+    fn new(label: &'static [u8]) -> Self {
+        Self {
+            inner: T::new(label),
+            log: TranscriptLog {
+                operations: vec![TranscriptOp::New {
+                    label: label_string(label),
+                }],
+            },
+            phantom: PhantomData,
+        }
+    }
+
+    fn append_message(&mut self, msg: &[u8]) {
+        self.log.operations.push(TranscriptOp::AppendMessage {
+            data: hex_string(msg),
+        });
+        self.inner.append_message(msg);
+    }
+
+    fn append_field_element(&mut self, element: F) {
+        self.log.operations.push(TranscriptOp::AppendFieldElement {
+            element: element.to_canonical_u64_vec(),
+        });
+        self.inner.append_field_element(element);
+    }
+
+    fn append_challenge(&mut self, challenge: Challenge<F>) {
+        self.log.operations.push(TranscriptOp::AppendChallenge {
+            elements: challenge_limbs(&challenge),
+        });
+        self.inner.append_challenge(challenge);
+    }
+
+    fn append_serializable_element<S: Serialize>(&mut self, label: &'static [u8], element: &S) {
+        let bytes = bincode::serialize(element).expect("failed to serialize element");
+        self.log
+            .operations
+            .push(TranscriptOp::AppendSerializableElement {
+                label: label_string(label),
+                data: hex_string(&bytes),
+            });
+        self.inner.append_serializable_element(label, element);
+    }
+
+    fn get_and_append_challenge(&mut self, label: &'static [u8]) -> Challenge<F> {
+        let challenge = self.inner.get_and_append_challenge(label);
+        self.log
+            .operations
+            .push(TranscriptOp::GetAndAppendChallenge {
+                label: label_string(label),
+                challenge: challenge_limbs(&challenge),
+            });
+        challenge
+    }
+
+    fn pow_hash(&self, nonce: u64) -> u64 {
+        self.inner.pow_hash(nonce)
+    }
+}
+
+fn label_string(label: &[u8]) -> String {
+    String::from_utf8_lossy(label).into_owned()
+}
+
+fn hex_string(bytes: &[u8]) -> String {
+    bytes.iter().fold(String::new(), |mut hex, byte| {
+        write!(hex, "{:02x}", byte).unwrap();
+        hex
+    })
+}
+
+fn challenge_limbs<F: SmallField>(challenge: &Challenge<F>) -> Vec<Vec<u64>> {
+    challenge
+        .elements
+        .iter()
+        .map(|element| element.to_canonical_u64_vec())
+        .collect()
+}
@@ -0,0 +1,309 @@
+use babybear::BabyBear;
+use ff::Field;
+use goldilocks::{conformance::modulus, Goldilocks as F, SmallField};
+use mersenne31::Mersenne31;
+
+use crate::{
+    diff, Blake3Transcript, KeccakTranscript, PoseidonTranscript, PowError, ProofOfWork,
+    RecordingTranscript, Sha256Transcript, Transcript, TranscriptLog, TranscriptOp,
+};
+
+fn challenge_u64s(transcript: &mut impl Transcript<F>) -> Vec<u64> {
+    transcript
+        .get_and_append_challenge(b"challenge")
+        .elements
+        .iter()
+        .map(|element| element.to_canonical_u64())
+        .collect()
+}
+
+/// The challenges of a fixed script of appends.
+fn known_answers<T: Transcript<F>>() -> [Vec<u64>; 2] {
+    let mut transcript = T::new(b"test");
+    transcript.append_field_element(F::ONE);
+    transcript.append_message(b"message");
+    let first = challenge_u64s(&mut transcript);
+    transcript.append_serializable_element(b"vec", &vec![F::from(2), F::from(3)]);
+    [first, challenge_u64s(&mut transcript)]
+}
+
+#[test]
+fn test_known_answers() {
+    assert_eq!(
+        known_answers::<PoseidonTranscript<F>>(),
+        [
+            [
+                17756721598440224130,
+                414413712614286532,
+                16321506278898846890,
+                16499647955340732329
+            ],
+            [
+                17584356180555209173,
+                1206098572881116767,
+                12443913940043922373,
+                16157562388795738885
+            ]
+        ]
+    );
+    assert_eq!(
+        known_answers::<KeccakTranscript<F>>(),
+        [
+            [
+                7789945135055947209,
+                17818789064877935852,
+                10842343343728928526,
+                7721386251298148471
+            ],
+            [
+                6979163003515858725,
+                2668419025716367113,
+                8375019689694038797,
+                9272916873848911238
+            ]
+        ]
+    );
+    assert_eq!(
+        known_answers::<Sha256Transcript<F>>(),
+        [
+            [
+                7137782951279822220,
+                4142544325893611805,
+                17959351047501737181,
+                12610633846697346414
+            ],
+            [
+                361547439270559692,
+                14260882792270650589,
+                17930206273044339001,
+                13456524761452985115
+            ]
+        ]
+    );
+    assert_eq!(
+        known_answers::<Blake3Transcript<F>>(),
+        [
+            [
+                16966244894572197446,
+                1267076208614093270,
+                5815596661384594593,
+                12120246932588528320
+            ],
+            [
+                574616628695130117,
+                1916623324297762435,
+                8048395291901409073,
+                14595468342592708547
+            ]
+        ]
+    );
+}
+
+fn challenge_binds_transcript<T: Transcript<F>>() {
+    let transcript = |label: &'static [u8], messages: &[&[u8]], element: F| {
+        let mut transcript = T::new(label);
+        messages
+            .iter()
+            .for_each(|message| transcript.append_message(message));
+        transcript.append_field_element(element);
+        challenge_u64s(&mut transcript)
+    };
+
+    let base = transcript(b"test", &[b"ab", b"c"], F::ONE);
+    assert_eq!(base, transcript(b"test", &[b"ab", b"c"], F::ONE));
+    assert_ne!(base, transcript(b"tesT", &[b"ab", b"c"], F::ONE));
+    assert_ne!(base, transcript(b"test", &[b"a", b"bc"], F::ONE));
+    assert_ne!(base, transcript(b"test", &[b"ab", b"c", b""], F::ONE));
+    assert_ne!(base, transcript(b"test", &[b"ab", b"c"], F::ZERO));
+
+    // equal elements are absorbed alike whatever their internal form
+    let modulus = 0xffffffff00000001u64;
+    let serialized = |element: F| {
+        let mut transcript = T::new(b"test");
+        transcript.append_field_element(element);
+        transcript.append_serializable_element(b"element", &element);
+        challenge_u64s(&mut transcript)
+    };
+    assert_eq!(serialized(F::from(5)), serialized(F::from(modulus + 5)));
+
+    // successive challenges differ, and depend on the challenge label
+    let mut transcript = T::new(b"test");
+    let first = transcript.get_and_append_challenge(b"first");
+    assert_ne!(first, transcript.get_and_append_challenge(b"first"));
+    let mut other = T::new(b"test");
+    assert_ne!(first, other.get_and_append_challenge(b"other"));
+}
+
+#[test]
+fn test_challenge_binds_transcript() {
+    challenge_binds_transcript::<PoseidonTranscript<F>>();
+    challenge_binds_transcript::<KeccakTranscript<F>>();
+    challenge_binds_transcript::<Sha256Transcript<F>>();
+    challenge_binds_transcript::<Blake3Transcript<F>>();
+}
+
+fn challenges_look_uniform<T: Transcript<F>>() {
+    let mut transcript = T::new(b"test");
+    let samples: Vec<u64> = (0..1000)
+        .flat_map(|_| challenge_u64s(&mut transcript))
+        .collect();
+
+    // every bit of the challenges is set about half of the time
+    for bit in 0..64 {
+        let ones = samples
+            .iter()
+            .filter(|sample| (*sample >> bit) & 1 == 1)
+            .count();
+        assert!(
+            (1800..2200).contains(&ones),
+            "bit {} is set in {} of {} samples",
+            bit,
+            ones,
+            samples.len()
+        );
+    }
+}
+
+#[test]
+fn test_challenges_look_uniform() {
+    challenges_look_uniform::<PoseidonTranscript<F>>();
+    challenges_look_uniform::<KeccakTranscript<F>>();
+    challenges_look_uniform::<Sha256Transcript<F>>();
+    challenges_look_uniform::<Blake3Transcript<F>>();
+}
+
+#[test]
+fn test_recording_transcript() {
+    type T = RecordingTranscript<F, PoseidonTranscript<F>>;
+
+    // recording does not change the challenges
+    assert_eq!(
+        known_answers::<T>(),
+        known_answers::<PoseidonTranscript<F>>()
+    );
+
+    let run = |message: &[u8]| {
+        let mut transcript = T::new(b"test");
+        transcript.append_message(message);
+        transcript.append_field_element(F::from(7));
+        transcript.get_and_append_challenge(b"r");
+        transcript.append_serializable_element(b"x", &F::from(8));
+        transcript.get_and_append_challenge(b"s");
+        transcript.into_parts().1
+    };
+    let log = run(b"ab");
+    assert_eq!(log.operations.len(), 6);
+    assert_eq!(
+        log.operations[1],
+        TranscriptOp::AppendMessage {
+            data: "6162".to_string()
+        }
+    );
+    assert_eq!(
+        log.operations[2],
+        TranscriptOp::AppendFieldElement { element: vec![7] }
+    );
+    assert_eq!(TranscriptLog::from_json(&log.to_json()).unwrap(), log);
+    assert_eq!(diff(&log, &run(b"ab")), None);
+
+    // a different message is reported where it is appended, even though
+    // the challenges after it differ too
+    let mismatch = diff(&log, &run(b"ac")).unwrap();
+    assert_eq!(mismatch.index, 1);
+    assert_eq!(mismatch.left, Some(log.operations[1].clone()));
+
+    // a log that stops early mismatches where it ends
+    let mut short = log.clone();
+    short.operations.truncate(4);
+    let mismatch = diff(&log, &short).unwrap();
+    assert_eq!((mismatch.index, mismatch.right), (4, None));
+}
+
+fn grinding<F: SmallField, T: Transcript<F> + Sync>() {
+    for bits in [0, 4, 10] {
+        let pow = ProofOfWork::new(bits);
+        let mut prover = T::new(b"test");
+        prover.append_message(b"message");
+        let mut verifier = T::new(b"test");
+        verifier.append_message(b"message");
+
+        // the smallest nonce with enough work is found
+        let nonce = pow.grind(&mut prover);
+        assert!(verifier.pow_hash(nonce).leading_zeros() >= bits);
+        assert!((0..nonce).all(|nonce| verifier.pow_hash(nonce).leading_zeros() < bits));
+
+        if bits > 0 {
+            let bad_nonce = (0..)
+                .find(|nonce| verifier.pow_hash(*nonce).leading_zeros() < bits)
+                .unwrap();
+            assert_eq!(
+                pow.verify(&mut verifier, bad_nonce),
+                Err(PowError::InsufficientWork {
+                    bits,
+                    nonce: bad_nonce
+                })
+            );
+        }
+        pow.verify(&mut verifier, nonce).unwrap();
+        assert_eq!(
+            prover.get_and_append_challenge(b"challenge"),
+            verifier.get_and_append_challenge(b"challenge")
+        );
+    }
+}
+
+#[test]
+fn test_grinding() {
+    assert_eq!(ProofOfWork::new(10).bits(), 10);
+    grinding::<F, PoseidonTranscript<F>>();
+    grinding::<F, KeccakTranscript<F>>();
+    grinding::<F, Sha256Transcript<F>>();
+    grinding::<F, Blake3Transcript<F>>();
+    grinding::<F, RecordingTranscript<F, PoseidonTranscript<F>>>();
+}
+
+/// Challenges over a field other than Goldilocks are canonical, spread over
+/// the field and bind the transcript.
+fn transcript_over_field<F: SmallField, T: Transcript<F> + Sync>() {
+    let modulus = modulus::<F>() as u64;
+    let challenge = |element: F, message: &[u8]| {
+        let mut transcript = T::new(b"test");
+        transcript.append_message(message);
+        transcript.append_field_element(element);
+        transcript.append_serializable_element(b"element", &element);
+        transcript.get_and_append_challenge(b"challenge")
+    };
+    let base = challenge(F::from(5), b"message");
+    assert_eq!(base, challenge(F::from(modulus + 5), b"message"));
+    assert_ne!(base, challenge(F::from(6), b"message"));
+    assert_ne!(base, challenge(F::from(5), b"messagE"));
+
+    let mut transcript = T::new(b"test");
+    let samples: Vec<u64> = (0..1000)
+        .flat_map(|_| transcript.get_and_append_challenge(b"challenge").elements)
+        .map(|element| element.to_canonical_u64_vec()[0])
+        .collect();
+    assert!(samples.iter().all(|sample| *sample < modulus));
+    let high = samples
+        .iter()
+        .filter(|sample| **sample >= modulus / 2)
+        .count();
+    assert!(
+        (1800..2200).contains(&high),
+        "{} of {} samples are in the upper half of the field",
+        high,
+        samples.len()
+    );
+
+    grinding::<F, T>();
+}
+
+#[test]
+fn test_transcripts_over_fields() {
+    transcript_over_field::<BabyBear, PoseidonTranscript<BabyBear>>();
+    transcript_over_field::<BabyBear, KeccakTranscript<BabyBear>>();
+    transcript_over_field::<BabyBear, Blake3Transcript<BabyBear>>();
+    transcript_over_field::<Mersenne31, PoseidonTranscript<Mersenne31>>();
+    transcript_over_field::<Mersenne31, Sha256Transcript<Mersenne31>>();
+    transcript_over_field::<Mersenne31, Blake3Transcript<Mersenne31>>();
+}